@@ -0,0 +1,29 @@
+use chrono::{DateTime, Utc};
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+struct Plain(#[serde(with = "humantime_serde::vec")] Vec<DateTime<Utc>>);
+
+#[derive(Deserialize)]
+struct Memoized(#[serde(deserialize_with = "humantime_serde::memo::deserialize")] Vec<DateTime<Utc>>);
+
+fn bucketed(c: &mut Criterion) {
+    // 1000 samples in buckets of 50 sharing a timestamp.
+    let times: Vec<String> = (0..1000)
+        .map(|i| format!("\"2018-05-11T18:{:02}:00Z\"", i / 50))
+        .collect();
+    let json = format!("[{}]", times.join(","));
+
+    let mut group = c.benchmark_group("bucketed_1000");
+    group.bench_function("vec", |b| {
+        b.iter(|| serde_json::from_str::<Plain>(black_box(&json)).unwrap())
+    });
+    group.bench_function("memo", |b| {
+        b.iter(|| serde_json::from_str::<Memoized>(black_box(&json)).unwrap())
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bucketed);
+criterion_main!(benches);
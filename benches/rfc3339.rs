@@ -0,0 +1,30 @@
+use chrono::{DateTime, FixedOffset, SecondsFormat, Utc};
+use core::time::Duration;
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use humantime_serde::Serde;
+
+fn timestamps(c: &mut Criterion) {
+    let utc = DateTime::<Utc>::UNIX_EPOCH + Duration::new(1526063310, 0);
+    let offset = utc.with_timezone(&FixedOffset::east_opt(2 * 3600).unwrap());
+    let many = vec![Serde::from(utc); 1000];
+
+    let mut group = c.benchmark_group("rfc3339");
+    group.bench_function("chrono", |b| {
+        b.iter(|| black_box(&utc).to_rfc3339_opts(SecondsFormat::Secs, true))
+    });
+    group.bench_function("format", |b| b.iter(|| humantime_serde::format(black_box(&utc))));
+    group.bench_function("format_offset", |b| {
+        b.iter(|| humantime_serde::format(black_box(&offset)))
+    });
+    group.bench_function("json_1000", |b| {
+        let mut out = Vec::with_capacity(32 * 1000);
+        b.iter(|| {
+            out.clear();
+            serde_json::to_writer(&mut out, black_box(&many)).unwrap();
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, timestamps);
+criterion_main!(benches);
@@ -0,0 +1,295 @@
+//! Procedural macros for the `humantime-serde` crate.
+//!
+//! Use them through the `derive` feature of `humantime-serde` rather than
+//! depending on this crate directly.
+
+extern crate proc_macro;
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{
+    parse_macro_input, parse_quote, Attribute, Data, DeriveInput, Fields,
+    GenericArgument, GenericParam, PathArguments, PathSegment, Type,
+};
+
+/// Implements `Serialize` and `Deserialize` for a struct, routing every
+/// `Duration`, `DateTime<Tz>`, `Option` and `Vec` of those through
+/// `humantime_serde`.
+///
+/// Fields are recognized by their type path: `Duration`,
+/// `core::time::Duration` or `std::time::Duration`, and `DateTime<Tz>` or
+/// `chrono::DateTime<Tz>`. Other types named `Duration`, such as
+/// `chrono::Duration`, are left alone. So are fields marked
+/// `#[humantime_serde(skip)]`, for a bare `Duration` that is not the
+/// standard one.
+///
+/// All other `#[serde(...)]` attributes are honored as with serde's own
+/// derives, which must not be derived as well. Fields that already carry a
+/// `with`, `serialize_with` or `deserialize_with` attribute are left alone.
+#[proc_macro_derive(HumantimeSerde, attributes(serde, humantime_serde))]
+pub fn derive_humantime_serde(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn expand(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    &input.ident,
+                    "HumantimeSerde only supports structs with named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &input.ident,
+                "HumantimeSerde only supports structs with named fields",
+            ))
+        }
+    };
+    if let Some(param) = input
+        .generics
+        .params
+        .iter()
+        .find(|param| !matches!(param, GenericParam::Lifetime(_)))
+    {
+        return Err(syn::Error::new_spanned(
+            param,
+            "HumantimeSerde does not support generic type or const parameters",
+        ));
+    }
+
+    let ident = &input.ident;
+    let shadow = format_ident!("__HumantimeSerde{}", ident);
+    let generics = &input.generics;
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let remote = quote!(#ident #ty_generics).to_string();
+    let container_attrs = serde_attrs(&input.attrs);
+
+    let fields = fields
+        .iter()
+        .map(|field| {
+            let name = &field.ident;
+            let ty = &field.ty;
+            let attrs = serde_attrs(&field.attrs);
+            let keys = serde_keys(&field.attrs);
+            let has_with = keys.iter().any(|key| key.ends_with("with"));
+            let extra = if has_with || is_skipped(&field.attrs)? {
+                quote!()
+            } else {
+                match route(ty) {
+                    Some(route) => with_attr(route, &keys),
+                    None => quote!(),
+                }
+            };
+            Ok(quote!(#(#attrs)* #extra #name: #ty))
+        })
+        .collect::<syn::Result<Vec<_>>>()?;
+
+    let mut de_generics = generics.clone();
+    de_generics.params.insert(0, parse_quote!('de));
+    let (de_impl_generics, _, _) = de_generics.split_for_impl();
+
+    Ok(quote! {
+        const _: () = {
+            #[allow(dead_code, non_camel_case_types)]
+            #[derive(::serde::Serialize, ::serde::Deserialize)]
+            #[serde(remote = #remote)]
+            #(#container_attrs)*
+            struct #shadow #generics #where_clause {
+                #(#fields,)*
+            }
+
+            impl #impl_generics ::serde::Serialize for #ident #ty_generics #where_clause {
+                fn serialize<__S>(
+                    &self,
+                    serializer: __S,
+                ) -> ::core::result::Result<__S::Ok, __S::Error>
+                where
+                    __S: ::serde::Serializer,
+                {
+                    #shadow::serialize(self, serializer)
+                }
+            }
+
+            impl #de_impl_generics ::serde::Deserialize<'de> for #ident #ty_generics #where_clause {
+                fn deserialize<__D>(
+                    deserializer: __D,
+                ) -> ::core::result::Result<Self, __D::Error>
+                where
+                    __D: ::serde::Deserializer<'de>,
+                {
+                    #shadow::deserialize(deserializer)
+                }
+            }
+        };
+    })
+}
+
+/// Adds the right `#[serde(with = "...")]` attribute to each field marked
+/// with `#[humantime]`, along with `default` for `Option` fields.
+///
+/// It must be placed before `#[derive(Serialize, Deserialize)]`. Marked
+/// fields must be a `Duration`, a `DateTime<Tz>`, or an `Option` or `Vec` of
+/// those, written as `#[derive(HumantimeSerde)]` recognizes them.
+#[proc_macro_attribute]
+pub fn humantime(args: TokenStream, input: TokenStream) -> TokenStream {
+    let args = TokenStream2::from(args);
+    if !args.is_empty() {
+        return syn::Error::new_spanned(args, "#[humantime] takes no arguments")
+            .into_compile_error()
+            .into();
+    }
+    let mut input = parse_macro_input!(input as DeriveInput);
+    match mark_fields(&mut input) {
+        Ok(()) => quote!(#input).into(),
+        Err(err) => err.into_compile_error().into(),
+    }
+}
+
+fn mark_fields(input: &mut DeriveInput) -> syn::Result<()> {
+    let fields = match &mut input.data {
+        Data::Struct(data) => &mut data.fields,
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &input.ident,
+                "#[humantime] only supports structs",
+            ))
+        }
+    };
+    for field in fields.iter_mut() {
+        let len = field.attrs.len();
+        field.attrs.retain(|attr| !attr.path().is_ident("humantime"));
+        if field.attrs.len() == len {
+            continue;
+        }
+        let route = route(&field.ty).ok_or_else(|| {
+            syn::Error::new_spanned(
+                &field.ty,
+                "#[humantime] fields must be a Duration, a DateTime, or an Option or Vec of those",
+            )
+        })?;
+        let keys = serde_keys(&field.attrs);
+        let attr = with_attr(route, &keys);
+        field.attrs.push(parse_quote!(#attr));
+    }
+    Ok(())
+}
+
+fn with_attr(route: Route, keys: &[String]) -> TokenStream2 {
+    match route {
+        Route::Plain => quote!(#[serde(with = "::humantime_serde")]),
+        Route::Option if keys.iter().any(|key| key == "default") => {
+            quote!(#[serde(with = "::humantime_serde::option")])
+        }
+        Route::Option => quote!(#[serde(with = "::humantime_serde::option", default)]),
+        Route::Vec => quote!(#[serde(with = "::humantime_serde::vec")]),
+    }
+}
+
+enum Route {
+    Plain,
+    Option,
+    Vec,
+}
+
+fn route(ty: &Type) -> Option<Route> {
+    if is_supported(ty) {
+        return Some(Route::Plain);
+    }
+    let segment = last_segment(ty)?;
+    let inner = single_argument(segment)?;
+    if !is_supported(inner) {
+        None
+    } else if segment.ident == "Option" {
+        Some(Route::Option)
+    } else if segment.ident == "Vec" {
+        Some(Route::Vec)
+    } else {
+        None
+    }
+}
+
+fn is_supported(ty: &Type) -> bool {
+    let path = match ty {
+        Type::Path(path) if path.qself.is_none() => &path.path,
+        _ => return false,
+    };
+    let names: Vec<String> =
+        path.segments.iter().map(|s| s.ident.to_string()).collect();
+    let names: Vec<&str> = names.iter().map(String::as_str).collect();
+    matches!(
+        names.as_slice(),
+        ["Duration"]
+            | ["core" | "std", "time", "Duration"]
+            | ["DateTime"]
+            | ["chrono", "DateTime"]
+    )
+}
+
+/// Whether the field is marked `#[humantime_serde(skip)]`.
+fn is_skipped(attrs: &[Attribute]) -> syn::Result<bool> {
+    let mut skip = false;
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("humantime_serde")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("skip") {
+                skip = true;
+                Ok(())
+            } else {
+                Err(meta.error("expected `skip`"))
+            }
+        })?;
+    }
+    Ok(skip)
+}
+
+fn last_segment(ty: &Type) -> Option<&PathSegment> {
+    match ty {
+        Type::Path(path) if path.qself.is_none() => path.path.segments.last(),
+        _ => None,
+    }
+}
+
+fn single_argument(segment: &PathSegment) -> Option<&Type> {
+    match &segment.arguments {
+        PathArguments::AngleBracketed(args) if args.args.len() == 1 => {
+            match args.args.first() {
+                Some(GenericArgument::Type(ty)) => Some(ty),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+fn serde_attrs(attrs: &[Attribute]) -> impl Iterator<Item = &Attribute> {
+    attrs.iter().filter(|attr| attr.path().is_ident("serde"))
+}
+
+/// Returns the keys of all `#[serde(...)]` attributes, e.g. `default` and
+/// `with` for `#[serde(default, with = "...")]`.
+fn serde_keys(attrs: &[Attribute]) -> Vec<String> {
+    let mut keys = Vec::new();
+    for attr in serde_attrs(attrs) {
+        let _ = attr.parse_nested_meta(|meta| {
+            if let Some(ident) = meta.path.get_ident() {
+                keys.push(ident.to_string());
+            }
+            if meta.input.peek(syn::Token![=]) {
+                let _: syn::Expr = meta.value()?.parse()?;
+            } else if meta.input.peek(syn::token::Paren) {
+                let content;
+                syn::parenthesized!(content in meta.input);
+                let _: TokenStream2 = content.parse()?;
+            }
+            Ok(())
+        });
+    }
+    keys
+}
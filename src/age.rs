@@ -0,0 +1,180 @@
+use core::fmt;
+use core::str::FromStr;
+use core::time::Duration;
+
+use chrono::{DateTime, TimeDelta, Utc};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use super::format::{HumantimeFormat, Ref};
+use super::{Error, Serde};
+
+/// When something came into being, written as an RFC 3339 timestamp or as
+/// a duration before now, such as `"3 days ago"`.
+///
+/// A relative age is resolved against the system clock when it is read,
+/// which needs the `std` feature, and is always written back as the
+/// timestamp, so the value does not drift. [`Age::parse_at`] resolves it
+/// against a given time instead.
+///
+/// # Example
+///
+/// ```
+/// use core::time::Duration;
+/// use chrono::{DateTime, Utc};
+/// use serde::{Serialize, Deserialize};
+/// use humantime_serde::Age;
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct Entry {
+///     created: Age,
+/// }
+///
+/// let entry: Entry = serde_json::from_str(r#"{"created": "2018-05-11T18:28:30Z"}"#).unwrap();
+/// let now: DateTime<Utc> = "2018-05-12T18:28:30Z".parse().unwrap();
+/// assert_eq!(entry.created.elapsed(now), Duration::from_secs(86_400));
+///
+/// let age = Age::parse_at("3 days ago", now).unwrap();
+/// assert_eq!(age.to_string(), "2018-05-09T18:28:30Z");
+/// ```
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq, Ord, PartialOrd)]
+pub struct Age(DateTime<Utc>);
+
+impl Age {
+    /// Creates an age starting at `t`.
+    pub const fn new(t: DateTime<Utc>) -> Age {
+        Age(t)
+    }
+
+    /// The point in time the age starts at.
+    pub fn timestamp(&self) -> DateTime<Utc> {
+        self.0
+    }
+
+    /// The time elapsed between the start and `now`, or zero if the age
+    /// starts after `now`.
+    pub fn elapsed(&self, now: DateTime<Utc>) -> Duration {
+        now.signed_duration_since(self.0).to_std().unwrap_or(Duration::ZERO)
+    }
+
+    /// Parses an age, taking a duration `ago` to be before `now`.
+    pub fn parse_at(s: &str, now: DateTime<Utc>) -> Result<Age, Error> {
+        parse(s, || Some(now))
+    }
+}
+
+fn parse<F>(s: &str, now: F) -> Result<Age, Error>
+where
+    F: FnOnce() -> Option<DateTime<Utc>>,
+{
+    let ago = match s.trim().strip_suffix(" ago") {
+        Some(ago) => ago,
+        None => return crate::parse::<DateTime<Utc>>(s).map(Age),
+    };
+    let now = now().ok_or(Error::Invalid("a timestamp, as there is no clock"))?;
+    let ago = crate::parse::<Duration>(ago.trim())?;
+    let ago = TimeDelta::from_std(ago).map_err(|_| Error::NumberOverflow)?;
+    now.checked_sub_signed(ago).map(Age).ok_or(Error::NumberOverflow)
+}
+
+impl HumantimeFormat for Age {
+    const EXPECTING: &'static str = "a timestamp or a duration ago";
+
+    type Err = Error;
+
+    fn parse(s: &str) -> Result<Age, Error> {
+        #[cfg(feature = "std")]
+        let now = || Some(<crate::clock::SystemClock as crate::clock::Clock>::now());
+        #[cfg(not(feature = "std"))]
+        let now = || None;
+        parse(s, now)
+    }
+
+    fn format(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        HumantimeFormat::format(&self.0, f)
+    }
+
+    fn is_overflow(err: &Error) -> bool {
+        *err == Error::NumberOverflow
+    }
+}
+
+impl fmt::Display for Age {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.format(f)
+    }
+}
+
+impl FromStr for Age {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Age, Error> {
+        Age::parse(s)
+    }
+}
+
+impl From<DateTime<Utc>> for Age {
+    fn from(t: DateTime<Utc>) -> Age {
+        Age(t)
+    }
+}
+
+impl From<Age> for DateTime<Utc> {
+    fn from(age: Age) -> DateTime<Utc> {
+        age.0
+    }
+}
+
+impl Serialize for Age {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        Ref(self).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Age {
+    fn deserialize<D>(d: D) -> Result<Age, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Serde::deserialize(d).map(Serde::into_inner)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloc::string::ToString;
+
+    #[test]
+    fn parse() {
+        let now = DateTime::<Utc>::UNIX_EPOCH + Duration::from_secs(86_400);
+        let age = Age::parse_at("1h 30m ago", now).unwrap();
+        assert_eq!(age.elapsed(now), Duration::from_secs(5400));
+        assert_eq!(age.to_string(), "1970-01-01T22:30:00Z");
+        assert_eq!(Age::parse_at(" 1 day ago ", now).unwrap().timestamp(), DateTime::<Utc>::UNIX_EPOCH);
+        assert_eq!(Age::parse_at("1970-01-02T00:00:00+01:00", now).unwrap().elapsed(now), Duration::from_secs(3600));
+        assert_eq!(Age::parse_at("1970-01-03T00:00:00Z", now).unwrap().elapsed(now), Duration::ZERO);
+        assert_eq!(Age::parse_at("1000000y ago", now), Err(Error::NumberOverflow));
+        assert!(Age::parse_at("ago", now).is_err());
+        assert!(Age::parse_at("1h", now).is_err());
+    }
+
+    #[test]
+    fn serde() {
+        let age: Age = serde_json::from_str("\"2018-05-11T20:28:30+02:00\"").unwrap();
+        assert_eq!(serde_json::to_string(&age).unwrap(), "\"2018-05-11T18:28:30Z\"");
+        let err = serde_json::from_str::<Age>("\"yesterday\"").unwrap_err();
+        assert!(err.to_string().contains("expected a timestamp or a duration ago"));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn system_clock() {
+        let before = <crate::clock::SystemClock as crate::clock::Clock>::now();
+        let age: Age = serde_json::from_str("\"1h ago\"").unwrap();
+        assert!(age.elapsed(before) < Duration::from_secs(3600));
+        assert!(age.elapsed(before + Duration::from_secs(3600)) >= Duration::from_secs(3600));
+    }
+}
@@ -0,0 +1,128 @@
+use chrono::{DateTime, LocalResult, NaiveDateTime, TimeZone};
+
+use super::Error;
+
+/// Which instant a local time names when a change of offset makes it
+/// ambiguous, as when clocks go back for the end of daylight saving time.
+///
+/// Local times skipped by a change of offset, as when clocks go forward,
+/// name no instant and are rejected whatever the policy.
+///
+/// # Example
+///
+/// ```
+/// use chrono::{FixedOffset, NaiveDate};
+/// use humantime_serde::Ambiguity;
+///
+/// let tz = FixedOffset::east_opt(3600).unwrap();
+/// let t = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap().and_hms_opt(9, 0, 0).unwrap();
+/// let t = Ambiguity::Reject.resolve(&tz, &t).unwrap();
+/// assert_eq!(t.to_rfc3339(), "2024-01-01T09:00:00+01:00");
+/// ```
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub enum Ambiguity {
+    /// The earlier of the two instants, as [`TimeOfDay::on`] picks.
+    ///
+    /// [`TimeOfDay::on`]: crate::TimeOfDay::on
+    Earliest,
+    /// The later of the two instants.
+    Latest,
+    /// Ambiguous local times are rejected.
+    Reject,
+}
+
+impl Ambiguity {
+    /// The instant at local time `t` in `tz`.
+    pub fn resolve<Tz>(self, tz: &Tz, t: &NaiveDateTime) -> Result<DateTime<Tz>, Error>
+    where
+        Tz: TimeZone,
+    {
+        match tz.from_local_datetime(t) {
+            LocalResult::Single(t) => Ok(t),
+            LocalResult::Ambiguous(earliest, latest) => match self {
+                Ambiguity::Earliest => Ok(earliest),
+                Ambiguity::Latest => Ok(latest),
+                Ambiguity::Reject => Err(Error::AmbiguousTime),
+            },
+            LocalResult::None => Err(Error::NonexistentTime),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use chrono::{FixedOffset, NaiveDate, NaiveTime};
+
+    // A zone whose offset goes from +02:00 back to +01:00 at 03:00 local
+    // time on 2024-10-27, and forward again at 02:00 on 2025-03-30.
+    #[derive(Clone, Copy, Debug)]
+    struct Dst;
+
+    impl Dst {
+        fn winter(t: &NaiveDateTime) -> bool {
+            let end = NaiveDate::from_ymd_opt(2024, 10, 27).unwrap().and_hms_opt(1, 0, 0).unwrap();
+            let start = NaiveDate::from_ymd_opt(2025, 3, 30).unwrap().and_hms_opt(1, 0, 0).unwrap();
+            *t >= end && *t < start
+        }
+    }
+
+    impl TimeZone for Dst {
+        type Offset = FixedOffset;
+
+        fn from_offset(_: &FixedOffset) -> Dst {
+            Dst
+        }
+
+        fn offset_from_local_date(&self, d: &NaiveDate) -> LocalResult<FixedOffset> {
+            self.offset_from_local_datetime(&d.and_time(NaiveTime::MIN))
+        }
+
+        fn offset_from_local_datetime(&self, t: &NaiveDateTime) -> LocalResult<FixedOffset> {
+            let summer = FixedOffset::east_opt(7200).unwrap();
+            let winter = FixedOffset::east_opt(3600).unwrap();
+            let before = self.offset_from_utc_datetime(&(*t - summer));
+            let after = self.offset_from_utc_datetime(&(*t - winter));
+            match (before, after) {
+                (a, b) if a == summer && b == winter => LocalResult::Ambiguous(a, b),
+                (a, _) if a == summer => LocalResult::Single(a),
+                (_, b) if b == winter => LocalResult::Single(b),
+                _ => LocalResult::None,
+            }
+        }
+
+        fn offset_from_utc_date(&self, d: &NaiveDate) -> FixedOffset {
+            self.offset_from_utc_datetime(&d.and_time(NaiveTime::MIN))
+        }
+
+        fn offset_from_utc_datetime(&self, t: &NaiveDateTime) -> FixedOffset {
+            FixedOffset::east_opt(if Dst::winter(t) { 3600 } else { 7200 }).unwrap()
+        }
+    }
+
+    #[test]
+    fn resolve() {
+        let at = |d, h| NaiveDate::from_ymd_opt(2024, 10, d).unwrap().and_hms_opt(h, 30, 0).unwrap();
+        let t = Ambiguity::Earliest.resolve(&Dst, &at(27, 2)).unwrap();
+        assert_eq!(t.to_rfc3339(), "2024-10-27T02:30:00+02:00");
+        let t = Ambiguity::Latest.resolve(&Dst, &at(27, 2)).unwrap();
+        assert_eq!(t.to_rfc3339(), "2024-10-27T02:30:00+01:00");
+        assert_eq!(Ambiguity::Reject.resolve(&Dst, &at(27, 2)), Err(Error::AmbiguousTime));
+        assert!(Ambiguity::Reject.resolve(&Dst, &at(26, 2)).is_ok());
+
+        let gap = NaiveDate::from_ymd_opt(2025, 3, 30).unwrap().and_hms_opt(2, 30, 0).unwrap();
+        assert_eq!(Ambiguity::Latest.resolve(&Dst, &gap), Err(Error::NonexistentTime));
+    }
+
+    #[test]
+    fn zone() {
+        let summer = FixedOffset::east_opt(7200).unwrap();
+        let winter = FixedOffset::east_opt(3600).unwrap();
+        let day = |m, d| NaiveDate::from_ymd_opt(2024, m, d).unwrap();
+        assert_eq!(Dst.offset_from_utc_date(&day(7, 1)), summer);
+        assert_eq!(Dst.offset_from_utc_date(&day(12, 1)), winter);
+        assert_eq!(Dst.offset_from_local_date(&day(12, 1)), LocalResult::Single(winter));
+        let t = Dst.from_utc_datetime(&day(12, 1).and_time(NaiveTime::MIN));
+        assert_eq!(t.to_rfc3339(), "2024-12-01T01:00:00+01:00");
+    }
+}
@@ -0,0 +1,78 @@
+//! A `with` module for `DateTime<Tz>` fields always written in UTC.
+//!
+//! By default a timestamp is written with the offset it has in memory, so a
+//! `DateTime<Local>` or `DateTime<FixedOffset>` leaks the local offset into
+//! the output. This module converts it to UTC first, for audit logs and
+//! other output that must never contain local offsets. Deserialization
+//! accepts any offset.
+//!
+//! # Example
+//!
+//! ```
+//! use chrono::{DateTime, FixedOffset};
+//! use serde::{Serialize, Deserialize};
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Entry {
+//!     #[serde(with = "humantime_serde::as_utc")]
+//!     at: DateTime<FixedOffset>,
+//! }
+//!
+//! let entry: Entry = serde_json::from_str(r#"{"at": "2018-05-11T20:28:30+02:00"}"#).unwrap();
+//! let json = serde_json::to_string(&entry).unwrap();
+//! assert_eq!(json, r#"{"at":"2018-05-11T18:28:30Z"}"#);
+//! ```
+
+use chrono::{DateTime, FixedOffset, TimeZone, Utc};
+use serde::{Deserialize, Deserializer, Serializer};
+
+use super::Serde;
+
+/// Serializes a `DateTime<Tz>` converted to UTC.
+pub fn serialize<Tz, S>(t: &DateTime<Tz>, s: S) -> Result<S::Ok, S::Error>
+where
+    Tz: TimeZone,
+    S: Serializer,
+{
+    crate::serialize(&t.with_timezone(&Utc), s)
+}
+
+/// Deserializes a `DateTime<Tz>` with any offset.
+pub fn deserialize<'a, Tz, D>(d: D) -> Result<DateTime<Tz>, D::Error>
+where
+    Tz: TimeZone,
+    DateTime<Tz>: From<DateTime<FixedOffset>>,
+    D: Deserializer<'a>,
+{
+    Serde::<DateTime<FixedOffset>>::deserialize(d).map(|t| t.into_inner().into())
+}
+
+crate::__option_module!([Tz] ::chrono::DateTime<Tz>, [
+    Tz: ::chrono::TimeZone,
+    ::chrono::DateTime<Tz>: ::core::convert::From<::chrono::DateTime<::chrono::FixedOffset>>,
+]);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde::Serialize;
+
+    #[derive(Serialize, Deserialize)]
+    struct Foo {
+        #[serde(with = "super")]
+        at: DateTime<FixedOffset>,
+        #[serde(with = "super::option")]
+        maybe: Option<DateTime<FixedOffset>>,
+    }
+
+    #[test]
+    fn as_utc() {
+        let json = r#"{"at": "2018-05-11T20:28:30+02:00", "maybe": "2018-05-11T13:28:30.5-05:00"}"#;
+        let foo: Foo = serde_json::from_str(json).unwrap();
+        assert_eq!(foo.at.offset().local_minus_utc(), 7200);
+        assert_eq!(
+            serde_json::to_string(&foo).unwrap(),
+            r#"{"at":"2018-05-11T18:28:30Z","maybe":"2018-05-11T18:28:30Z"}"#
+        );
+    }
+}
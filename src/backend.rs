@@ -0,0 +1,129 @@
+//! The engines that read and write the strings.
+//!
+//! Every `Duration` and `DateTime` going through the default format is
+//! parsed and formatted by the [`Backend`] named [`Selected`]: in the
+//! crate-level functions, the `Serde` wrapper and the modules built on
+//! them, in the types embedding durations or timestamps such as `Rate` or
+//! `RawDuration`, and in the `valuable` and `defmt` impls. Formats with a
+//! syntax of their own, such as [`custom_format!`](crate::custom_format)
+//! or the `kubernetes` module, do not go through it. Which backend is
+//! selected depends only on features, so switching engines does not touch
+//! a single `#[serde(with)]` attribute:
+//!
+//! * [`Humantime`], the default: humantime for durations and chrono's
+//!   RFC 3339 support for timestamps.
+//! * [`Fast`], with the `fast-parse` feature: a single pass parser for
+//!   common durations, falling back to humantime for the rest.
+//!
+//! Another engine is added as a type implementing `Backend`, overriding
+//! the methods it handles, and a feature selecting it. Errors are reported
+//! in humantime's and chrono's types whatever the engine, so the error
+//! types of the public API do not change with the backend.
+//!
+//! # Example
+//!
+//! ```
+//! use core::time::Duration;
+//! use humantime_serde::backend::{Backend, Humantime, Selected};
+//!
+//! assert_eq!(Humantime::parse_duration("1m 30s"), Ok(Duration::from_secs(90)));
+//! assert_eq!(Selected::parse_duration("1m 30s"), Humantime::parse_duration("1m 30s"));
+//! ```
+
+use core::fmt;
+use core::time::Duration;
+
+#[cfg(feature = "chrono")]
+use chrono::{DateTime, FixedOffset, TimeZone};
+
+/// A string parsing and formatting engine.
+///
+/// Every method defaults to the [`Humantime`] engine.
+pub trait Backend {
+    /// Parses a duration.
+    fn parse_duration(s: &str) -> Result<Duration, humantime::DurationError> {
+        humantime::parse_duration(s)
+    }
+
+    /// Writes a duration.
+    fn format_duration(d: Duration, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&humantime::format_duration(d), f)
+    }
+
+    /// Parses an RFC 3339 timestamp.
+    #[cfg(feature = "chrono")]
+    fn parse_timestamp(s: &str) -> Result<DateTime<FixedOffset>, chrono::ParseError> {
+        DateTime::parse_from_rfc3339(s)
+    }
+
+    /// Writes an RFC 3339 timestamp in whole seconds, with a `Z` for UTC.
+    #[cfg(feature = "chrono")]
+    fn format_timestamp<Tz>(t: &DateTime<Tz>, f: &mut fmt::Formatter) -> fmt::Result
+    where
+        Tz: TimeZone,
+    {
+        crate::format::write_rfc3339(t, f)
+    }
+}
+
+/// humantime for durations and chrono for timestamps.
+#[derive(Copy, Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Humantime;
+
+impl Backend for Humantime {}
+
+/// A single pass parser for durations in the common subset of humantime's
+/// syntax, falling back to humantime for anything else, so the result is
+/// the same either way.
+#[cfg(feature = "fast-parse")]
+#[derive(Copy, Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Fast;
+
+#[cfg(feature = "fast-parse")]
+impl Backend for Fast {
+    fn parse_duration(s: &str) -> Result<Duration, humantime::DurationError> {
+        match crate::fast::parse_duration(s) {
+            Some(d) => Ok(d),
+            None => humantime::parse_duration(s),
+        }
+    }
+}
+
+/// The backend used by the default format.
+#[cfg(feature = "fast-parse")]
+pub type Selected = Fast;
+
+/// The backend used by the default format.
+#[cfg(not(feature = "fast-parse"))]
+pub type Selected = Humantime;
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn selected() {
+        for s in &["1h 30m", "15 seconds", "2 secnds", "", "99999999999999999999y"] {
+            assert_eq!(Selected::parse_duration(s), Humantime::parse_duration(s), "{:?}", s);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn formats() {
+        let d = crate::format(&Duration::from_millis(90_500));
+        assert_eq!(d, "1m 30s 500ms");
+        let raw = crate::RawDuration::from(Duration::from_millis(90_500));
+        assert_eq!(raw.as_str(), d);
+        let rate = crate::Rate::new(3, Duration::from_millis(90_500));
+        assert_eq!(crate::format(&rate), "3/1m 30s 500ms");
+    }
+
+    #[test]
+    #[cfg(all(feature = "chrono", feature = "alloc"))]
+    fn timestamp() {
+        let t = Selected::parse_timestamp("2018-05-11T20:28:30+02:00").unwrap();
+        assert_eq!(crate::format(&t), "2018-05-11T20:28:30+02:00");
+        assert!(Selected::parse_timestamp("2018-05-11").is_err());
+    }
+}
@@ -0,0 +1,358 @@
+use core::fmt::{self, Write};
+use core::str::FromStr;
+use core::time::Duration;
+
+use serde::de::{self, MapAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use super::format::{HumantimeFormat, Ref};
+
+/// How the delay grows between retries.
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BackoffKind {
+    /// The initial delay every time.
+    Constant,
+    /// The initial delay times the attempt number.
+    Linear,
+    /// The initial delay times the multiplier to the power of the attempt.
+    Exponential,
+}
+
+impl BackoffKind {
+    fn as_str(&self) -> &'static str {
+        match self {
+            BackoffKind::Constant => "constant",
+            BackoffKind::Linear => "linear",
+            BackoffKind::Exponential => "exponential",
+        }
+    }
+}
+
+/// A retry policy, written `"exponential 100ms..30s x2 jitter 0.1"` or as a
+/// map of its fields.
+///
+/// The string form is the kind, the initial delay, optionally `..` and the
+/// maximum delay, then optionally `x` and the multiplier (exponential only,
+/// defaulting to 2) and `jitter` with the randomization factor between 0
+/// and 1. The delays are written without spaces, as in `1m30s`. The map form has the fields `kind`, `initial`, `max`, `multiplier`
+/// and `jitter`, all but the first two optional. Either way it serializes to
+/// the string form.
+///
+/// The fields map directly onto common retry crates, e.g. the `initial_interval`,
+/// `max_interval`, `multiplier` and `randomization_factor` of
+/// `backoff::ExponentialBackoff`.
+///
+/// # Example
+///
+/// ```
+/// use core::time::Duration;
+/// use humantime_serde::BackoffSpec;
+///
+/// let spec: BackoffSpec = "exponential 100ms..1s x3".parse().unwrap();
+/// let delays: Vec<_> = spec.delays().take(4).collect();
+/// assert_eq!(delays, [
+///     Duration::from_millis(100),
+///     Duration::from_millis(300),
+///     Duration::from_millis(900),
+///     Duration::from_secs(1),
+/// ]);
+///
+/// let json = r#"{"kind": "constant", "initial": "5s", "jitter": 0.5}"#;
+/// let spec: BackoffSpec = serde_json::from_str(json).unwrap();
+/// assert_eq!(spec.to_string(), "constant 5s jitter 0.5");
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct BackoffSpec {
+    /// How the delay grows.
+    pub kind: BackoffKind,
+    /// The delay before the first retry.
+    pub initial: Duration,
+    /// The cap on the delay, if any.
+    pub max: Option<Duration>,
+    /// The growth factor of exponential backoff.
+    pub multiplier: f64,
+    /// The fraction of the delay by which it may be randomized, from 0 to 1.
+    pub jitter: f64,
+}
+
+impl BackoffSpec {
+    /// Creates a policy of `kind` starting at `initial`, without cap or
+    /// jitter and with a multiplier of 2.
+    pub const fn new(kind: BackoffKind, initial: Duration) -> BackoffSpec {
+        BackoffSpec {
+            kind,
+            initial,
+            max: None,
+            multiplier: 2.0,
+            jitter: 0.0,
+        }
+    }
+
+    /// The delay before retry number `attempt`, counting from zero, before
+    /// jitter.
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let max = self.max.unwrap_or(Duration::MAX);
+        let delay = match self.kind {
+            BackoffKind::Constant => self.initial,
+            BackoffKind::Linear => self.initial.saturating_mul(attempt.saturating_add(1)),
+            BackoffKind::Exponential => {
+                let nanos = self.initial.as_nanos() as f64 * powu(self.multiplier, attempt);
+                if nanos < u64::MAX as f64 {
+                    Duration::from_nanos(nanos as u64)
+                } else {
+                    max
+                }
+            }
+        };
+        delay.min(max)
+    }
+
+    /// The delays before each retry, before jitter.
+    pub fn delays(&self) -> impl Iterator<Item = Duration> + '_ {
+        (0..).map(move |attempt| self.delay(attempt))
+    }
+
+    fn validate(self) -> Result<BackoffSpec, InvalidBackoff> {
+        let valid = self.multiplier.is_finite()
+            && self.multiplier >= 1.0
+            && (0.0..=1.0).contains(&self.jitter)
+            && self.max.map_or(true, |max| max >= self.initial);
+        if valid {
+            Ok(self)
+        } else {
+            Err(InvalidBackoff)
+        }
+    }
+}
+
+/// `base` to the power of `exp`, since `f64::powi` needs `std`.
+fn powu(mut base: f64, mut exp: u32) -> f64 {
+    let mut acc = 1.0;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            acc *= base;
+        }
+        base *= base;
+        exp >>= 1;
+    }
+    acc
+}
+
+/// The error returned when parsing a [`BackoffSpec`] fails.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct InvalidBackoff;
+
+impl fmt::Display for InvalidBackoff {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("invalid backoff, expected e.g. exponential 100ms..30s x2")
+    }
+}
+
+impl core::error::Error for InvalidBackoff {}
+
+impl HumantimeFormat for BackoffSpec {
+    const EXPECTING: &'static str = "a backoff policy";
+
+    type Err = InvalidBackoff;
+
+    fn parse(s: &str) -> Result<BackoffSpec, InvalidBackoff> {
+        let mut words = s.split_whitespace();
+        let kind = match words.next() {
+            Some("constant") => BackoffKind::Constant,
+            Some("linear") => BackoffKind::Linear,
+            Some("exponential") => BackoffKind::Exponential,
+            _ => return Err(InvalidBackoff),
+        };
+        let range = words.next().ok_or(InvalidBackoff)?;
+        let (initial, max) = match range.split_once("..") {
+            Some((initial, max)) => (initial, Some(max)),
+            None => (range, None),
+        };
+        let mut spec = BackoffSpec::new(kind, Duration::parse(initial).map_err(|_| InvalidBackoff)?);
+        spec.max = max
+            .map(|max| Duration::parse(max).map_err(|_| InvalidBackoff))
+            .transpose()?;
+        while let Some(word) = words.next() {
+            if let Some(multiplier) = word.strip_prefix('x') {
+                if kind != BackoffKind::Exponential {
+                    return Err(InvalidBackoff);
+                }
+                spec.multiplier = multiplier.parse().map_err(|_| InvalidBackoff)?;
+            } else if word == "jitter" {
+                let jitter = words.next().ok_or(InvalidBackoff)?;
+                spec.jitter = jitter.parse().map_err(|_| InvalidBackoff)?;
+            } else {
+                return Err(InvalidBackoff);
+            }
+        }
+        spec.validate()
+    }
+
+    fn format(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} ", self.kind.as_str())?;
+        write!(Compact(f), "{}", Ref(&self.initial))?;
+        if let Some(max) = self.max {
+            f.write_str("..")?;
+            write!(Compact(f), "{}", Ref(&max))?;
+        }
+        if self.kind == BackoffKind::Exponential && self.multiplier != 2.0 {
+            write!(f, " x{}", self.multiplier)?;
+        }
+        if self.jitter != 0.0 {
+            write!(f, " jitter {}", self.jitter)?;
+        }
+        Ok(())
+    }
+}
+
+/// Writes through to a formatter without the spaces between the units of a
+/// duration, so the duration stays one word of the string form.
+struct Compact<'a, 'b>(&'a mut fmt::Formatter<'b>);
+
+impl fmt::Write for Compact<'_, '_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        s.split(' ').try_for_each(|part| self.0.write_str(part))
+    }
+}
+
+impl FromStr for BackoffSpec {
+    type Err = InvalidBackoff;
+
+    fn from_str(s: &str) -> Result<BackoffSpec, InvalidBackoff> {
+        BackoffSpec::parse(s)
+    }
+}
+
+impl fmt::Display for BackoffSpec {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.format(f)
+    }
+}
+
+impl Serialize for BackoffSpec {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        Ref(self).serialize(serializer)
+    }
+}
+
+/// The map form of a `BackoffSpec`.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Fields {
+    kind: BackoffKind,
+    #[serde(with = "crate")]
+    initial: Duration,
+    #[serde(with = "crate::option", default)]
+    max: Option<Duration>,
+    multiplier: Option<f64>,
+    #[serde(default)]
+    jitter: f64,
+}
+
+impl<'de> Deserialize<'de> for BackoffSpec {
+    fn deserialize<D>(d: D) -> Result<BackoffSpec, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct V;
+
+        impl<'de> Visitor<'de> for V {
+            type Value = BackoffSpec;
+
+            fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+                fmt.write_str(BackoffSpec::EXPECTING)
+            }
+
+            fn visit_str<E>(self, v: &str) -> Result<BackoffSpec, E>
+            where
+                E: de::Error,
+            {
+                crate::check_len(v, crate::DEFAULT_MAX_LEN, BackoffSpec::EXPECTING)?;
+                BackoffSpec::parse(v).map_err(|err| {
+                    E::invalid_value(de::Unexpected::Str(v), &crate::Because(&self, &err))
+                })
+            }
+
+            fn visit_map<A>(self, map: A) -> Result<BackoffSpec, A::Error>
+            where
+                A: MapAccess<'de>,
+            {
+                let fields = Fields::deserialize(de::value::MapAccessDeserializer::new(map))?;
+                let mut spec = BackoffSpec::new(fields.kind, fields.initial);
+                spec.max = fields.max;
+                spec.jitter = fields.jitter;
+                if let Some(multiplier) = fields.multiplier {
+                    spec.multiplier = multiplier;
+                }
+                spec.validate().map_err(de::Error::custom)
+            }
+        }
+
+        d.deserialize_any(V)
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod test {
+    use super::*;
+    use alloc::string::ToString;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn parse() {
+        let spec: BackoffSpec = "exponential 100ms..30s x1.5 jitter 0.2".parse().unwrap();
+        assert_eq!(spec.initial, Duration::from_millis(100));
+        assert_eq!(spec.max, Some(Duration::from_secs(30)));
+        assert_eq!(spec.multiplier, 1.5);
+        assert_eq!(spec.jitter, 0.2);
+        assert_eq!(spec.to_string(), "exponential 100ms..30s x1.5 jitter 0.2");
+
+        assert_eq!("linear 1s x2".parse::<BackoffSpec>(), Err(InvalidBackoff));
+        assert_eq!("exponential 10s..1s".parse::<BackoffSpec>(), Err(InvalidBackoff));
+        assert_eq!("exponential 1s jitter 2".parse::<BackoffSpec>(), Err(InvalidBackoff));
+        assert_eq!("random 1s".parse::<BackoffSpec>(), Err(InvalidBackoff));
+    }
+
+    #[test]
+    fn delays() {
+        let spec: BackoffSpec = "linear 1s..3s".parse().unwrap();
+        let delays: Vec<_> = spec.delays().take(4).map(|d| d.as_secs()).collect();
+        assert_eq!(delays, [1, 2, 3, 3]);
+
+        let spec: BackoffSpec = "exponential 1s".parse().unwrap();
+        assert_eq!(spec.delay(3), Duration::from_secs(8));
+        assert_eq!(spec.delay(u32::MAX), Duration::MAX);
+    }
+
+    #[test]
+    fn serde() {
+        let spec: BackoffSpec = serde_json::from_str(r#""exponential 1s..1m""#).unwrap();
+        assert_eq!(serde_json::to_string(&spec).unwrap(), r#""exponential 1s..1m""#);
+
+        let json = r#"{"kind": "exponential", "initial": "1s", "max": "1m", "multiplier": 3}"#;
+        let spec: BackoffSpec = serde_json::from_str(json).unwrap();
+        assert_eq!(serde_json::to_string(&spec).unwrap(), r#""exponential 1s..1m x3""#);
+
+        let json = r#"{"kind": "constant", "initial": "1s", "jitter": 1.5}"#;
+        let err = serde_json::from_str::<BackoffSpec>(json).unwrap_err();
+        assert!(err.to_string().starts_with("invalid backoff"));
+
+        let err = serde_json::from_str::<BackoffSpec>(r#""linear 1s x2""#).unwrap_err();
+        assert!(err.to_string().contains("expected a backoff policy: invalid backoff"));
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut spec = BackoffSpec::new(BackoffKind::Exponential, Duration::from_millis(90_500));
+        spec.max = Some(Duration::from_secs(3_661));
+        spec.jitter = 0.25;
+        let json = serde_json::to_string(&spec).unwrap();
+        assert_eq!(json, r#""exponential 1m30s500ms..1h1m1s jitter 0.25""#);
+        assert_eq!(serde_json::from_str::<BackoffSpec>(&json).unwrap(), spec);
+        assert_eq!(spec.to_string().parse(), Ok(spec));
+    }
+}
@@ -0,0 +1,136 @@
+//! `borsh` support, enabled with the `borsh` feature.
+//!
+//! Durations are stored as the whole seconds (`u64`) followed by the
+//! sub-second nanoseconds (`u32`). Timestamps are stored as the seconds since
+//! the unix epoch (`i64`) followed by the sub-second nanoseconds (`u32`). All
+//! integers are little-endian, as usual for borsh.
+
+use core::time::Duration;
+
+use borsh::io::{Error, ErrorKind, Read, Result, Write};
+use borsh::{BorshDeserialize, BorshSerialize};
+#[cfg(feature = "chrono")]
+use chrono::{DateTime, Utc};
+
+use super::{HumanDuration, Serde};
+#[cfg(feature = "chrono")]
+use super::HumanTimestamp;
+
+const NANOS_PER_SEC: u32 = 1_000_000_000;
+
+fn write_duration<W: Write>(d: &Duration, writer: &mut W) -> Result<()> {
+    BorshSerialize::serialize(&d.as_secs(), writer)?;
+    BorshSerialize::serialize(&d.subsec_nanos(), writer)
+}
+
+fn read_duration<R: Read>(reader: &mut R) -> Result<Duration> {
+    let secs = u64::deserialize_reader(reader)?;
+    let nanos = u32::deserialize_reader(reader)?;
+    if nanos >= NANOS_PER_SEC {
+        return Err(Error::new(ErrorKind::InvalidData, "duration nanoseconds out of range"));
+    }
+    Ok(Duration::new(secs, nanos))
+}
+
+#[cfg(feature = "chrono")]
+fn write_timestamp<W: Write>(t: &DateTime<Utc>, writer: &mut W) -> Result<()> {
+    BorshSerialize::serialize(&t.timestamp(), writer)?;
+    BorshSerialize::serialize(&t.timestamp_subsec_nanos(), writer)
+}
+
+#[cfg(feature = "chrono")]
+fn read_timestamp<R: Read>(reader: &mut R) -> Result<DateTime<Utc>> {
+    let secs = i64::deserialize_reader(reader)?;
+    let nanos = u32::deserialize_reader(reader)?;
+    if nanos >= NANOS_PER_SEC {
+        return Err(Error::new(ErrorKind::InvalidData, "timestamp nanoseconds out of range"));
+    }
+    DateTime::from_timestamp(secs, nanos)
+        .ok_or_else(|| Error::new(ErrorKind::InvalidData, "timestamp out of range"))
+}
+
+impl BorshSerialize for Serde<Duration> {
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+        write_duration(&self.0, writer)
+    }
+}
+
+impl BorshDeserialize for Serde<Duration> {
+    fn deserialize_reader<R: Read>(reader: &mut R) -> Result<Self> {
+        read_duration(reader).map(Serde)
+    }
+}
+
+impl BorshSerialize for HumanDuration {
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+        write_duration(self, writer)
+    }
+}
+
+impl BorshDeserialize for HumanDuration {
+    fn deserialize_reader<R: Read>(reader: &mut R) -> Result<Self> {
+        read_duration(reader).map(HumanDuration::from)
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl BorshSerialize for Serde<DateTime<Utc>> {
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+        write_timestamp(&self.0, writer)
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl BorshDeserialize for Serde<DateTime<Utc>> {
+    fn deserialize_reader<R: Read>(reader: &mut R) -> Result<Self> {
+        read_timestamp(reader).map(Serde)
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl BorshSerialize for HumanTimestamp {
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+        write_timestamp(self, writer)
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl BorshDeserialize for HumanTimestamp {
+    fn deserialize_reader<R: Read>(reader: &mut R) -> Result<Self> {
+        read_timestamp(reader).map(HumanTimestamp::from)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn duration() {
+        let dur = Serde::from(Duration::new(90, 500));
+        let bytes = borsh::to_vec(&dur).unwrap();
+        assert_eq!(bytes, [90, 0, 0, 0, 0, 0, 0, 0, 0xf4, 0x01, 0, 0]);
+        assert_eq!(borsh::from_slice::<Serde<Duration>>(&bytes).unwrap(), dur);
+    }
+
+    #[test]
+    fn human_duration() {
+        let dur = HumanDuration::from(Duration::new(90, 500));
+        let bytes = borsh::to_vec(&dur).unwrap();
+        assert_eq!(bytes, borsh::to_vec(&Serde::from(*dur)).unwrap());
+        assert_eq!(borsh::from_slice::<HumanDuration>(&bytes).unwrap(), dur);
+    }
+
+    #[test]
+    fn time() {
+        let time = Serde::from(DateTime::UNIX_EPOCH + Duration::new(1526063310, 0));
+        let bytes = borsh::to_vec(&time).unwrap();
+        assert_eq!(borsh::from_slice::<Serde<DateTime<Utc>>>(&bytes).unwrap(), time);
+    }
+
+    #[test]
+    fn invalid_nanos() {
+        let bytes = [0, 0, 0, 0, 0, 0, 0, 0, 0xff, 0xff, 0xff, 0xff];
+        assert!(borsh::from_slice::<Serde<Duration>>(&bytes).is_err());
+    }
+}
@@ -0,0 +1,525 @@
+//! Duration and timestamp formats restricted to a range, generated with
+//! [`bounded!`].
+//!
+//! The generated module deserializes like the crate's own, but rejects
+//! values outside the range with an error naming the allowed bounds. The
+//! range can be inclusive (`"1s" ..= "1h"`), half-open (`"1s" .. "1h"`) or
+//! open on either side (`"1s" ..`, `..= "1h"`).
+//!
+//! The bounds are written in the same syntax as the values, durations or
+//! RFC 3339 timestamps, and are parsed at compile time, so an invalid bound
+//! fails the build. A value is never within a bound of the other kind.
+//!
+//! # Example
+//!
+//! ```
+//! use core::time::Duration;
+//! use chrono::{DateTime, Utc};
+//! use serde::{Serialize, Deserialize};
+//!
+//! humantime_serde::bounded!(pub mod poll_interval: "1s" ..= "1h");
+//! humantime_serde::bounded!(
+//!     pub mod this_century: "2000-01-01T00:00:00Z" .. "2100-01-01T00:00:00Z"
+//! );
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Foo {
+//!     #[serde(with = "poll_interval")]
+//!     interval: Duration,
+//!     #[serde(with = "this_century")]
+//!     recorded_at: DateTime<Utc>,
+//! }
+//!
+//! let json = r#"{"interval": "2h", "recorded_at": "2018-05-11T18:28:30Z"}"#;
+//! let err = serde_json::from_str::<Foo>(json).unwrap_err();
+//! assert!(err
+//!     .to_string()
+//!     .starts_with("2h is out of range, expected a duration between 1s and 1h"));
+//! ```
+//!
+//! ```compile_fail
+//! humantime_serde::bounded!(pub mod broken: "1s" ..= "1 parsec");
+//! ```
+//!
+//! [`bounded!`]: crate::bounded!
+
+use core::cmp::Ordering;
+use core::fmt;
+use core::time::Duration;
+
+#[cfg(feature = "chrono")]
+use chrono::{DateTime, TimeZone};
+use serde::{de, Deserialize, Deserializer};
+
+use super::format::{HumantimeFormat, Ref};
+use super::Serde;
+
+/// One end of a range, parsed at compile time.
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Bound {
+    text: &'static str,
+    value: Value,
+}
+
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+enum Value {
+    Duration(Duration),
+    /// Seconds and nanoseconds since the Unix epoch.
+    Timestamp(i64, u32),
+}
+
+impl Bound {
+    /// Parses a duration or an RFC 3339 timestamp.
+    ///
+    /// # Panics
+    ///
+    /// Panics, and so fails compilation in a const, if `text` is neither.
+    pub const fn new(text: &'static str) -> Bound {
+        let value = match parse_timestamp(text.as_bytes()) {
+            Some((secs, nanos)) => Value::Timestamp(secs, nanos),
+            None => Value::Duration(crate::literal::parse_duration(text)),
+        };
+        Bound { text, value }
+    }
+}
+
+/// Whether `b` is at or below `max`, panicking if they are not of the same
+/// kind.
+const fn not_above(b: &Bound, max: &Bound) -> bool {
+    match (b.value, max.value) {
+        (Value::Duration(b), Value::Duration(max)) => b.as_nanos() <= max.as_nanos(),
+        (Value::Timestamp(b, b_nanos), Value::Timestamp(max, max_nanos)) => {
+            b < max || (b == max && b_nanos <= max_nanos)
+        }
+        _ => panic!("invalid bounds: a duration and a timestamp"),
+    }
+}
+
+/// The range accepted by a bounded format.
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Bounds {
+    min: Option<Bound>,
+    max: Option<Bound>,
+    max_inclusive: bool,
+}
+
+impl Bounds {
+    /// Accepts values from `min` to `max`, both included.
+    ///
+    /// # Panics
+    ///
+    /// Panics, and so fails compilation in a const, if a bound is invalid
+    /// or `min` is above `max`.
+    pub const fn inclusive(min: &'static str, max: &'static str) -> Bounds {
+        Bounds::range(min, max, true)
+    }
+
+    /// Accepts values from `min` included to `max` excluded.
+    ///
+    /// # Panics
+    ///
+    /// Panics, and so fails compilation in a const, if a bound is invalid
+    /// or `min` is above `max`.
+    pub const fn exclusive(min: &'static str, max: &'static str) -> Bounds {
+        Bounds::range(min, max, false)
+    }
+
+    const fn range(min: &'static str, max: &'static str, max_inclusive: bool) -> Bounds {
+        let (min, max) = (Bound::new(min), Bound::new(max));
+        if !not_above(&min, &max) {
+            panic!("invalid bounds: the minimum is above the maximum");
+        }
+        Bounds { min: Some(min), max: Some(max), max_inclusive }
+    }
+
+    /// Accepts values of at least `min`.
+    ///
+    /// # Panics
+    ///
+    /// Panics, and so fails compilation in a const, if `min` is invalid.
+    pub const fn at_least(min: &'static str) -> Bounds {
+        Bounds { min: Some(Bound::new(min)), max: None, max_inclusive: true }
+    }
+
+    /// Accepts values of at most `max`.
+    ///
+    /// # Panics
+    ///
+    /// Panics, and so fails compilation in a const, if `max` is invalid.
+    pub const fn at_most(max: &'static str) -> Bounds {
+        Bounds { min: None, max: Some(Bound::new(max)), max_inclusive: true }
+    }
+
+    /// Returns whether `v` is within the bounds.
+    pub fn contains<T>(&self, v: &T) -> bool
+    where
+        T: Bounded,
+    {
+        let above_min = self.min.map_or(true, |min| {
+            matches!(v.cmp_bound(&min), Some(Ordering::Greater | Ordering::Equal))
+        });
+        let below_max = self.max.map_or(true, |max| match v.cmp_bound(&max) {
+            Some(Ordering::Less) => true,
+            Some(Ordering::Equal) => self.max_inclusive,
+            _ => false,
+        });
+        above_min && below_max
+    }
+}
+
+impl fmt::Display for Bounds {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let min = self.min.map(|b| b.text);
+        let max = self.max.map(|b| b.text);
+        match (min, max, self.max_inclusive) {
+            (Some(min), Some(max), true) => write!(f, "between {} and {}", min, max),
+            (Some(min), Some(max), false) => {
+                write!(f, "at least {} and less than {}", min, max)
+            }
+            (Some(min), None, _) => write!(f, "at least {}", min),
+            (None, Some(max), true) => write!(f, "at most {}", max),
+            (None, Some(max), false) => write!(f, "less than {}", max),
+            (None, None, _) => Ok(()),
+        }
+    }
+}
+
+/// A type that can be checked against [`Bounds`].
+pub trait Bounded: HumantimeFormat {
+    /// Compares the value with `bound`, or returns `None` if the bound is
+    /// of another kind.
+    fn cmp_bound(&self, bound: &Bound) -> Option<Ordering>;
+}
+
+impl Bounded for Duration {
+    fn cmp_bound(&self, bound: &Bound) -> Option<Ordering> {
+        match bound.value {
+            Value::Duration(d) => Some(self.cmp(&d)),
+            Value::Timestamp(..) => None,
+        }
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl<Tz> Bounded for DateTime<Tz>
+where
+    Tz: TimeZone,
+    DateTime<Tz>: HumantimeFormat,
+{
+    fn cmp_bound(&self, bound: &Bound) -> Option<Ordering> {
+        match bound.value {
+            Value::Timestamp(secs, nanos) => {
+                Some((self.timestamp(), self.timestamp_subsec_nanos()).cmp(&(secs, nanos)))
+            }
+            Value::Duration(_) => None,
+        }
+    }
+}
+
+/// Parses an RFC 3339 timestamp into seconds and nanoseconds since the
+/// Unix epoch, or returns `None` if `b` does not start with a year.
+///
+/// Panics if `b` starts with a year but is not a valid timestamp.
+const fn parse_timestamp(b: &[u8]) -> Option<(i64, u32)> {
+    if b.len() < 5 || !digits(b, 0, 4) || b[4] != b'-' {
+        return None;
+    }
+    let valid = b.len() > 19
+        && digits(b, 5, 7)
+        && b[7] == b'-'
+        && digits(b, 8, 10)
+        && matches!(b[10], b'T' | b't' | b' ')
+        && digits(b, 11, 13)
+        && b[13] == b':'
+        && digits(b, 14, 16)
+        && b[16] == b':'
+        && digits(b, 17, 19);
+    if !valid {
+        panic!("invalid timestamp: expected YYYY-MM-DDTHH:MM:SS");
+    }
+    let (year, month, day) = (number(b, 0, 4), number(b, 5, 7), number(b, 8, 10));
+    let (hour, minute, second) = (number(b, 11, 13), number(b, 14, 16), number(b, 17, 19));
+    if month < 1 || month > 12 || day < 1 || day > days_in_month(year, month) {
+        panic!("invalid timestamp: no such date");
+    }
+    if hour > 23 || minute > 59 || second > 59 {
+        panic!("invalid timestamp: no such time");
+    }
+    let mut i = 19;
+    let mut nanos: u32 = 0;
+    if b[i] == b'.' {
+        i += 1;
+        let start = i;
+        while i < b.len() && b[i].is_ascii_digit() {
+            if i - start < 9 {
+                nanos = nanos * 10 + (b[i] - b'0') as u32;
+            }
+            i += 1;
+        }
+        if i == start {
+            panic!("invalid timestamp: expected digits after the point");
+        }
+        let mut digits = i - start;
+        while digits < 9 {
+            nanos *= 10;
+            digits += 1;
+        }
+    }
+    let offset = if i < b.len() && matches!(b[i], b'Z' | b'z') {
+        i += 1;
+        0
+    } else if i + 6 == b.len()
+        && matches!(b[i], b'+' | b'-')
+        && digits(b, i + 1, i + 3)
+        && b[i + 3] == b':'
+        && digits(b, i + 4, i + 6)
+    {
+        let (hours, minutes) = (number(b, i + 1, i + 3), number(b, i + 4, i + 6));
+        if hours > 23 || minutes > 59 {
+            panic!("invalid timestamp: no such offset");
+        }
+        let offset = hours * 3600 + minutes * 60;
+        let sign = b[i];
+        i += 6;
+        if sign == b'-' {
+            -offset
+        } else {
+            offset
+        }
+    } else {
+        panic!("invalid timestamp: expected Z or an offset such as +02:00");
+    };
+    if i != b.len() {
+        panic!("invalid timestamp: trailing characters");
+    }
+    let days = days_from_civil(year, month, day);
+    Some((days * 86_400 + hour * 3600 + minute * 60 + second - offset, nanos))
+}
+
+/// Whether `b[start..end]` are all ASCII digits.
+const fn digits(b: &[u8], start: usize, end: usize) -> bool {
+    let mut i = start;
+    while i < end {
+        if i >= b.len() || !b[i].is_ascii_digit() {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+/// The number written in the digits `b[start..end]`.
+const fn number(b: &[u8], start: usize, end: usize) -> i64 {
+    let mut n = 0;
+    let mut i = start;
+    while i < end {
+        n = n * 10 + (b[i] - b'0') as i64;
+        i += 1;
+    }
+    n
+}
+
+const fn days_in_month(year: i64, month: i64) -> i64 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// The number of days from 1970-01-01 to the given date, for years 0 to
+/// 9999.
+const fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = (if year >= 0 { year } else { year - 399 }) / 400;
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// Deserializes a `Duration` or `DateTime<Tz>`, rejecting it if it is
+/// outside `bounds`.
+pub fn deserialize<'a, T, D>(bounds: &Bounds, d: D) -> Result<T, D::Error>
+where
+    T: Bounded,
+    D: Deserializer<'a>,
+{
+    let v = Serde::<T>::deserialize(d)?.into_inner();
+    if bounds.contains(&v) {
+        Ok(v)
+    } else {
+        Err(de::Error::custom(format_args!(
+            "{} is out of range, expected {} {}",
+            Ref(&v),
+            T::EXPECTING,
+            bounds,
+        )))
+    }
+}
+
+/// Generates a module for `#[serde(with = "...")]` accepting durations or
+/// timestamps within a range.
+///
+/// The generated module has an `option` submodule for `Option` fields.
+///
+/// See the [`bounded`](mod@crate::bounded) module for details.
+#[macro_export]
+macro_rules! bounded {
+    (@module [$($attr:tt)*] $vis:vis $name:ident $bounds:expr) => {
+        $($attr)*
+        $vis mod $name {
+            const BOUNDS: $crate::bounded::Bounds = $bounds;
+
+            /// Serializes a `Duration` or `DateTime<Tz>`.
+            pub fn serialize<T, S>(d: &T, s: S) -> ::core::result::Result<S::Ok, S::Error>
+            where
+                T: $crate::format::HumantimeFormat,
+                S: $crate::re::serde::Serializer,
+            {
+                $crate::serialize(d, s)
+            }
+
+            /// Deserializes a `Duration` or `DateTime<Tz>`, rejecting it if
+            /// out of range.
+            pub fn deserialize<'a, T, D>(d: D) -> ::core::result::Result<T, D::Error>
+            where
+                T: $crate::bounded::Bounded,
+                D: $crate::re::serde::Deserializer<'a>,
+            {
+                $crate::bounded::deserialize(&BOUNDS, d)
+            }
+
+            $crate::__option_module!([T] T, [
+                T: $crate::bounded::Bounded,
+            ]);
+        }
+    };
+    ($(#[$attr:meta])* $vis:vis mod $name:ident : $min:literal ..= $max:literal) => {
+        $crate::bounded!(@module [$(#[$attr])*] $vis $name
+            $crate::bounded::Bounds::inclusive($min, $max));
+    };
+    ($(#[$attr:meta])* $vis:vis mod $name:ident : $min:literal .. $max:literal) => {
+        $crate::bounded!(@module [$(#[$attr])*] $vis $name
+            $crate::bounded::Bounds::exclusive($min, $max));
+    };
+    ($(#[$attr:meta])* $vis:vis mod $name:ident : $min:literal ..) => {
+        $crate::bounded!(@module [$(#[$attr])*] $vis $name
+            $crate::bounded::Bounds::at_least($min));
+    };
+    ($(#[$attr:meta])* $vis:vis mod $name:ident : ..= $max:literal) => {
+        $crate::bounded!(@module [$(#[$attr])*] $vis $name
+            $crate::bounded::Bounds::at_most($max));
+    };
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod test {
+    use super::*;
+    use alloc::string::ToString;
+    #[cfg(feature = "chrono")]
+    use chrono::{DateTime, Utc};
+    use core::time::Duration;
+    use serde::Serialize;
+
+    crate::bounded!(mod poll_interval: "1s" ..= "1h");
+    crate::bounded!(mod this_century: "2000-01-01T00:00:00Z" .. "2100-01-01T00:00:00Z");
+
+    #[test]
+    fn duration() {
+        #[derive(Serialize, Deserialize)]
+        struct Foo {
+            #[serde(with = "poll_interval")]
+            interval: Duration,
+        }
+
+        let foo = serde_json::from_str::<Foo>(r#"{"interval": "1h"}"#).unwrap();
+        assert_eq!(foo.interval, Duration::from_secs(3600));
+        let reverse = serde_json::to_string(&foo).unwrap();
+        assert_eq!(reverse, r#"{"interval":"1h"}"#);
+
+        let err = serde_json::from_str::<Foo>(r#"{"interval": "500ms"}"#).unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with("500ms is out of range, expected a duration between 1s and 1h"));
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn time() {
+        #[derive(Serialize, Deserialize)]
+        struct Foo {
+            #[serde(with = "this_century")]
+            time: DateTime<Utc>,
+        }
+
+        let json = r#"{"time": "2018-05-11T18:28:30Z"}"#;
+        let foo = serde_json::from_str::<Foo>(json).unwrap();
+        assert_eq!(serde_json::to_string(&foo).unwrap(), r#"{"time":"2018-05-11T18:28:30Z"}"#);
+
+        let err = serde_json::from_str::<Foo>(r#"{"time": "1970-01-01T00:00:00Z"}"#).unwrap_err();
+        assert!(err.to_string().starts_with(
+            "1970-01-01T00:00:00Z is out of range, expected a timestamp \
+             at least 2000-01-01T00:00:00Z and less than 2100-01-01T00:00:00Z"
+        ));
+    }
+
+    #[test]
+    fn option() {
+        #[derive(Serialize, Deserialize)]
+        struct Foo {
+            #[serde(with = "poll_interval::option", default)]
+            interval: Option<Duration>,
+        }
+
+        let foo = serde_json::from_str::<Foo>(r#"{"interval": "1m"}"#).unwrap();
+        assert_eq!(foo.interval, Some(Duration::from_secs(60)));
+        assert_eq!(serde_json::to_string(&foo).unwrap(), r#"{"interval":"1m"}"#);
+        let foo = serde_json::from_str::<Foo>("{}").unwrap();
+        assert_eq!(foo.interval, None);
+        assert_eq!(serde_json::to_string(&foo).unwrap(), r#"{"interval":null}"#);
+        assert!(serde_json::from_str::<Foo>(r#"{"interval": "2h"}"#).is_err());
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(Bounds::exclusive("1s", "1h").to_string(), "at least 1s and less than 1h");
+        assert_eq!(Bounds::at_least("1s").to_string(), "at least 1s");
+        assert_eq!(Bounds::at_most("1h").to_string(), "at most 1h");
+        assert!(!Bounds::exclusive("1s", "1h").contains(&Duration::from_secs(3600)));
+        #[cfg(feature = "chrono")]
+        assert!(!Bounds::at_least("1s").contains(&DateTime::<Utc>::UNIX_EPOCH));
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn timestamps() {
+        for s in &[
+            "2000-01-01T00:00:00Z",
+            "2018-05-11T20:28:30+02:00",
+            "1969-12-31T23:59:59.999999999-01:30",
+            "2024-02-29T12:00:00.5Z",
+            "0001-03-01T00:00:00Z",
+            "9999-12-31T23:59:59Z",
+        ] {
+            let t = DateTime::parse_from_rfc3339(s).unwrap();
+            let expected = (t.timestamp(), t.timestamp_subsec_nanos());
+            assert_eq!(parse_timestamp(s.as_bytes()), Some(expected), "{}", s);
+        }
+        assert_eq!(parse_timestamp(b"1h"), None);
+        assert_eq!(parse_timestamp(b"2000s"), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "no such date")]
+    fn invalid_timestamp() {
+        parse_timestamp(b"2023-02-29T00:00:00Z");
+    }
+
+    #[test]
+    #[should_panic(expected = "minimum is above the maximum")]
+    fn reversed() {
+        Bounds::inclusive("1h", "1s");
+    }
+}
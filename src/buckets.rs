@@ -0,0 +1,193 @@
+use alloc::vec::Vec;
+use core::fmt;
+use core::ops::Deref;
+use core::str::FromStr;
+use core::time::Duration;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use super::format::{HumantimeFormat, Ref};
+use super::Serde;
+
+/// The upper bounds of latency histogram buckets, written as a comma
+/// separated list such as `"1ms, 5ms, 25ms, 100ms, 1s"`.
+///
+/// There is at least one bucket, and each bound is above the one before
+/// it. Buckets are written back separated by `", "`.
+///
+/// # Example
+///
+/// ```
+/// use core::time::Duration;
+/// use humantime_serde::{Buckets, InvalidBuckets};
+///
+/// let buckets: Buckets = "1ms, 5ms, 25ms, 100ms, 1s".parse().unwrap();
+/// assert_eq!(buckets.len(), 5);
+/// assert_eq!(buckets[4], Duration::from_secs(1));
+/// assert_eq!(buckets.to_string(), "1ms, 5ms, 25ms, 100ms, 1s");
+///
+/// let err = "1ms, 25ms, 5ms".parse::<Buckets>().unwrap_err();
+/// assert_eq!(err, InvalidBuckets::NotIncreasing(2));
+/// ```
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Buckets(Vec<Duration>);
+
+impl Buckets {
+    /// Checks that `bounds` is a valid list of buckets.
+    pub fn new(bounds: Vec<Duration>) -> Result<Buckets, InvalidBuckets> {
+        if bounds.is_empty() {
+            return Err(InvalidBuckets::Empty);
+        }
+        match bounds.windows(2).position(|w| w[0] >= w[1]) {
+            None => Ok(Buckets(bounds)),
+            Some(i) => Err(InvalidBuckets::NotIncreasing(i + 1)),
+        }
+    }
+
+    /// The bucket a value falls in: the index of the first bound at or
+    /// above it, or the number of buckets if it is above all of them.
+    pub fn bucket(&self, d: Duration) -> usize {
+        self.0.partition_point(|&bound| bound < d)
+    }
+
+    /// Consumes the `Buckets`, returning the bounds.
+    pub fn into_inner(self) -> Vec<Duration> {
+        self.0
+    }
+}
+
+/// The error returned when parsing [`Buckets`] fails.
+#[derive(Clone, Debug, PartialEq)]
+pub enum InvalidBuckets {
+    /// There are no buckets.
+    Empty,
+    /// The bucket at the index is not a valid duration.
+    Bucket(usize, humantime::DurationError),
+    /// The bucket at the index is not above the one before it.
+    NotIncreasing(usize),
+}
+
+impl fmt::Display for InvalidBuckets {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            InvalidBuckets::Empty => f.write_str("no buckets"),
+            InvalidBuckets::Bucket(i, e) => write!(f, "invalid bucket {}: {}", i, e),
+            InvalidBuckets::NotIncreasing(i) => {
+                write!(f, "bucket {} is not above the one before it", i)
+            }
+        }
+    }
+}
+
+impl core::error::Error for InvalidBuckets {}
+
+impl HumantimeFormat for Buckets {
+    const EXPECTING: &'static str = "a list of histogram buckets";
+
+    type Err = InvalidBuckets;
+
+    fn parse(s: &str) -> Result<Buckets, InvalidBuckets> {
+        if s.trim().is_empty() {
+            return Err(InvalidBuckets::Empty);
+        }
+        let bounds = s
+            .split(',')
+            .enumerate()
+            .map(|(i, bound)| Duration::parse(bound.trim()).map_err(|e| InvalidBuckets::Bucket(i, e)))
+            .collect::<Result<Vec<_>, _>>()?;
+        Buckets::new(bounds)
+    }
+
+    fn format(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, bound) in self.0.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            fmt::Display::fmt(&Ref(bound), f)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Buckets {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.format(f)
+    }
+}
+
+impl FromStr for Buckets {
+    type Err = InvalidBuckets;
+
+    fn from_str(s: &str) -> Result<Buckets, InvalidBuckets> {
+        Buckets::parse(s)
+    }
+}
+
+impl Deref for Buckets {
+    type Target = [Duration];
+
+    fn deref(&self) -> &[Duration] {
+        &self.0
+    }
+}
+
+impl From<Buckets> for Vec<Duration> {
+    fn from(b: Buckets) -> Vec<Duration> {
+        b.0
+    }
+}
+
+impl Serialize for Buckets {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        Ref(self).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Buckets {
+    fn deserialize<D>(d: D) -> Result<Buckets, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Serde::deserialize(d).map(Serde::into_inner)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloc::string::ToString;
+    use alloc::vec;
+
+    #[test]
+    fn parse() {
+        let ms = Duration::from_millis;
+        let b: Buckets = " 1ms,5ms , 1s ".parse().unwrap();
+        assert_eq!(*b, [ms(1), ms(5), ms(1000)]);
+        assert_eq!(b.to_string(), "1ms, 5ms, 1s");
+        assert_eq!("".parse::<Buckets>(), Err(InvalidBuckets::Empty));
+        assert_eq!("1ms, 1ms".parse::<Buckets>(), Err(InvalidBuckets::NotIncreasing(1)));
+        assert!(matches!("1ms, 5x".parse::<Buckets>(), Err(InvalidBuckets::Bucket(1, _))));
+        assert!(matches!("1ms,".parse::<Buckets>(), Err(InvalidBuckets::Bucket(1, _))));
+        assert_eq!(Buckets::new(vec![]), Err(InvalidBuckets::Empty));
+    }
+
+    #[test]
+    fn bucket() {
+        let b: Buckets = "10ms, 100ms".parse().unwrap();
+        assert_eq!(b.bucket(Duration::ZERO), 0);
+        assert_eq!(b.bucket(Duration::from_millis(10)), 0);
+        assert_eq!(b.bucket(Duration::from_millis(11)), 1);
+        assert_eq!(b.bucket(Duration::from_secs(1)), 2);
+    }
+
+    #[test]
+    fn serde() {
+        let b: Buckets = serde_json::from_str("\"5ms, 1m 30s\"").unwrap();
+        assert_eq!(serde_json::to_string(&b).unwrap(), "\"5ms, 1m 30s\"");
+        let err = serde_json::from_str::<Buckets>("\"1s, 1ms\"").unwrap_err();
+        assert!(err.to_string().contains("bucket 1 is not above the one before it"));
+    }
+}
@@ -0,0 +1,351 @@
+//! Timestamp formats rejecting values on the wrong side of the current time.
+//!
+//! Fields such as `expires_at` must lie in the future and fields such as
+//! `recorded_at` in the past; the modules here reject the other direction
+//! on deserialization. Serialization is unchanged.
+//!
+//! The current time comes from a [`Clock`]. With the `std` feature, the
+//! [`future`] and [`past`] modules use the system clock. Other clocks, such
+//! as a fixed one in tests, are plugged in with [`must_be_future!`] and
+//! [`must_be_past!`].
+//!
+//! Fields such as `start_at` may instead be given relative to the current
+//! time. [`relative_to!`] generates a module reading either a timestamp or
+//! an expression such as `now + 2h`, in the syntax of
+//! [`parse_timestamp_flexible`](crate::parse_timestamp_flexible), into a
+//! `DateTime<Utc>`. With the `std` feature, the [`relative`] module uses
+//! the system clock. Both need the `alloc` feature.
+//!
+//! # Example
+//!
+//! ```
+//! use chrono::{DateTime, TimeZone, Utc};
+//! use serde::{Serialize, Deserialize};
+//! use humantime_serde::clock::Clock;
+//!
+//! struct Fixed;
+//!
+//! impl Clock for Fixed {
+//!     fn now() -> DateTime<Utc> {
+//!         Utc.with_ymd_and_hms(2020, 1, 1, 0, 0, 0).unwrap()
+//!     }
+//! }
+//!
+//! humantime_serde::must_be_future!(mod expiry: Fixed);
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Token {
+//!     #[serde(with = "expiry")]
+//!     expires_at: DateTime<Utc>,
+//! }
+//!
+//! let json = r#"{"expires_at": "2018-05-11T18:28:30Z"}"#;
+//! let err = serde_json::from_str::<Token>(json).unwrap_err();
+//! assert!(err.to_string().starts_with("2018-05-11T18:28:30Z is in the past"));
+//! ```
+//!
+//! [`must_be_future!`]: crate::must_be_future
+//! [`must_be_past!`]: crate::must_be_past
+//! [`relative_to!`]: crate::relative_to
+
+#[cfg(feature = "alloc")]
+use core::fmt;
+
+use chrono::{DateTime, Utc};
+use serde::{de, Deserialize, Deserializer};
+
+use super::format::{HumantimeFormat, Ref};
+use super::Serde;
+
+/// A source of the current time.
+pub trait Clock {
+    /// Returns the current time.
+    fn now() -> DateTime<Utc>;
+}
+
+/// The system clock.
+#[cfg(feature = "std")]
+#[derive(Copy, Clone, Debug, Default)]
+pub struct SystemClock;
+
+#[cfg(feature = "std")]
+impl Clock for SystemClock {
+    fn now() -> DateTime<Utc> {
+        std::time::SystemTime::now().into()
+    }
+}
+
+/// Deserializes a `DateTime<Tz>`, rejecting it unless it is after
+/// `C::now()`.
+pub fn deserialize_future<'a, C, T, D>(d: D) -> Result<T, D::Error>
+where
+    C: Clock,
+    T: HumantimeFormat + PartialOrd<DateTime<Utc>>,
+    D: Deserializer<'a>,
+{
+    let v = Serde::<T>::deserialize(d)?.into_inner();
+    if v > C::now() {
+        Ok(v)
+    } else {
+        Err(de::Error::custom(format_args!(
+            "{} is in the past, expected a future timestamp",
+            Ref(&v),
+        )))
+    }
+}
+
+/// Deserializes a `DateTime<Tz>`, rejecting it if it is after `C::now()`.
+pub fn deserialize_past<'a, C, T, D>(d: D) -> Result<T, D::Error>
+where
+    C: Clock,
+    T: HumantimeFormat + PartialOrd<DateTime<Utc>>,
+    D: Deserializer<'a>,
+{
+    let v = Serde::<T>::deserialize(d)?.into_inner();
+    if v <= C::now() {
+        Ok(v)
+    } else {
+        Err(de::Error::custom(format_args!(
+            "{} is in the future, expected a past timestamp",
+            Ref(&v),
+        )))
+    }
+}
+
+/// Deserializes a `DateTime<Utc>` from a timestamp or an expression
+/// relative to `C::now()`, such as `now + 2h`.
+#[cfg(feature = "alloc")]
+pub fn deserialize_relative<'a, C, D>(d: D) -> Result<DateTime<Utc>, D::Error>
+where
+    C: Clock,
+    D: Deserializer<'a>,
+{
+    struct V<C>(core::marker::PhantomData<C>);
+
+    impl<C> de::Visitor<'_> for V<C>
+    where
+        C: Clock,
+    {
+        type Value = DateTime<Utc>;
+
+        fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+            fmt.write_str("a timestamp or a time relative to now")
+        }
+
+        fn visit_str<E>(self, v: &str) -> Result<DateTime<Utc>, E>
+        where
+            E: de::Error,
+        {
+            crate::check_len(v, crate::DEFAULT_MAX_LEN, "a timestamp or a time relative to now")?;
+            crate::lenient::evaluate(v, || Some(C::now()))
+                .map(|t| t.with_timezone(&Utc))
+                .map_err(|err| {
+                    E::invalid_value(de::Unexpected::Str(v), &crate::Because(&self, &err))
+                })
+        }
+    }
+
+    d.deserialize_str(V::<C>(core::marker::PhantomData))
+}
+
+/// Generates a module for `#[serde(with = "...")]` rejecting timestamps
+/// that are not after the given clock's current time.
+///
+/// The generated module has an `option` submodule for `Option` fields.
+///
+/// See the [`clock`](mod@crate::clock) module for details.
+#[macro_export]
+macro_rules! must_be_future {
+    (@module [$($attr:tt)*] $vis:vis $name:ident $deserialize:expr) => {
+        $($attr)*
+        $vis mod $name {
+            // Resolves the clock type from where the macro is invoked.
+            #[allow(unused_imports)]
+            use super::*;
+
+            /// Serializes a `DateTime<Tz>`.
+            pub fn serialize<T, S>(d: &T, s: S) -> ::core::result::Result<S::Ok, S::Error>
+            where
+                T: $crate::format::HumantimeFormat,
+                S: $crate::re::serde::Serializer,
+            {
+                $crate::serialize(d, s)
+            }
+
+            /// Deserializes a `DateTime<Tz>`, checking it against the clock.
+            pub fn deserialize<'a, T, D>(d: D) -> ::core::result::Result<T, D::Error>
+            where
+                T: $crate::format::HumantimeFormat
+                    + ::core::cmp::PartialOrd<$crate::re::chrono::DateTime<$crate::re::chrono::Utc>>,
+                D: $crate::re::serde::Deserializer<'a>,
+            {
+                $deserialize(d)
+            }
+
+            $crate::__option_module!([T] T, [
+                T: $crate::format::HumantimeFormat
+                    + ::core::cmp::PartialOrd<$crate::re::chrono::DateTime<$crate::re::chrono::Utc>>,
+            ]);
+        }
+    };
+    ($(#[$attr:meta])* $vis:vis mod $name:ident : $clock:ty) => {
+        $crate::must_be_future!(@module [$(#[$attr])*] $vis $name
+            $crate::clock::deserialize_future::<$clock, T, D>);
+    };
+}
+
+/// Generates a module for `#[serde(with = "...")]` rejecting timestamps
+/// that are after the given clock's current time.
+///
+/// The generated module has an `option` submodule for `Option` fields.
+///
+/// See the [`clock`](mod@crate::clock) module for details.
+#[macro_export]
+macro_rules! must_be_past {
+    ($(#[$attr:meta])* $vis:vis mod $name:ident : $clock:ty) => {
+        $crate::must_be_future!(@module [$(#[$attr])*] $vis $name
+            $crate::clock::deserialize_past::<$clock, T, D>);
+    };
+}
+
+/// Generates a module for `#[serde(with = "...")]` reading a `DateTime<Utc>`
+/// from a timestamp or an expression relative to the given clock's current
+/// time, such as `now + 2h`. Timestamps are written in RFC 3339 syntax.
+///
+/// The generated module has an `option` submodule for `Option` fields.
+///
+/// See the [`clock`](mod@crate::clock) module for details.
+#[cfg(feature = "alloc")]
+#[macro_export]
+macro_rules! relative_to {
+    ($(#[$attr:meta])* $vis:vis mod $name:ident : $clock:ty) => {
+        $(#[$attr])*
+        $vis mod $name {
+            // Resolves the clock type from where the macro is invoked.
+            #[allow(unused_imports)]
+            use super::*;
+
+            /// Serializes a `DateTime<Utc>`.
+            pub fn serialize<S>(
+                d: &$crate::re::chrono::DateTime<$crate::re::chrono::Utc>,
+                s: S,
+            ) -> ::core::result::Result<S::Ok, S::Error>
+            where
+                S: $crate::re::serde::Serializer,
+            {
+                $crate::serialize(d, s)
+            }
+
+            /// Deserializes a `DateTime<Utc>`, resolving relative times
+            /// against the clock.
+            pub fn deserialize<'a, D>(
+                d: D,
+            ) -> ::core::result::Result<$crate::re::chrono::DateTime<$crate::re::chrono::Utc>, D::Error>
+            where
+                D: $crate::re::serde::Deserializer<'a>,
+            {
+                $crate::clock::deserialize_relative::<$clock, D>(d)
+            }
+
+            $crate::__option_module!([] $crate::re::chrono::DateTime<$crate::re::chrono::Utc>, []);
+        }
+    };
+}
+
+#[cfg(feature = "std")]
+crate::relative_to!(
+    /// Reads timestamps or times relative to the system clock.
+    pub mod relative: SystemClock
+);
+
+#[cfg(feature = "std")]
+crate::must_be_future!(
+    /// Rejects timestamps that are not in the future according to the
+    /// system clock.
+    pub mod future: SystemClock
+);
+
+#[cfg(feature = "std")]
+crate::must_be_past!(
+    /// Rejects timestamps that are in the future according to the system
+    /// clock.
+    pub mod past: SystemClock
+);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloc::string::ToString;
+    use chrono::{FixedOffset, TimeZone};
+    use serde::Serialize;
+
+    struct Fixed;
+
+    impl Clock for Fixed {
+        fn now() -> DateTime<Utc> {
+            Utc.with_ymd_and_hms(2020, 1, 1, 0, 0, 0).unwrap()
+        }
+    }
+
+    crate::must_be_future!(mod expiry: Fixed);
+    crate::must_be_past!(mod recorded: Fixed);
+
+    #[derive(Serialize, Deserialize)]
+    struct Foo {
+        #[serde(with = "expiry")]
+        expires_at: DateTime<Utc>,
+        #[serde(with = "recorded")]
+        recorded_at: DateTime<FixedOffset>,
+    }
+
+    #[test]
+    fn accepts() {
+        let json = r#"{"expires_at":"2021-01-01T00:00:00Z","recorded_at":"2020-01-01T02:00:00+02:00"}"#;
+        let foo: Foo = serde_json::from_str(json).unwrap();
+        assert_eq!(foo.expires_at, Utc.with_ymd_and_hms(2021, 1, 1, 0, 0, 0).unwrap());
+        assert_eq!(serde_json::to_string(&foo).unwrap(), json);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn relative() {
+        crate::relative_to!(mod start: Fixed);
+
+        #[derive(Serialize, Deserialize)]
+        struct Job {
+            #[serde(with = "start")]
+            start_at: DateTime<Utc>,
+            #[serde(with = "start::option", default)]
+            stop_at: Option<DateTime<Utc>>,
+        }
+
+        let job: Job = serde_json::from_str(r#"{"start_at": "now + 2h"}"#).unwrap();
+        assert_eq!(job.start_at, Utc.with_ymd_and_hms(2020, 1, 1, 2, 0, 0).unwrap());
+        assert_eq!(job.stop_at, None);
+        let json = r#"{"start_at":"2020-01-01T02:00:00Z","stop_at":null}"#;
+        assert_eq!(serde_json::to_string(&job).unwrap(), json);
+
+        let json = r#"{"start_at": "2021-01-01T01:00:00+01:00", "stop_at": "now - 1d"}"#;
+        let job: Job = serde_json::from_str(json).unwrap();
+        assert_eq!(job.start_at, Utc.with_ymd_and_hms(2021, 1, 1, 0, 0, 0).unwrap());
+        assert_eq!(job.stop_at, Some(Utc.with_ymd_and_hms(2019, 12, 31, 0, 0, 0).unwrap()));
+
+        let err = serde_json::from_str::<Job>(r#"{"start_at": "tomorrow"}"#).unwrap_err();
+        assert!(err.to_string().contains("expected a timestamp or a time relative to now"));
+    }
+
+    #[test]
+    fn rejects() {
+        let json = r#"{"expires_at":"2020-01-01T00:00:00Z","recorded_at":"2019-01-01T00:00:00Z"}"#;
+        let err = serde_json::from_str::<Foo>(json).unwrap_err();
+        assert!(err.to_string().starts_with(
+            "2020-01-01T00:00:00Z is in the past, expected a future timestamp"
+        ));
+
+        let json = r#"{"expires_at":"2021-01-01T00:00:00Z","recorded_at":"2020-01-01T00:00:01Z"}"#;
+        let err = serde_json::from_str::<Foo>(json).unwrap_err();
+        assert!(err.to_string().starts_with(
+            "2020-01-01T00:00:01Z is in the future, expected a past timestamp"
+        ));
+    }
+}
@@ -0,0 +1,251 @@
+//! Helpers for moving existing data over to humantime strings.
+//!
+//! [`numeric_migrate`] reads both humantime strings and the legacy
+//! representation as a whole number of seconds: seconds for durations and
+//! seconds since the Unix epoch for timestamps. It writes humantime
+//! strings, while [`numeric_migrate::write_legacy`] keeps writing numbers.
+//! Readers can switch first, and writers once every reader is upgraded.
+//!
+//! Telling a string from a number needs a self-describing format such as
+//! JSON, YAML or TOML. Formats that rely on the type to know what comes
+//! next, such as bincode or postcard, cannot read these fields.
+//!
+//! # Example
+//!
+//! ```
+//! use core::time::Duration;
+//! use serde::{Serialize, Deserialize};
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Old {
+//!     #[serde(with = "humantime_serde::compat::numeric_migrate::write_legacy")]
+//!     timeout: Duration,
+//! }
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct New {
+//!     #[serde(with = "humantime_serde::compat::numeric_migrate")]
+//!     timeout: Duration,
+//! }
+//!
+//! let old: Old = serde_json::from_str(r#"{"timeout": "2m"}"#).unwrap();
+//! assert_eq!(serde_json::to_string(&old).unwrap(), r#"{"timeout":120}"#);
+//!
+//! let new: New = serde_json::from_str(r#"{"timeout": 120}"#).unwrap();
+//! assert_eq!(serde_json::to_string(&new).unwrap(), r#"{"timeout":"2m"}"#);
+//! ```
+
+use core::convert::TryFrom;
+use core::fmt;
+use core::marker::PhantomData;
+use core::time::Duration;
+
+#[cfg(feature = "chrono")]
+use chrono::{DateTime, Utc};
+use serde::{de, ser, Deserializer, Serializer};
+
+use super::format::HumantimeFormat;
+
+/// A type with a legacy representation as a whole number of seconds.
+pub trait LegacySeconds: HumantimeFormat {
+    /// Converts from the legacy representation, or `None` if out of range.
+    fn from_secs(secs: i64) -> Option<Self>;
+
+    /// Converts to the legacy representation, or `None` if the value is not
+    /// a whole number of seconds or out of range.
+    fn to_secs(&self) -> Option<i64>;
+}
+
+impl LegacySeconds for Duration {
+    fn from_secs(secs: i64) -> Option<Duration> {
+        u64::try_from(secs).ok().map(Duration::from_secs)
+    }
+
+    fn to_secs(&self) -> Option<i64> {
+        if self.subsec_nanos() != 0 {
+            return None;
+        }
+        i64::try_from(self.as_secs()).ok()
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl LegacySeconds for DateTime<Utc> {
+    fn from_secs(secs: i64) -> Option<DateTime<Utc>> {
+        DateTime::from_timestamp(secs, 0)
+    }
+
+    fn to_secs(&self) -> Option<i64> {
+        if self.timestamp_subsec_nanos() != 0 {
+            return None;
+        }
+        Some(self.timestamp())
+    }
+}
+
+/// Reads humantime strings or legacy numbers, writes humantime strings.
+pub mod numeric_migrate {
+    use super::*;
+
+    /// Serializes as a humantime string.
+    pub fn serialize<T, S>(d: &T, s: S) -> Result<S::Ok, S::Error>
+    where
+        T: LegacySeconds,
+        S: Serializer,
+    {
+        crate::serialize(d, s)
+    }
+
+    /// Deserializes from a humantime string or a legacy number of seconds.
+    ///
+    /// This goes through `deserialize_any`, so it fails with formats that
+    /// are not self-describing, such as bincode or postcard.
+    pub fn deserialize<'a, T, D>(d: D) -> Result<T, D::Error>
+    where
+        T: LegacySeconds,
+        D: Deserializer<'a>,
+    {
+        struct V<T>(PhantomData<T>);
+
+        impl<T> de::Visitor<'_> for V<T>
+        where
+            T: LegacySeconds,
+        {
+            type Value = T;
+
+            fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+                write!(fmt, "{} or a number of seconds", T::EXPECTING)
+            }
+
+            fn visit_str<E>(self, v: &str) -> Result<T, E>
+            where
+                E: de::Error,
+            {
+                crate::parse_str(v, &self)
+            }
+
+            fn visit_i64<E>(self, v: i64) -> Result<T, E>
+            where
+                E: de::Error,
+            {
+                T::from_secs(v).ok_or_else(|| E::invalid_value(de::Unexpected::Signed(v), &self))
+            }
+
+            fn visit_u64<E>(self, v: u64) -> Result<T, E>
+            where
+                E: de::Error,
+            {
+                i64::try_from(v)
+                    .ok()
+                    .and_then(T::from_secs)
+                    .ok_or_else(|| E::invalid_value(de::Unexpected::Unsigned(v), &self))
+            }
+        }
+
+        d.deserialize_any(V(PhantomData))
+    }
+
+    crate::__option_module!([T] T, [T: crate::compat::LegacySeconds]);
+
+    /// Reads humantime strings or legacy numbers, keeps writing legacy
+    /// numbers.
+    pub mod write_legacy {
+        use super::*;
+
+        pub use super::deserialize;
+
+        /// Serializes as a legacy number of seconds, failing if the value
+        /// has a fractional second.
+        pub fn serialize<T, S>(d: &T, s: S) -> Result<S::Ok, S::Error>
+        where
+            T: LegacySeconds,
+            S: Serializer,
+        {
+            match d.to_secs() {
+                Some(secs) => s.serialize_i64(secs),
+                None => Err(ser::Error::custom(format_args!(
+                    "{} is not a whole number of seconds",
+                    crate::format::Ref(d),
+                ))),
+            }
+        }
+
+        crate::__option_module!([T] T, [T: crate::compat::LegacySeconds]);
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod test {
+    use super::*;
+    use alloc::string::ToString;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Legacy {
+        #[serde(with = "numeric_migrate::write_legacy")]
+        timeout: Duration,
+    }
+
+    #[test]
+    fn read_both() {
+        let a: Legacy = serde_json::from_str(r#"{"timeout": 90}"#).unwrap();
+        let b: Legacy = serde_json::from_str(r#"{"timeout": "90s"}"#).unwrap();
+        assert_eq!(a, b);
+        assert_eq!(serde_json::to_string(&a).unwrap(), r#"{"timeout":90}"#);
+
+        let err = serde_json::from_str::<Legacy>(r#"{"timeout": -1}"#).unwrap_err();
+        assert!(err.to_string().contains("expected a duration or a number of seconds"));
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn read_both_timestamps() {
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct Legacy {
+            #[serde(with = "numeric_migrate::write_legacy")]
+            since: DateTime<Utc>,
+        }
+
+        let a: Legacy = serde_json::from_str(r#"{"since": 1526063310}"#).unwrap();
+        let b: Legacy = serde_json::from_str(r#"{"since": "2018-05-11T18:28:30Z"}"#).unwrap();
+        assert_eq!(a, b);
+        assert_eq!(serde_json::to_string(&a).unwrap(), r#"{"since":1526063310}"#);
+    }
+
+    #[test]
+    fn string_errors() {
+        let json = r#"{"timeout": "2 secnds"}"#;
+        let err = serde_json::from_str::<Legacy>(json).unwrap_err();
+        assert!(err.to_string().contains("did you mean \"seconds\"?"));
+
+        let json = r#"{"timeout": "99999999999999999999y"}"#;
+        let err = serde_json::from_str::<Legacy>(json).unwrap_err();
+        assert!(err.to_string().contains("is too large for a duration"));
+
+        let long = "1s ".repeat(200);
+        let json = alloc::format!(r#"{{"timeout": "{}"}}"#, long);
+        let err = serde_json::from_str::<Legacy>(&json).unwrap_err();
+        assert!(err.to_string().contains("is too long for a duration"));
+    }
+
+    #[test]
+    fn option() {
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct Foo {
+            #[serde(with = "numeric_migrate::option", default)]
+            timeout: Option<Duration>,
+        }
+
+        let foo: Foo = serde_json::from_str(r#"{"timeout": 90}"#).unwrap();
+        assert_eq!(foo.timeout, Some(Duration::from_secs(90)));
+        assert_eq!(serde_json::to_string(&foo).unwrap(), r#"{"timeout":"1m 30s"}"#);
+        assert_eq!(serde_json::from_str::<Foo>("{}").unwrap(), Foo { timeout: None });
+    }
+
+    #[test]
+    fn fractional() {
+        let legacy = Legacy { timeout: Duration::from_millis(1500) };
+        let err = serde_json::to_string(&legacy).unwrap_err();
+        assert_eq!(err.to_string(), "1s 500ms is not a whole number of seconds");
+    }
+}
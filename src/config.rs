@@ -0,0 +1,186 @@
+//! Process-wide defaults for the timestamp format.
+//!
+//! [`set_defaults`] changes how timestamps are read and written wherever
+//! this crate uses its default format: the crate-level [`serialize`] and
+//! [`deserialize`], the [`Serde`] wrapper, and the modules built on them
+//! such as [`option`] and [`vec`]. A codebase can then change its output
+//! precision in one place instead of in every `#[serde(with)]` attribute.
+//! Formats spelled out with [`custom_format!`] are not affected.
+//!
+//! Set the defaults once, at startup, before any timestamp is read or
+//! written: changing them while other threads serialize is safe, but which
+//! of the two formats those threads use is not specified. Writing with
+//! anything other than [`FormatOptions::DEFAULT`] allocates a `String` per
+//! timestamp.
+//!
+//! # Example
+//!
+//! ```
+//! use chrono::{DateTime, SecondsFormat, Utc};
+//! use humantime_serde::config::{self, FormatOptions};
+//! use serde::{Serialize, Deserialize};
+//!
+//! config::set_defaults(FormatOptions {
+//!     seconds_format: SecondsFormat::Millis,
+//!     use_z: false,
+//!     ..FormatOptions::DEFAULT
+//! });
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Foo {
+//!     #[serde(with = "humantime_serde")]
+//!     time: DateTime<Utc>,
+//! }
+//!
+//! let foo: Foo = serde_json::from_str(r#"{"time": "2018-05-11T18:28:30Z"}"#).unwrap();
+//! let json = serde_json::to_string(&foo).unwrap();
+//! assert_eq!(json, r#"{"time":"2018-05-11T18:28:30.000+00:00"}"#);
+//! ```
+//!
+//! [`serialize`]: crate::serialize
+//! [`deserialize`]: crate::deserialize
+//! [`Serde`]: crate::Serde
+//! [`option`]: crate::option
+//! [`vec`]: crate::vec
+//! [`custom_format!`]: crate::custom_format
+
+use alloc::string::String;
+use core::fmt;
+use core::sync::atomic::{AtomicU8, Ordering};
+
+use chrono::{DateTime, FixedOffset, SecondsFormat, TimeZone};
+
+use crate::backend::{Backend, Selected};
+
+/// The options consulted by the default format.
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub struct FormatOptions {
+    /// The number of fractional digits written.
+    pub seconds_format: SecondsFormat,
+    /// Whether a zero offset is written as `Z` rather than `+00:00`.
+    pub use_z: bool,
+    /// Whether timestamps that aren't strictly RFC 3339 are accepted, as
+    /// [`parse_timestamp_flexible`](crate::parse_timestamp_flexible) does,
+    /// but without `now` or added durations, so a value reads the same
+    /// whenever it is read.
+    pub lenient: bool,
+}
+
+impl FormatOptions {
+    /// The options the crate starts with: whole seconds, `Z` for UTC and
+    /// strict RFC 3339.
+    pub const DEFAULT: FormatOptions = FormatOptions {
+        seconds_format: SecondsFormat::Secs,
+        use_z: true,
+        lenient: false,
+    };
+}
+
+impl Default for FormatOptions {
+    fn default() -> FormatOptions {
+        FormatOptions::DEFAULT
+    }
+}
+
+/// The seconds formats by their index in the low bits of `DEFAULTS`.
+const SECONDS_FORMATS: [SecondsFormat; 5] = [
+    SecondsFormat::Secs,
+    SecondsFormat::Millis,
+    SecondsFormat::Micros,
+    SecondsFormat::Nanos,
+    SecondsFormat::AutoSi,
+];
+const USE_Z: u8 = 1 << 3;
+const LENIENT: u8 = 1 << 4;
+
+/// The current options, packed into one byte so they are read and written
+/// together without a lock.
+static DEFAULTS: AtomicU8 = AtomicU8::new(USE_Z);
+
+fn encode(options: FormatOptions) -> u8 {
+    let format = SECONDS_FORMATS
+        .iter()
+        .position(|&f| f == options.seconds_format)
+        .unwrap_or(SECONDS_FORMATS.len() - 1);
+    let mut bits = format as u8;
+    if options.use_z {
+        bits |= USE_Z;
+    }
+    if options.lenient {
+        bits |= LENIENT;
+    }
+    bits
+}
+
+fn decode(bits: u8) -> FormatOptions {
+    FormatOptions {
+        seconds_format: SECONDS_FORMATS[usize::from(bits & 7)],
+        use_z: bits & USE_Z != 0,
+        lenient: bits & LENIENT != 0,
+    }
+}
+
+/// Sets the options used by the default format from now on.
+pub fn set_defaults(options: FormatOptions) {
+    DEFAULTS.store(encode(options), Ordering::Relaxed);
+}
+
+/// The options currently used by the default format.
+pub fn defaults() -> FormatOptions {
+    decode(DEFAULTS.load(Ordering::Relaxed))
+}
+
+/// Writes `t` in the default format.
+pub(crate) fn format<Tz>(t: &DateTime<Tz>, f: &mut fmt::Formatter) -> fmt::Result
+where
+    Tz: TimeZone,
+{
+    let options = defaults();
+    if options == FormatOptions::DEFAULT {
+        return Selected::format_timestamp(t, f);
+    }
+    let s: String = t.fixed_offset().to_rfc3339_opts(options.seconds_format, options.use_z);
+    f.write_str(&s)
+}
+
+/// Parses `s` in the default format, keeping the RFC 3339 error if a
+/// lenient parse fails too.
+pub(crate) fn parse(s: &str) -> Result<DateTime<FixedOffset>, chrono::ParseError> {
+    parse_with(s, defaults().lenient)
+}
+
+fn parse_with(s: &str, lenient: bool) -> Result<DateTime<FixedOffset>, chrono::ParseError> {
+    Selected::parse_timestamp(s).or_else(|err| {
+        if lenient {
+            crate::lenient::parse_single(s.trim()).map_err(|_| err)
+        } else {
+            Err(err)
+        }
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn packing() {
+        assert_eq!(decode(encode(FormatOptions::DEFAULT)), FormatOptions::DEFAULT);
+        assert_eq!(decode(DEFAULTS.load(Ordering::Relaxed)), FormatOptions::DEFAULT);
+        for &seconds_format in &SECONDS_FORMATS {
+            for &(use_z, lenient) in &[(false, false), (false, true), (true, false), (true, true)] {
+                let options = FormatOptions { seconds_format, use_z, lenient };
+                assert_eq!(decode(encode(options)), options);
+            }
+        }
+    }
+
+    #[test]
+    fn lenient() {
+        let t = parse_with("2018-05-11 18:28:30", true).unwrap();
+        assert_eq!(t.timestamp(), 1526063310);
+        assert!(parse_with("2018-05-11 18:28:30", false).is_err());
+        assert!(parse_with("now", true).is_err());
+        assert!(parse_with("2018-05-11 18:28:30 + 1h", true).is_err());
+    }
+}
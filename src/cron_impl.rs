@@ -0,0 +1,138 @@
+//! Cron schedules, enabled with the `cron` feature.
+
+use core::fmt;
+use core::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use super::format::{HumantimeFormat, Ref};
+use super::Serde;
+
+/// A cron expression, validated when deserialized and serialized back as
+/// written.
+///
+/// Expressions use the syntax of the `cron` crate, which starts with a
+/// seconds field.
+///
+/// # Example
+///
+/// ```
+/// use chrono::{TimeZone, Utc};
+/// use humantime_serde::Schedule;
+///
+/// let schedule: Schedule = serde_json::from_str(r#""0 30 9 * * Mon-Fri""#).unwrap();
+/// let t = Utc.with_ymd_and_hms(2024, 1, 6, 12, 0, 0).unwrap();
+/// assert_eq!(
+///     schedule.next_after(t),
+///     Some(Utc.with_ymd_and_hms(2024, 1, 8, 9, 30, 0).unwrap()),
+/// );
+/// assert!(serde_json::from_str::<Schedule>(r#""every day""#).is_err());
+/// ```
+#[derive(Clone, Debug)]
+pub struct Schedule(cron::Schedule);
+
+impl Schedule {
+    /// Returns the first time after `t` matching the schedule.
+    pub fn next_after(&self, t: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        self.0.after(&t).next()
+    }
+
+    /// Consumes the `Schedule`, returning the inner `cron::Schedule`.
+    pub fn into_inner(self) -> cron::Schedule {
+        self.0
+    }
+}
+
+impl PartialEq for Schedule {
+    fn eq(&self, other: &Schedule) -> bool {
+        self.0.source() == other.0.source()
+    }
+}
+
+impl Eq for Schedule {}
+
+impl AsRef<cron::Schedule> for Schedule {
+    fn as_ref(&self) -> &cron::Schedule {
+        &self.0
+    }
+}
+
+impl From<cron::Schedule> for Schedule {
+    fn from(s: cron::Schedule) -> Schedule {
+        Schedule(s)
+    }
+}
+
+impl HumantimeFormat for Schedule {
+    const EXPECTING: &'static str = "a cron expression";
+
+    type Err = cron::error::Error;
+
+    fn parse(s: &str) -> Result<Schedule, cron::error::Error> {
+        cron::Schedule::from_str(s).map(Schedule)
+    }
+
+    fn format(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.0.source())
+    }
+}
+
+impl fmt::Display for Schedule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.format(f)
+    }
+}
+
+impl FromStr for Schedule {
+    type Err = cron::error::Error;
+
+    fn from_str(s: &str) -> Result<Schedule, cron::error::Error> {
+        Schedule::parse(s)
+    }
+}
+
+impl Serialize for Schedule {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        Ref(self).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Schedule {
+    fn deserialize<D>(d: D) -> Result<Schedule, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Serde::deserialize(d).map(Serde::into_inner)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloc::string::ToString;
+    use chrono::TimeZone;
+
+    #[test]
+    fn serde() {
+        #[derive(Serialize, Deserialize)]
+        struct Job {
+            schedule: Schedule,
+            #[serde(with = "crate::option", default)]
+            fallback: Option<Schedule>,
+        }
+
+        let json = r#"{"schedule":"0 0 * * * *","fallback":null}"#;
+        let job: Job = serde_json::from_str(json).unwrap();
+        assert_eq!(serde_json::to_string(&job).unwrap(), json);
+
+        let t = Utc.with_ymd_and_hms(2024, 1, 1, 10, 15, 0).unwrap();
+        assert_eq!(job.schedule.next_after(t), Some(Utc.with_ymd_and_hms(2024, 1, 1, 11, 0, 0).unwrap()));
+
+        let err = serde_json::from_str::<Schedule>(r#""61 * * * * *""#).unwrap_err();
+        assert!(err.to_string().contains("expected a cron expression"));
+    }
+}
@@ -0,0 +1,511 @@
+//! Timestamp formats configured at compile time with [`custom_format!`].
+//!
+//! The macro generates a module usable with `#[serde(with = "...")]` from a
+//! list of options, all of which are optional:
+//!
+//! * `precision`: the number of fractional digits written, one of the
+//!   [`Precision`] variants. Defaults to `Secs`.
+//! * `offset`: whether the offset is kept or converted to UTC when writing,
+//!   one of the [`Offset`] variants. Defaults to `Utc`.
+//! * `lenient`: when `true`, also accepts timestamps using a space instead
+//!   of `T`, offsets without a colon, and timestamps without any offset,
+//!   which are taken as UTC. Unlike
+//!   [`parse_timestamp_flexible`](crate::parse_timestamp_flexible), it
+//!   accepts neither `now` nor added durations; see
+//!   [`relative_to!`](crate::relative_to) for those. Defaults to `false`.
+//! * `max_len`: the longest input accepted, in bytes. Defaults to
+//!   [`DEFAULT_MAX_LEN`](crate::DEFAULT_MAX_LEN).
+//! * `leap_second`: what happens to timestamps on a leap second, one of the
+//!   [`LeapSecond`] variants. Defaults to `Keep`.
+//! * `abbreviations`: when `true`, also accepts a time zone abbreviation
+//!   such as `EST` in place of the offset, as
+//!   [`parse_timestamp_abbreviated`](crate::parse_timestamp_abbreviated)
+//!   does. Defaults to `false`.
+//! * `checked`: when `true`, serialization fails instead of truncating a
+//!   timestamp with more fractional digits than `precision` writes.
+//!   Defaults to `false`.
+//!
+//! # Example
+//!
+//! ```
+//! use serde::{Serialize, Deserialize};
+//! use chrono::{DateTime, FixedOffset};
+//!
+//! humantime_serde::custom_format!(pub mod my_ts {
+//!     precision: Millis,
+//!     offset: Preserve,
+//!     lenient: true,
+//! });
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Foo {
+//!     #[serde(with = "my_ts")]
+//!     time: DateTime<FixedOffset>,
+//! }
+//!
+//! let json = r#"{"time": "2018-05-11 20:28:30+02:00"}"#;
+//! let foo: Foo = serde_json::from_str(json).unwrap();
+//! let reverse = serde_json::to_string(&foo).unwrap();
+//! assert_eq!(reverse, r#"{"time":"2018-05-11T20:28:30.000+02:00"}"#);
+//! ```
+//!
+//! [`custom_format!`]: crate::custom_format
+
+use alloc::string::String;
+use core::fmt;
+
+use chrono::{DateTime, FixedOffset, SecondsFormat, TimeDelta, TimeZone, Timelike, Utc};
+use serde::{de, ser, Deserializer, Serialize, Serializer};
+
+/// The number of fractional digits written.
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub enum Precision {
+    /// No fractional seconds.
+    Secs,
+    /// Three digits.
+    Millis,
+    /// Six digits.
+    Micros,
+    /// Nine digits.
+    Nanos,
+    /// As many digits as needed, in groups of three.
+    Auto,
+}
+
+impl Precision {
+    fn seconds_format(self) -> SecondsFormat {
+        match self {
+            Precision::Secs => SecondsFormat::Secs,
+            Precision::Millis => SecondsFormat::Millis,
+            Precision::Micros => SecondsFormat::Micros,
+            Precision::Nanos => SecondsFormat::Nanos,
+            Precision::Auto => SecondsFormat::AutoSi,
+        }
+    }
+
+    /// Whether a fraction of `nanos` nanoseconds is written without loss.
+    pub const fn is_exact(self, nanos: u32) -> bool {
+        let nanos = nanos % 1_000_000_000;
+        match self {
+            Precision::Secs => nanos == 0,
+            Precision::Millis => nanos % 1_000_000 == 0,
+            Precision::Micros => nanos % 1_000 == 0,
+            Precision::Nanos | Precision::Auto => true,
+        }
+    }
+}
+
+/// What happens to the offset of a timestamp when it is written.
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub enum Offset {
+    /// The timestamp is converted to UTC and written with a `Z`.
+    Utc,
+    /// The timestamp is written with its own offset.
+    Preserve,
+}
+
+/// What happens to a timestamp on a leap second, such as `23:59:60Z`.
+///
+/// chrono represents a leap second as the second before it with a
+/// nanosecond count of a billion or more, which most code does not expect.
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub enum LeapSecond {
+    /// The timestamp is kept as chrono represents it.
+    Keep,
+    /// The timestamp is rejected.
+    Reject,
+    /// The timestamp is moved to the last nanosecond of second 59.
+    ClampTo59,
+    /// The timestamp is moved into the following second, keeping its
+    /// fraction, so `23:59:60.5` becomes `00:00:00.5`.
+    SmearToNext,
+}
+
+impl LeapSecond {
+    /// Applies the policy to `t`, which is returned as is unless it falls on
+    /// a leap second.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use chrono::DateTime;
+    /// use humantime_serde::custom::LeapSecond;
+    ///
+    /// let t = DateTime::parse_from_rfc3339("2016-12-31T23:59:60.5Z").unwrap();
+    /// let t = LeapSecond::SmearToNext.apply(t).unwrap();
+    /// assert_eq!(t.to_rfc3339(), "2017-01-01T00:00:00.500+00:00");
+    /// assert!(LeapSecond::Reject.apply(t).is_ok());
+    /// ```
+    pub fn apply<Tz>(self, t: DateTime<Tz>) -> Result<DateTime<Tz>, crate::Error>
+    where
+        Tz: TimeZone,
+    {
+        let nanos = t.nanosecond();
+        if nanos < 1_000_000_000 {
+            return Ok(t);
+        }
+        let t = match self {
+            LeapSecond::Keep => Some(t),
+            LeapSecond::Reject => None,
+            LeapSecond::ClampTo59 => t.with_nanosecond(999_999_999),
+            LeapSecond::SmearToNext => t
+                .with_nanosecond(nanos - 1_000_000_000)
+                .and_then(|t| t.checked_add_signed(TimeDelta::seconds(1))),
+        };
+        t.ok_or(crate::Error::LeapSecond)
+    }
+}
+
+/// The options of a custom format.
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Options {
+    /// See [`Precision`].
+    pub precision: Precision,
+    /// See [`Offset`].
+    pub offset: Offset,
+    /// Whether timestamps that aren't strictly RFC 3339 are accepted.
+    pub lenient: bool,
+    /// The longest input accepted, in bytes.
+    pub max_len: usize,
+    /// See [`LeapSecond`].
+    pub leap_second: LeapSecond,
+    /// Whether writing a timestamp more precise than `precision` fails
+    /// rather than truncating it.
+    pub checked: bool,
+    /// Whether time zone abbreviations are accepted.
+    pub abbreviations: bool,
+}
+
+impl Options {
+    /// The options matching the default format of this crate.
+    pub const DEFAULT: Options = Options {
+        precision: Precision::Secs,
+        offset: Offset::Utc,
+        lenient: false,
+        max_len: crate::DEFAULT_MAX_LEN,
+        leap_second: LeapSecond::Keep,
+        checked: false,
+        abbreviations: false,
+    };
+
+    /// Sets the precision.
+    pub const fn precision(self, precision: Precision) -> Options {
+        Options { precision, ..self }
+    }
+
+    /// Sets the offset handling.
+    pub const fn offset(self, offset: Offset) -> Options {
+        Options { offset, ..self }
+    }
+
+    /// Sets the leniency.
+    pub const fn lenient(self, lenient: bool) -> Options {
+        Options { lenient, ..self }
+    }
+
+    /// Sets the longest input accepted.
+    pub const fn max_len(self, max_len: usize) -> Options {
+        Options { max_len, ..self }
+    }
+
+    /// Sets the leap second policy.
+    pub const fn leap_second(self, leap_second: LeapSecond) -> Options {
+        Options { leap_second, ..self }
+    }
+
+    /// Sets whether writing truncates or fails.
+    pub const fn checked(self, checked: bool) -> Options {
+        Options { checked, ..self }
+    }
+
+    /// Sets whether time zone abbreviations are accepted.
+    pub const fn abbreviations(self, abbreviations: bool) -> Options {
+        Options { abbreviations, ..self }
+    }
+
+    /// Formats a timestamp.
+    pub fn format<Tz>(&self, t: &DateTime<Tz>) -> String
+    where
+        Tz: TimeZone,
+    {
+        let precision = self.precision.seconds_format();
+        match self.offset {
+            Offset::Utc => t.with_timezone(&Utc).to_rfc3339_opts(precision, true),
+            Offset::Preserve => t.fixed_offset().to_rfc3339_opts(precision, true),
+        }
+    }
+
+    /// Parses a timestamp.
+    pub fn parse(&self, s: &str) -> Result<DateTime<FixedOffset>, crate::Error> {
+        let t = if self.abbreviations {
+            crate::parse_timestamp_abbreviated(s)?
+        } else if self.lenient {
+            crate::lenient::parse_single(s.trim())?
+        } else {
+            DateTime::parse_from_rfc3339(s)?
+        };
+        self.leap_second.apply(t)
+    }
+}
+
+impl Default for Options {
+    fn default() -> Options {
+        Options::DEFAULT
+    }
+}
+
+/// Serializes a `DateTime<Tz>` according to `options`.
+pub fn serialize<Tz, S>(options: &Options, d: &DateTime<Tz>, s: S) -> Result<S::Ok, S::Error>
+where
+    Tz: TimeZone,
+    S: Serializer,
+{
+    if options.checked && !options.precision.is_exact(d.nanosecond()) {
+        return Err(ser::Error::custom(format_args!(
+            "{} has more fractional digits than {:?} precision writes",
+            d.to_rfc3339_opts(SecondsFormat::AutoSi, true),
+            options.precision,
+        )));
+    }
+    options.format(d).serialize(s)
+}
+
+/// Deserializes a `DateTime<Tz>` according to `options`.
+pub fn deserialize<'a, Tz, D>(options: &Options, d: D) -> Result<DateTime<Tz>, D::Error>
+where
+    Tz: TimeZone,
+    DateTime<Tz>: From<DateTime<FixedOffset>>,
+    D: Deserializer<'a>,
+{
+    struct V<'a>(&'a Options);
+
+    impl<'de> de::Visitor<'de> for V<'_> {
+        type Value = DateTime<FixedOffset>;
+
+        fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+            fmt.write_str("a timestamp")
+        }
+
+        fn visit_str<E>(self, v: &str) -> Result<DateTime<FixedOffset>, E>
+        where
+            E: de::Error,
+        {
+            crate::check_len(v, self.0.max_len, "a timestamp")?;
+            self.0.parse(v).map_err(|err| {
+                E::invalid_value(de::Unexpected::Str(v), &crate::Because(&self, &err))
+            })
+        }
+
+        fn visit_borrowed_str<E>(self, v: &'de str) -> Result<DateTime<FixedOffset>, E>
+        where
+            E: de::Error,
+        {
+            self.visit_str(v)
+        }
+
+        fn visit_string<E>(self, v: String) -> Result<DateTime<FixedOffset>, E>
+        where
+            E: de::Error,
+        {
+            self.visit_str(&v)
+        }
+    }
+
+    d.deserialize_str(V(options)).map(Into::into)
+}
+
+/// Generates a module for `#[serde(with = "...")]` from a list of options.
+///
+/// The generated module has an `option` submodule for `Option` fields.
+///
+/// See the [`custom`](crate::custom) module for the available options.
+#[macro_export]
+macro_rules! custom_format {
+    (
+        $(#[$attr:meta])*
+        $vis:vis mod $name:ident { $($key:ident : $value:tt),* $(,)? }
+    ) => {
+        $(#[$attr])*
+        $vis mod $name {
+            const OPTIONS: $crate::custom::Options = $crate::custom::Options::DEFAULT
+                $(.$key($crate::custom_format!(@value $key $value)))*;
+
+            /// Serializes a `DateTime<Tz>` in this format.
+            pub fn serialize<Tz, S>(
+                d: &$crate::re::chrono::DateTime<Tz>,
+                s: S,
+            ) -> ::core::result::Result<S::Ok, S::Error>
+            where
+                Tz: $crate::re::chrono::TimeZone,
+                S: $crate::re::serde::Serializer,
+            {
+                $crate::custom::serialize(&OPTIONS, d, s)
+            }
+
+            /// Deserializes a `DateTime<Tz>` in this format.
+            pub fn deserialize<'a, Tz, D>(
+                d: D,
+            ) -> ::core::result::Result<$crate::re::chrono::DateTime<Tz>, D::Error>
+            where
+                Tz: $crate::re::chrono::TimeZone,
+                $crate::re::chrono::DateTime<Tz>: ::core::convert::From<
+                    $crate::re::chrono::DateTime<$crate::re::chrono::FixedOffset>,
+                >,
+                D: $crate::re::serde::Deserializer<'a>,
+            {
+                $crate::custom::deserialize(&OPTIONS, d)
+            }
+
+            $crate::__option_module!([Tz] $crate::re::chrono::DateTime<Tz>, [
+                Tz: $crate::re::chrono::TimeZone,
+                $crate::re::chrono::DateTime<Tz>: ::core::convert::From<
+                    $crate::re::chrono::DateTime<$crate::re::chrono::FixedOffset>,
+                >,
+            ]);
+        }
+    };
+    (@value precision $value:ident) => {
+        $crate::custom::Precision::$value
+    };
+    (@value offset $value:ident) => {
+        $crate::custom::Offset::$value
+    };
+    (@value lenient $value:tt) => {
+        $value
+    };
+    (@value max_len $value:tt) => {
+        $value
+    };
+    (@value leap_second $value:ident) => {
+        $crate::custom::LeapSecond::$value
+    };
+    (@value checked $value:tt) => {
+        $value
+    };
+    (@value abbreviations $value:tt) => {
+        $value
+    };
+}
+
+#[cfg(test)]
+mod test {
+    use serde::{Deserialize, Serialize};
+    use chrono::{DateTime, FixedOffset, Utc};
+    use core::time::Duration;
+
+    crate::custom_format!(mod millis_preserve {
+        precision: Millis,
+        offset: Preserve,
+        lenient: true,
+    });
+
+    crate::custom_format!(mod strict {});
+
+    crate::custom_format!(mod abbreviated {
+        abbreviations: true,
+    });
+
+    crate::custom_format!(mod exact_millis {
+        precision: Millis,
+        checked: true,
+    });
+
+    #[test]
+    fn custom() {
+        #[derive(Serialize, Deserialize)]
+        struct Foo {
+            #[serde(with = "millis_preserve")]
+            offset: DateTime<FixedOffset>,
+            #[serde(with = "millis_preserve")]
+            utc: DateTime<Utc>,
+            #[serde(with = "strict")]
+            strict: DateTime<Utc>,
+        }
+
+        let json = r#"{
+            "offset": "2018-05-11 20:28:30+02:00",
+            "utc": "2018-05-11T18:28:30",
+            "strict": "2018-05-11T20:28:30.5+02:00"
+        }"#;
+        let foo = serde_json::from_str::<Foo>(json).unwrap();
+        let time = DateTime::UNIX_EPOCH + Duration::new(1526063310, 0);
+        assert_eq!(foo.offset, time);
+        assert_eq!(foo.utc, time);
+        assert_eq!(foo.strict, time + Duration::from_millis(500));
+        let reverse = serde_json::to_string(&foo).unwrap();
+        assert_eq!(
+            reverse,
+            r#"{"offset":"2018-05-11T20:28:30.000+02:00","utc":"2018-05-11T18:28:30.000Z","strict":"2018-05-11T18:28:30Z"}"#
+        );
+    }
+
+    #[test]
+    fn strict_rejects_lenient_input() {
+        #[derive(Deserialize)]
+        struct Foo {
+            #[serde(with = "strict")]
+            #[allow(dead_code)]
+            time: DateTime<Utc>,
+        }
+
+        assert!(serde_json::from_str::<Foo>(r#"{"time": "2018-05-11T18:28:30"}"#).is_err());
+    }
+
+    #[test]
+    fn lenient_is_not_relative() {
+        #[derive(Deserialize)]
+        struct Foo {
+            #[serde(with = "millis_preserve")]
+            #[allow(dead_code)]
+            lenient: DateTime<Utc>,
+            #[serde(with = "abbreviated")]
+            #[allow(dead_code)]
+            abbreviated: DateTime<Utc>,
+        }
+
+        let parse = |lenient: &str, abbreviated: &str| {
+            let json = serde_json::json!({ "lenient": lenient, "abbreviated": abbreviated });
+            serde_json::from_value::<Foo>(json)
+        };
+        let t = "2018-05-11 18:28:30";
+        assert!(parse(t, "2018-05-11 13:28:30 EST").is_ok());
+        assert!(parse("now", t).is_err());
+        assert!(parse(t, "now").is_err());
+        assert!(parse("2018-05-11 18:28:30 + 1h", t).is_err());
+        assert!(parse(t, "2018-05-11 18:28:30 + 1h").is_err());
+    }
+
+    #[test]
+    fn checked() {
+        #[derive(Serialize)]
+        struct Foo {
+            #[serde(with = "exact_millis")]
+            time: DateTime<Utc>,
+        }
+
+        let time = DateTime::UNIX_EPOCH + Duration::new(1526063310, 250_000_000);
+        let json = serde_json::to_string(&Foo { time }).unwrap();
+        assert_eq!(json, r#"{"time":"2018-05-11T18:28:30.250Z"}"#);
+
+        let time = time + Duration::from_micros(1);
+        let err = serde_json::to_string(&Foo { time }).unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with("2018-05-11T18:28:30.250001Z has more fractional digits than Millis precision writes"));
+    }
+
+    #[test]
+    fn leap_second() {
+        use super::{LeapSecond, Options};
+
+        let parse = |policy| Options::DEFAULT.leap_second(policy).parse("2016-12-31T23:59:60.25Z");
+        assert_eq!(parse(LeapSecond::Reject), Err(crate::Error::LeapSecond));
+        let clamped = parse(LeapSecond::ClampTo59).unwrap();
+        assert_eq!(clamped.to_rfc3339(), "2016-12-31T23:59:59.999999999+00:00");
+        let next = parse(LeapSecond::SmearToNext).unwrap();
+        assert_eq!(next.to_rfc3339(), "2017-01-01T00:00:00.250+00:00");
+        let kept = parse(LeapSecond::Keep).unwrap();
+        assert_eq!(kept.to_rfc3339(), "2016-12-31T23:59:60.250+00:00");
+        assert!(Options::DEFAULT.leap_second(LeapSecond::Reject).parse("2016-12-31T23:59:59Z").is_ok());
+    }
+}
@@ -0,0 +1,190 @@
+use core::fmt;
+use core::str::FromStr;
+use core::time::Duration;
+
+use chrono::{DateTime, TimeDelta, Utc};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use super::format::{HumantimeFormat, Ref};
+use super::Serde;
+
+/// A deadline given either relative to now, as a duration such as `"30m"`,
+/// or as an absolute RFC 3339 timestamp.
+///
+/// It serializes back to the form it was written in. Values shaped like a
+/// date, with a `-` after the year, are parsed as timestamps and everything
+/// else as durations.
+///
+/// # Example
+///
+/// ```
+/// use core::time::Duration;
+/// use chrono::{DateTime, Utc};
+/// use serde::{Serialize, Deserialize};
+/// use humantime_serde::DurationOrTimestamp;
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct Job {
+///     deadline: DurationOrTimestamp,
+/// }
+///
+/// let now = DateTime::<Utc>::UNIX_EPOCH;
+/// let job: Job = serde_json::from_str(r#"{"deadline": "30m"}"#).unwrap();
+/// assert_eq!(job.deadline.resolve(now), now + Duration::from_secs(1800));
+///
+/// let job: Job = serde_json::from_str(r#"{"deadline": "2018-05-11T18:28:30Z"}"#).unwrap();
+/// assert_eq!(job.deadline.resolve(now).timestamp(), 1526063310);
+/// ```
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub enum DurationOrTimestamp {
+    /// A duration from the moment the deadline is resolved.
+    Duration(Duration),
+    /// A point in time.
+    Timestamp(DateTime<Utc>),
+}
+
+impl DurationOrTimestamp {
+    /// Returns the point in time the deadline falls on, measuring durations
+    /// from `now`.
+    ///
+    /// Saturates at `DateTime::<Utc>::MAX_UTC` if the duration is too long.
+    pub fn resolve(&self, now: DateTime<Utc>) -> DateTime<Utc> {
+        match *self {
+            DurationOrTimestamp::Duration(d) => TimeDelta::from_std(d)
+                .ok()
+                .and_then(|d| now.checked_add_signed(d))
+                .unwrap_or(DateTime::<Utc>::MAX_UTC),
+            DurationOrTimestamp::Timestamp(t) => t,
+        }
+    }
+}
+
+/// The error returned when parsing a [`DurationOrTimestamp`] fails.
+#[derive(Clone, Debug, PartialEq)]
+pub enum DeadlineError {
+    /// The value looked like a duration but was invalid.
+    Duration(humantime::DurationError),
+    /// The value looked like a timestamp but was invalid.
+    Timestamp(chrono::ParseError),
+}
+
+impl fmt::Display for DeadlineError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DeadlineError::Duration(e) => write!(f, "invalid duration: {}", e),
+            DeadlineError::Timestamp(e) => write!(f, "invalid timestamp: {}", e),
+        }
+    }
+}
+
+impl core::error::Error for DeadlineError {}
+
+fn looks_like_timestamp(s: &str) -> bool {
+    s.as_bytes().get(4) == Some(&b'-')
+}
+
+impl HumantimeFormat for DurationOrTimestamp {
+    const EXPECTING: &'static str = "a duration or a timestamp";
+
+    type Err = DeadlineError;
+
+    fn parse(s: &str) -> Result<DurationOrTimestamp, DeadlineError> {
+        if looks_like_timestamp(s) {
+            DateTime::<Utc>::parse(s)
+                .map(DurationOrTimestamp::Timestamp)
+                .map_err(DeadlineError::Timestamp)
+        } else {
+            Duration::parse(s)
+                .map(DurationOrTimestamp::Duration)
+                .map_err(DeadlineError::Duration)
+        }
+    }
+
+    fn format(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DurationOrTimestamp::Duration(d) => d.format(f),
+            DurationOrTimestamp::Timestamp(t) => HumantimeFormat::format(t, f),
+        }
+    }
+}
+
+impl fmt::Display for DurationOrTimestamp {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.format(f)
+    }
+}
+
+impl FromStr for DurationOrTimestamp {
+    type Err = DeadlineError;
+
+    fn from_str(s: &str) -> Result<DurationOrTimestamp, DeadlineError> {
+        DurationOrTimestamp::parse(s)
+    }
+}
+
+impl From<Duration> for DurationOrTimestamp {
+    fn from(d: Duration) -> DurationOrTimestamp {
+        DurationOrTimestamp::Duration(d)
+    }
+}
+
+impl From<DateTime<Utc>> for DurationOrTimestamp {
+    fn from(t: DateTime<Utc>) -> DurationOrTimestamp {
+        DurationOrTimestamp::Timestamp(t)
+    }
+}
+
+impl Serialize for DurationOrTimestamp {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        Ref(self).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for DurationOrTimestamp {
+    fn deserialize<D>(d: D) -> Result<DurationOrTimestamp, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Serde::deserialize(d).map(Serde::into_inner)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloc::string::ToString;
+
+    #[test]
+    fn serde() {
+        for s in &["\"1h 30m\"", "\"2018-05-11T18:28:30Z\""] {
+            let v: DurationOrTimestamp = serde_json::from_str(s).unwrap();
+            assert_eq!(serde_json::to_string(&v).unwrap(), *s);
+        }
+
+        let v: DurationOrTimestamp = serde_json::from_str("\"2018-05-11T20:28:30+02:00\"").unwrap();
+        assert_eq!(v.to_string(), "2018-05-11T18:28:30Z");
+    }
+
+    #[test]
+    fn errors() {
+        let err = "2018-05-11".parse::<DurationOrTimestamp>().unwrap_err();
+        assert!(matches!(err, DeadlineError::Timestamp(_)));
+        let err = "15 parsecs".parse::<DurationOrTimestamp>().unwrap_err();
+        assert!(matches!(err, DeadlineError::Duration(_)));
+
+        let err = serde_json::from_str::<DurationOrTimestamp>("\"soon\"").unwrap_err();
+        assert!(err.to_string().contains("expected a duration or a timestamp"));
+    }
+
+    #[test]
+    fn resolve() {
+        let now = DateTime::<Utc>::UNIX_EPOCH;
+        let d = DurationOrTimestamp::from(Duration::from_secs(60));
+        assert_eq!(d.resolve(now).timestamp(), 60);
+        let d = DurationOrTimestamp::from(Duration::MAX);
+        assert_eq!(d.resolve(now), DateTime::<Utc>::MAX_UTC);
+    }
+}
@@ -0,0 +1,35 @@
+//! `defmt` support, enabled with the `defmt` feature.
+
+use core::time::Duration;
+
+use defmt::{Display2Format, Format, Formatter};
+
+use super::format::Ref;
+use super::{HumanDuration, Serde};
+
+impl Format for Serde<Duration> {
+    fn format(&self, f: Formatter) {
+        defmt::write!(f, "{}", Display2Format(&Ref(&self.0)))
+    }
+}
+
+impl Format for HumanDuration {
+    fn format(&self, f: Formatter) {
+        defmt::write!(f, "{}", Display2Format(self))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn assert_format<T: Format>(_: &T) {}
+
+    #[test]
+    fn format() {
+        let d = Duration::from_millis(90_500);
+        assert_format(&Serde::from(d));
+        assert_format(&HumanDuration::from(d));
+        assert_format(&Display2Format(&Ref(&d)));
+    }
+}
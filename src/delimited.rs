@@ -0,0 +1,233 @@
+//! Durations or timestamps packed into one delimited string, such as
+//! `"1h,30m,15s"`, for formats without arrays like environment variables
+//! and CSV cells.
+//!
+//! The functions here use a comma. [`delimited!`] generates a module using
+//! another delimiter, which should not be a space since durations such as
+//! `1h 30m` contain spaces. On deserialization, whitespace around each
+//! element is ignored and an empty string is an empty list. Deserializing
+//! needs the `alloc` feature.
+//!
+//! # Example
+//!
+//! ```
+//! use core::time::Duration;
+//! use serde::{Serialize, Deserialize};
+//!
+//! humantime_serde::delimited!(pub mod semicolons: ";");
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Foo {
+//!     #[serde(with = "humantime_serde::delimited")]
+//!     retries: Vec<Duration>,
+//!     #[serde(with = "semicolons")]
+//!     timeouts: Vec<Duration>,
+//! }
+//!
+//! let json = r#"{"retries": "1h, 30m", "timeouts": "1m 30s; 15s"}"#;
+//! let foo: Foo = serde_json::from_str(json).unwrap();
+//! assert_eq!(foo.retries, [Duration::from_secs(3600), Duration::from_secs(1800)]);
+//! let json = serde_json::to_string(&foo).unwrap();
+//! assert_eq!(json, r#"{"retries":"1h,30m","timeouts":"1m 30s;15s"}"#);
+//! ```
+//!
+//! [`delimited!`]: crate::delimited
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use core::fmt;
+
+#[cfg(feature = "alloc")]
+use serde::de::{self, Deserializer};
+use serde::Serializer;
+
+use super::format::{HumantimeFormat, Ref};
+
+/// Serializes a slice of `Duration` or `DateTime<Tz>` as one string, the
+/// elements separated by commas.
+pub fn serialize<T, S>(d: &[T], s: S) -> Result<S::Ok, S::Error>
+where
+    T: HumantimeFormat,
+    S: Serializer,
+{
+    serialize_with(",", d, s)
+}
+
+/// Serializes a slice of `Duration` or `DateTime<Tz>` as one string, the
+/// elements separated by `delimiter`.
+pub fn serialize_with<T, S>(delimiter: &str, d: &[T], s: S) -> Result<S::Ok, S::Error>
+where
+    T: HumantimeFormat,
+    S: Serializer,
+{
+    s.collect_str(&Joined(d, delimiter))
+}
+
+/// Deserializes a comma separated `Vec<Duration>` or `Vec<DateTime<Tz>>`.
+#[cfg(feature = "alloc")]
+pub fn deserialize<'a, T, D>(d: D) -> Result<Vec<T>, D::Error>
+where
+    T: HumantimeFormat,
+    D: Deserializer<'a>,
+{
+    deserialize_with(",", d)
+}
+
+/// Deserializes a `Vec<Duration>` or `Vec<DateTime<Tz>>` separated by
+/// `delimiter`.
+#[cfg(feature = "alloc")]
+pub fn deserialize_with<'a, T, D>(delimiter: &str, d: D) -> Result<Vec<T>, D::Error>
+where
+    T: HumantimeFormat,
+    D: Deserializer<'a>,
+{
+    struct V<'d, T>(&'d str, core::marker::PhantomData<T>);
+
+    impl<T> de::Visitor<'_> for V<'_, T>
+    where
+        T: HumantimeFormat,
+    {
+        type Value = Vec<T>;
+
+        fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+            write!(fmt, "a list of {} separated by {:?}", T::EXPECTING, self.0)
+        }
+
+        fn visit_str<E>(self, v: &str) -> Result<Vec<T>, E>
+        where
+            E: de::Error,
+        {
+            if v.trim().is_empty() {
+                return Ok(Vec::new());
+            }
+            v.split(self.0)
+                .map(|item| {
+                    let item = item.trim();
+                    crate::check_len(item, crate::DEFAULT_MAX_LEN, T::EXPECTING)?;
+                    T::parse(item).map_err(|err| {
+                        E::invalid_value(de::Unexpected::Str(item), &crate::Because(&self, &err))
+                    })
+                })
+                .collect()
+        }
+    }
+
+    d.deserialize_str(V(delimiter, core::marker::PhantomData))
+}
+
+struct Joined<'a, T>(&'a [T], &'a str);
+
+impl<T> fmt::Display for Joined<'_, T>
+where
+    T: HumantimeFormat,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, v) in self.0.iter().enumerate() {
+            if i > 0 {
+                f.write_str(self.1)?;
+            }
+            fmt::Display::fmt(&Ref(v), f)?;
+        }
+        Ok(())
+    }
+}
+
+/// Generates a module for `#[serde(with = "...")]` packing a `Vec` into one
+/// string with the given delimiter.
+///
+/// Without the `alloc` feature the module only has `serialize`.
+///
+/// See the [`delimited`](mod@crate::delimited) module for details.
+#[macro_export]
+macro_rules! delimited {
+    ($(#[$attr:meta])* $vis:vis mod $name:ident : $delimiter:literal) => {
+        $(#[$attr])*
+        $vis mod $name {
+            /// Serializes a slice as one delimited string.
+            pub fn serialize<T, S>(d: &[T], s: S) -> ::core::result::Result<S::Ok, S::Error>
+            where
+                T: $crate::format::HumantimeFormat,
+                S: $crate::re::serde::Serializer,
+            {
+                $crate::delimited::serialize_with($delimiter, d, s)
+            }
+
+            $crate::__delimited_deserialize!($delimiter);
+        }
+    };
+}
+
+#[cfg(feature = "alloc")]
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __delimited_deserialize {
+    ($delimiter:literal) => {
+        /// Deserializes a `Vec` from one delimited string.
+        pub fn deserialize<'a, T, D>(
+            d: D,
+        ) -> ::core::result::Result<$crate::__private::Vec<T>, D::Error>
+        where
+            T: $crate::format::HumantimeFormat,
+            D: $crate::re::serde::Deserializer<'a>,
+        {
+            $crate::delimited::deserialize_with($delimiter, d)
+        }
+    };
+}
+
+#[cfg(not(feature = "alloc"))]
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __delimited_deserialize {
+    ($delimiter:literal) => {};
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod test {
+    use super::*;
+    use alloc::vec;
+    use alloc::vec::Vec;
+    use core::time::Duration;
+    use serde::{Deserialize, Serialize};
+
+    crate::delimited!(mod pipes: " | ");
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Foo {
+        #[serde(with = "super")]
+        commas: Vec<Duration>,
+        #[serde(with = "pipes")]
+        pipes: Vec<Duration>,
+        #[serde(with = "super")]
+        empty: Vec<Duration>,
+    }
+
+    #[test]
+    fn delimited() {
+        let d = vec![Duration::from_secs(3600), Duration::from_secs(1800), Duration::from_secs(15)];
+        let foo = Foo { commas: d.clone(), pipes: d, empty: Vec::new() };
+        assert_eq!(
+            serde_json::to_string(&foo).unwrap(),
+            r#"{"commas":"1h,30m,15s","pipes":"1h | 30m | 15s","empty":""}"#
+        );
+
+        let json = r#"{"commas": " 1h, 30m ,15s", "pipes": "1h | 30m | 15s ", "empty": " "}"#;
+        assert_eq!(serde_json::from_str::<Foo>(json).unwrap(), foo);
+
+        let json = r#"{"commas": "1h,,15s", "pipes": "", "empty": ""}"#;
+        let err = serde_json::from_str::<Foo>(json).unwrap_err();
+        assert!(err.to_string().starts_with(
+            r#"invalid value: string "", expected a list of a duration separated by ",""#
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "chrono")]
+    fn timestamps() {
+        use chrono::{DateTime, Utc};
+
+        let d = serde_json::Value::from("2018-05-11T18:28:30Z, 2018-05-11T20:28:30+02:00");
+        let times: Vec<DateTime<Utc>> = deserialize(d).unwrap();
+        assert_eq!(times[0], times[1]);
+    }
+}
@@ -0,0 +1,169 @@
+//! `with` modules for durations reading both humantime (`1h 30m`) and
+//! ISO 8601 (`PT1H30M`) syntax.
+//!
+//! Input starting with `P` is read as an ISO 8601 duration, anything else
+//! as humantime. The parent module writes humantime syntax and the
+//! [`iso`](self::iso) submodule ISO 8601, so one schema serves users of
+//! either convention while writing the one its owners prefer.
+//! [`serialize_with`] takes the [`Dialect`] at runtime instead.
+//!
+//! # Example
+//!
+//! ```
+//! use core::time::Duration;
+//! use serde::{Serialize, Deserialize};
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Job {
+//!     #[serde(with = "humantime_serde::dialect")]
+//!     timeout: Duration,
+//!     #[serde(with = "humantime_serde::dialect::iso")]
+//!     lease: Duration,
+//! }
+//!
+//! let json = r#"{"timeout": "PT1H30M", "lease": "90s"}"#;
+//! let job: Job = serde_json::from_str(json).unwrap();
+//! assert_eq!(job.timeout, Duration::from_secs(5400));
+//! let json = serde_json::to_string(&job).unwrap();
+//! assert_eq!(json, r#"{"timeout":"1h 30m","lease":"PT1M30S"}"#);
+//! ```
+
+use core::fmt;
+use core::time::Duration;
+
+use serde::{de, Deserializer, Serializer};
+
+use super::format::Ref;
+use crate::Error;
+
+/// A syntax for writing durations.
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub enum Dialect {
+    /// Humantime syntax, as in `1h 30m`.
+    Humantime,
+    /// ISO 8601 syntax, as in `PT1H30M`.
+    Iso,
+}
+
+impl Dialect {
+    /// Formats `d` in this dialect.
+    pub fn format(self, d: Duration) -> impl fmt::Display {
+        Formatted(self, d)
+    }
+}
+
+struct Formatted(Dialect, Duration);
+
+impl fmt::Display for Formatted {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.0 {
+            Dialect::Humantime => fmt::Display::fmt(&Ref(&self.1), f),
+            Dialect::Iso => fmt::Display::fmt(&crate::iso::format_duration(self.1), f),
+        }
+    }
+}
+
+/// Parses a duration in either dialect.
+pub fn parse(s: &str) -> Result<Duration, Error> {
+    if s.starts_with('P') {
+        Ok(crate::iso::parse_duration(s)?)
+    } else {
+        crate::parse(s)
+    }
+}
+
+/// Serializes a `Duration` in humantime syntax.
+pub fn serialize<S>(d: &Duration, s: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serialize_with(Dialect::Humantime, d, s)
+}
+
+/// Serializes a `Duration` in `dialect`.
+pub fn serialize_with<S>(dialect: Dialect, d: &Duration, s: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    s.collect_str(&dialect.format(*d))
+}
+
+/// Deserializes a `Duration` from either dialect.
+pub fn deserialize<'a, D>(d: D) -> Result<Duration, D::Error>
+where
+    D: Deserializer<'a>,
+{
+    struct V;
+
+    impl de::Visitor<'_> for V {
+        type Value = Duration;
+
+        fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+            fmt.write_str("a humantime or ISO 8601 duration")
+        }
+
+        fn visit_str<E>(self, v: &str) -> Result<Duration, E>
+        where
+            E: de::Error,
+        {
+            crate::check_len(v, crate::DEFAULT_MAX_LEN, "a humantime or ISO 8601 duration")?;
+            parse(v).map_err(|err| {
+                E::invalid_value(de::Unexpected::Str(v), &crate::Because(&self, &err))
+            })
+        }
+    }
+
+    d.deserialize_str(V)
+}
+
+crate::__option_module!([] ::core::time::Duration, []);
+
+/// Reads either dialect, writes ISO 8601 syntax.
+pub mod iso {
+    use super::*;
+
+    pub use super::deserialize;
+
+    /// Serializes a `Duration` in ISO 8601 syntax.
+    pub fn serialize<S>(d: &Duration, s: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serialize_with(Dialect::Iso, d, s)
+    }
+
+    crate::__option_module!([] ::core::time::Duration, []);
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod test {
+    use super::*;
+    use alloc::string::ToString;
+    use serde::{Deserialize, Serialize};
+
+    #[test]
+    fn parsing() {
+        assert_eq!(parse("PT1H30M"), Ok(Duration::from_secs(5400)));
+        assert_eq!(parse("1h 30m"), Ok(Duration::from_secs(5400)));
+        assert_eq!(parse("P1D"), Ok(Duration::from_secs(86_400)));
+        assert_eq!(parse("P1Y"), Err(Error::Invalid("an ISO 8601 duration or interval")));
+        assert_eq!(parse(""), Err(Error::Empty));
+        assert_eq!(Dialect::Iso.format(Duration::from_millis(1500)).to_string(), "PT1.5S");
+        assert_eq!(Dialect::Humantime.format(Duration::from_millis(1500)).to_string(), "1s 500ms");
+    }
+
+    #[test]
+    fn option() {
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct Foo {
+            #[serde(with = "super::iso::option", default)]
+            timeout: Option<Duration>,
+        }
+
+        let foo: Foo = serde_json::from_str(r#"{"timeout": "2m"}"#).unwrap();
+        assert_eq!(serde_json::to_string(&foo).unwrap(), r#"{"timeout":"PT2M"}"#);
+        assert_eq!(serde_json::from_str::<Foo>("{}").unwrap(), Foo { timeout: None });
+        let err = serde_json::from_str::<Foo>(r#"{"timeout": "PT"}"#).unwrap_err();
+        assert!(err.to_string().contains("expected a humantime or ISO 8601 duration"));
+    }
+}
@@ -0,0 +1,252 @@
+use core::fmt;
+use core::ops::{Add, AddAssign, Deref, DerefMut, Div, DivAssign, Mul, MulAssign, Sub, SubAssign};
+use core::str::FromStr;
+use core::time::Duration;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use super::backend::{Backend, Selected};
+use super::Serde;
+
+/// A `Duration` which serializes to and from a humantime string on its own,
+/// without any `#[serde(with)]` annotation.
+///
+/// # Example
+///
+/// ```
+/// use serde::{Serialize, Deserialize};
+/// use humantime_serde::HumanDuration;
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct Foo {
+///     timeout: HumanDuration,
+///     retries: Vec<HumanDuration>,
+///     #[serde(default)]
+///     idle: Option<HumanDuration>,
+/// }
+///
+/// let timeout: HumanDuration = "1m 30s".parse().unwrap();
+/// assert_eq!(timeout.as_secs(), 90);
+/// assert_eq!(timeout.to_string(), "1m 30s");
+/// ```
+#[derive(Copy, Clone, Default, Eq, Hash, PartialEq, Ord, PartialOrd)]
+pub struct HumanDuration(Duration);
+
+impl HumanDuration {
+    /// Wraps a `Duration`.
+    pub const fn new(d: Duration) -> HumanDuration {
+        HumanDuration(d)
+    }
+
+    /// Consumes the `HumanDuration`, returning the inner `Duration`.
+    pub fn into_inner(self) -> Duration {
+        self.0
+    }
+
+    /// Checked addition, returning `None` on overflow.
+    pub fn checked_add(self, rhs: HumanDuration) -> Option<HumanDuration> {
+        self.0.checked_add(rhs.0).map(HumanDuration)
+    }
+
+    /// Checked subtraction, returning `None` if `rhs` is larger than `self`.
+    pub fn checked_sub(self, rhs: HumanDuration) -> Option<HumanDuration> {
+        self.0.checked_sub(rhs.0).map(HumanDuration)
+    }
+
+    /// Checked multiplication, returning `None` on overflow.
+    pub fn checked_mul(self, rhs: u32) -> Option<HumanDuration> {
+        self.0.checked_mul(rhs).map(HumanDuration)
+    }
+
+    /// Checked division, returning `None` if `rhs` is zero.
+    pub fn checked_div(self, rhs: u32) -> Option<HumanDuration> {
+        self.0.checked_div(rhs).map(HumanDuration)
+    }
+
+    /// Saturating addition, returning `Duration::MAX` on overflow.
+    pub fn saturating_add(self, rhs: HumanDuration) -> HumanDuration {
+        HumanDuration(self.0.saturating_add(rhs.0))
+    }
+
+    /// Saturating subtraction, returning zero if `rhs` is larger than `self`.
+    pub fn saturating_sub(self, rhs: HumanDuration) -> HumanDuration {
+        HumanDuration(self.0.saturating_sub(rhs.0))
+    }
+
+    /// Saturating multiplication, returning `Duration::MAX` on overflow.
+    pub fn saturating_mul(self, rhs: u32) -> HumanDuration {
+        HumanDuration(self.0.saturating_mul(rhs))
+    }
+}
+
+impl fmt::Debug for HumanDuration {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        self.0.fmt(formatter)
+    }
+}
+
+impl fmt::Display for HumanDuration {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        Selected::format_duration(self.0, formatter)
+    }
+}
+
+impl FromStr for HumanDuration {
+    type Err = humantime::DurationError;
+
+    fn from_str(s: &str) -> Result<HumanDuration, humantime::DurationError> {
+        Selected::parse_duration(s).map(HumanDuration)
+    }
+}
+
+impl Deref for HumanDuration {
+    type Target = Duration;
+
+    fn deref(&self) -> &Duration {
+        &self.0
+    }
+}
+
+impl DerefMut for HumanDuration {
+    fn deref_mut(&mut self) -> &mut Duration {
+        &mut self.0
+    }
+}
+
+impl From<Duration> for HumanDuration {
+    fn from(d: Duration) -> HumanDuration {
+        HumanDuration(d)
+    }
+}
+
+impl From<HumanDuration> for Duration {
+    fn from(d: HumanDuration) -> Duration {
+        d.0
+    }
+}
+
+impl Serialize for HumanDuration {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        Serde(&self.0).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for HumanDuration {
+    fn deserialize<D>(d: D) -> Result<HumanDuration, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Serde::<Duration>::deserialize(d).map(|d| HumanDuration(d.into_inner()))
+    }
+}
+
+impl Add for HumanDuration {
+    type Output = HumanDuration;
+
+    fn add(self, rhs: HumanDuration) -> HumanDuration {
+        HumanDuration(self.0 + rhs.0)
+    }
+}
+
+impl AddAssign for HumanDuration {
+    fn add_assign(&mut self, rhs: HumanDuration) {
+        self.0 += rhs.0;
+    }
+}
+
+impl Sub for HumanDuration {
+    type Output = HumanDuration;
+
+    fn sub(self, rhs: HumanDuration) -> HumanDuration {
+        HumanDuration(self.0 - rhs.0)
+    }
+}
+
+impl SubAssign for HumanDuration {
+    fn sub_assign(&mut self, rhs: HumanDuration) {
+        self.0 -= rhs.0;
+    }
+}
+
+impl Mul<u32> for HumanDuration {
+    type Output = HumanDuration;
+
+    fn mul(self, rhs: u32) -> HumanDuration {
+        HumanDuration(self.0 * rhs)
+    }
+}
+
+impl MulAssign<u32> for HumanDuration {
+    fn mul_assign(&mut self, rhs: u32) {
+        self.0 *= rhs;
+    }
+}
+
+impl Div<u32> for HumanDuration {
+    type Output = HumanDuration;
+
+    fn div(self, rhs: u32) -> HumanDuration {
+        HumanDuration(self.0 / rhs)
+    }
+}
+
+impl DivAssign<u32> for HumanDuration {
+    fn div_assign(&mut self, rhs: u32) {
+        self.0 /= rhs;
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod test {
+    use super::*;
+    use alloc::string::ToString;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn serde() {
+        #[derive(Serialize, Deserialize)]
+        struct Foo {
+            time: HumanDuration,
+            times: Vec<HumanDuration>,
+            #[serde(default)]
+            idle: Option<HumanDuration>,
+        }
+
+        let json = r#"{"time": "15 seconds", "times": ["1m", "90s"]}"#;
+        let foo = serde_json::from_str::<Foo>(json).unwrap();
+        assert_eq!(*foo.time, Duration::from_secs(15));
+        assert_eq!(foo.times[1], HumanDuration::from(Duration::from_secs(90)));
+        assert_eq!(foo.idle, None);
+        let reverse = serde_json::to_string(&foo).unwrap();
+        assert_eq!(reverse, r#"{"time":"15s","times":["1m","1m 30s"],"idle":null}"#);
+    }
+
+    #[test]
+    fn parse_display() {
+        let d: HumanDuration = "90 seconds".parse().unwrap();
+        assert_eq!(d.to_string(), "1m 30s");
+        assert!("90 parsecs".parse::<HumanDuration>().is_err());
+    }
+
+    #[test]
+    fn arithmetic() {
+        let d = HumanDuration::from(Duration::from_secs(10));
+        assert_eq!((d + d).into_inner(), Duration::from_secs(20));
+        assert_eq!((d - d).into_inner(), Duration::ZERO);
+        assert_eq!((d * 3).into_inner(), Duration::from_secs(30));
+        assert_eq!((d / 4).into_inner(), Duration::from_millis(2500));
+        assert!(d < d * 2);
+        assert_eq!(d.checked_sub(d * 2), None);
+        assert_eq!(d.checked_div(0), None);
+        assert_eq!(d.saturating_sub(d * 2).into_inner(), Duration::ZERO);
+        let max = HumanDuration::from(Duration::MAX);
+        assert_eq!(max.checked_add(d), None);
+        assert_eq!(max.checked_mul(2), None);
+        assert_eq!(max.saturating_add(d), max);
+        assert_eq!(max.saturating_mul(2), max);
+        assert_eq!(HumanDuration::default().into_inner(), Duration::ZERO);
+    }
+}
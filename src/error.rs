@@ -0,0 +1,235 @@
+use core::fmt;
+
+use super::format::HumantimeFormat;
+
+/// The error returned by [`parse`](crate::parse) and the other parse
+/// functions, telling why the input was rejected.
+///
+/// # Example
+///
+/// ```
+/// use core::time::Duration;
+/// use humantime_serde::Error;
+///
+/// match humantime_serde::parse::<Duration>("5 fortnights") {
+///     Err(Error::UnknownUnit { start, end, .. }) => assert_eq!(start..end, 2..12),
+///     other => panic!("{:?}", other),
+/// }
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum Error {
+    /// The input was empty.
+    Empty,
+    /// The byte at this offset is not part of the syntax.
+    InvalidCharacter(usize),
+    /// A number was expected at this byte offset.
+    NumberExpected(usize),
+    /// The unit at this byte range of the input is not known.
+    UnknownUnit {
+        /// Where the unit starts.
+        start: usize,
+        /// Where the unit ends.
+        end: usize,
+        /// A known unit spelled similarly, if any.
+        suggestion: Option<&'static str>,
+    },
+    /// A number, or the value as a whole, is too large.
+    NumberOverflow,
+    /// The input is not a valid timestamp.
+    #[cfg(feature = "chrono")]
+    InvalidTimestamp(chrono::ParseError),
+    /// The timestamp falls on a leap second, which was rejected by
+    /// [`LeapSecond::Reject`](crate::custom::LeapSecond::Reject).
+    #[cfg(feature = "chrono")]
+    LeapSecond,
+    /// The local time names two instants, which was rejected by
+    /// [`Ambiguity::Reject`](crate::Ambiguity::Reject).
+    #[cfg(feature = "chrono")]
+    AmbiguousTime,
+    /// The local time is skipped by a change of offset.
+    #[cfg(feature = "chrono")]
+    NonexistentTime,
+    /// The time zone abbreviation stands for several offsets.
+    #[cfg(feature = "chrono")]
+    AmbiguousAbbreviation {
+        /// The abbreviation, such as `CST`.
+        name: &'static str,
+        /// What it may stand for.
+        meanings: &'static [&'static str],
+    },
+    /// The input goes on after a complete value.
+    TrailingInput,
+    /// The input is not valid for a type without finer grained errors, e.g.
+    /// `"a rate"`.
+    Invalid(&'static str),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Empty => f.write_str("empty input"),
+            Error::InvalidCharacter(i) => write!(f, "invalid character at {}", i),
+            Error::NumberExpected(i) => write!(f, "expected a number at {}", i),
+            Error::UnknownUnit { start, end, suggestion } => {
+                write!(f, "unknown unit at {}..{}", start, end)?;
+                match suggestion {
+                    Some(unit) => write!(f, ", did you mean {:?}?", unit),
+                    None => Ok(()),
+                }
+            }
+            Error::NumberOverflow => f.write_str("number is too large"),
+            #[cfg(feature = "chrono")]
+            Error::InvalidTimestamp(e) => write!(f, "invalid timestamp: {}", e),
+            #[cfg(feature = "chrono")]
+            Error::LeapSecond => f.write_str("leap seconds are not accepted"),
+            #[cfg(feature = "chrono")]
+            Error::AmbiguousTime => f.write_str("local time is ambiguous"),
+            #[cfg(feature = "chrono")]
+            Error::NonexistentTime => f.write_str("local time does not exist"),
+            #[cfg(feature = "chrono")]
+            Error::AmbiguousAbbreviation { name, meanings } => {
+                write!(f, "ambiguous time zone abbreviation {}, which may be ", name)?;
+                for (i, meaning) in meanings.iter().enumerate() {
+                    match i {
+                        0 => {}
+                        i if i + 1 == meanings.len() => f.write_str(" or ")?,
+                        _ => f.write_str(", ")?,
+                    }
+                    f.write_str(meaning)?;
+                }
+                Ok(())
+            }
+            Error::TrailingInput => f.write_str("trailing input"),
+            Error::Invalid(expected) => write!(f, "invalid input, expected {}", expected),
+        }
+    }
+}
+
+impl core::error::Error for Error {}
+
+impl Error {
+    // Fills in the suggestion for an unknown unit, which needs the input the
+    // offsets refer to.
+    pub(crate) fn with_input(self, s: &str) -> Error {
+        match self {
+            Error::UnknownUnit { start, end, suggestion: None } => Error::UnknownUnit {
+                start,
+                end,
+                suggestion: s.get(start..end).and_then(crate::grammar::suggest_unit),
+            },
+            err => err,
+        }
+    }
+}
+
+impl From<humantime::DurationError> for Error {
+    fn from(err: humantime::DurationError) -> Error {
+        use humantime::DurationError as E;
+
+        match err {
+            E::Empty => Error::Empty,
+            E::InvalidCharacter(i) => Error::InvalidCharacter(i),
+            E::NumberExpected(i) => Error::NumberExpected(i),
+            E::UnknownUnit { start, end, .. } => Error::UnknownUnit {
+                start,
+                end,
+                suggestion: None,
+            },
+            E::NumberOverflow => Error::NumberOverflow,
+        }
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl From<chrono::ParseError> for Error {
+    fn from(err: chrono::ParseError) -> Error {
+        match err.kind() {
+            chrono::format::ParseErrorKind::TooLong => Error::TrailingInput,
+            _ => Error::InvalidTimestamp(err),
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl From<crate::InvalidBuckets> for Error {
+    fn from(err: crate::InvalidBuckets) -> Error {
+        match err {
+            crate::InvalidBuckets::Bucket(_, e) => e.into(),
+            _ => Error::Invalid(<crate::Buckets as HumantimeFormat>::EXPECTING),
+        }
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl From<crate::DeadlineError> for Error {
+    fn from(err: crate::DeadlineError) -> Error {
+        match err {
+            crate::DeadlineError::Duration(e) => e.into(),
+            crate::DeadlineError::Timestamp(e) => e.into(),
+        }
+    }
+}
+
+// Errors of the other types only say that the input was invalid.
+macro_rules! impl_from_invalid {
+    ($($(#[$attr:meta])* $err:ty => $expected:expr,)*) => {$(
+        $(#[$attr])*
+        impl From<$err> for Error {
+            fn from(_: $err) -> Error {
+                Error::Invalid($expected)
+            }
+        }
+    )*};
+}
+
+impl_from_invalid! {
+    crate::InvalidBackoff => "a backoff policy",
+    crate::InvalidEvery => <crate::Every as HumantimeFormat>::EXPECTING,
+    crate::InvalidJitter => <crate::Jittered as HumantimeFormat>::EXPECTING,
+    crate::InvalidPeriod => <crate::Period as HumantimeFormat>::EXPECTING,
+    crate::InvalidRate => <crate::Rate as HumantimeFormat>::EXPECTING,
+    crate::iso::InvalidIso => "an ISO 8601 duration or interval",
+    #[cfg(feature = "natural")]
+    crate::InvalidRecurrence => <crate::Recurrence as HumantimeFormat>::EXPECTING,
+    #[cfg(feature = "chrono")]
+    crate::InvalidTimeOfDay => <crate::TimeOfDay as HumantimeFormat>::EXPECTING,
+    #[cfg(feature = "chrono")]
+    crate::InvalidTimeWindow => <crate::TimeWindow as HumantimeFormat>::EXPECTING,
+    #[cfg(feature = "cron")]
+    cron::error::Error => <crate::Schedule as HumantimeFormat>::EXPECTING,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use core::time::Duration;
+
+    #[test]
+    fn kinds() {
+        assert_eq!(crate::parse::<Duration>(""), Err(Error::Empty));
+        assert_eq!(crate::parse::<Duration>("1000000000000000000000s"), Err(Error::NumberOverflow));
+        assert_eq!(
+            crate::parse::<Duration>("5 x"),
+            Err(Error::UnknownUnit { start: 2, end: 3, suggestion: None }),
+        );
+        assert_eq!(
+            crate::parse::<Duration>("10 secnds"),
+            Err(Error::UnknownUnit { start: 3, end: 9, suggestion: Some("seconds") }),
+        );
+        assert_eq!(crate::parse::<crate::Rate>("5/parsec"), Err(Error::Invalid("a rate")));
+    }
+
+    #[test]
+    #[cfg(feature = "chrono")]
+    fn timestamp_kinds() {
+        assert_eq!(
+            crate::parse::<chrono::DateTime<chrono::Utc>>("2018-05-11T18:28:30Zjunk"),
+            Err(Error::TrailingInput),
+        );
+        assert!(matches!(
+            crate::parse::<chrono::DateTime<chrono::Utc>>("2018-05-11"),
+            Err(Error::InvalidTimestamp(_)),
+        ));
+    }
+}
@@ -0,0 +1,173 @@
+#[cfg(feature = "chrono")]
+use core::convert::TryFrom;
+use core::fmt;
+use core::str::FromStr;
+use core::time::Duration;
+
+#[cfg(feature = "chrono")]
+use chrono::{DateTime, TimeDelta, TimeZone};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use super::format::{HumantimeFormat, Ref};
+use super::Serde;
+
+/// A fixed period recurrence, written `"every 5m"` or `"every 2 hours"`.
+///
+/// Occurrences fall a whole number of periods after an anchor, such as the
+/// process start or a configured epoch.
+///
+/// # Example
+///
+/// ```
+/// use core::time::Duration;
+/// use chrono::{DateTime, Utc};
+/// use humantime_serde::Every;
+///
+/// let every: Every = "every 2 hours".parse().unwrap();
+/// assert_eq!(every.period(), Duration::from_secs(7200));
+/// assert_eq!(every.to_string(), "every 2h");
+///
+/// let anchor = DateTime::<Utc>::UNIX_EPOCH;
+/// let next: Vec<_> = every.occurrences(anchor).take(2).map(|t| t.timestamp()).collect();
+/// assert_eq!(next, [0, 7200]);
+/// ```
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq, Ord, PartialOrd)]
+pub struct Every(Duration);
+
+impl Every {
+    /// Creates a recurrence every `period`, or `None` if it is zero.
+    pub fn new(period: Duration) -> Option<Every> {
+        if period == Duration::ZERO {
+            None
+        } else {
+            Some(Every(period))
+        }
+    }
+
+    /// The time between occurrences.
+    pub fn period(&self) -> Duration {
+        self.0
+    }
+
+    /// The occurrences from `anchor` on, starting with `anchor` itself.
+    ///
+    /// The iterator ends when the next occurrence is out of range.
+    #[cfg(feature = "chrono")]
+    pub fn occurrences<Tz>(&self, anchor: DateTime<Tz>) -> impl Iterator<Item = DateTime<Tz>>
+    where
+        Tz: TimeZone,
+    {
+        let period = TimeDelta::from_std(self.0).ok();
+        core::iter::successors(Some(anchor), move |t| t.clone().checked_add_signed(period?))
+    }
+
+    /// The first occurrence strictly after `t`, counting from `anchor`.
+    #[cfg(feature = "chrono")]
+    pub fn next_after<Tz>(&self, anchor: DateTime<Tz>, t: &DateTime<Tz>) -> Option<DateTime<Tz>>
+    where
+        Tz: TimeZone,
+    {
+        let period = self.0.as_nanos() as i128;
+        let elapsed = t.clone().signed_duration_since(anchor.clone()).num_nanoseconds()? as i128;
+        // Periods elapsed so far, rounded down, plus one.
+        let n = elapsed.div_euclid(period) + 1;
+        let offset = i64::try_from(n * period).ok()?;
+        anchor.checked_add_signed(TimeDelta::nanoseconds(offset))
+    }
+}
+
+/// The error returned when parsing an [`Every`] recurrence fails.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct InvalidEvery;
+
+impl fmt::Display for InvalidEvery {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("invalid recurrence, expected e.g. every 5m")
+    }
+}
+
+impl core::error::Error for InvalidEvery {}
+
+impl HumantimeFormat for Every {
+    const EXPECTING: &'static str = "a recurrence";
+
+    type Err = InvalidEvery;
+
+    fn parse(s: &str) -> Result<Every, InvalidEvery> {
+        let period = s.trim().strip_prefix("every ").ok_or(InvalidEvery)?;
+        let period = Duration::parse(period.trim()).map_err(|_| InvalidEvery)?;
+        Every::new(period).ok_or(InvalidEvery)
+    }
+
+    fn format(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("every ")?;
+        self.0.format(f)
+    }
+}
+
+impl fmt::Display for Every {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.format(f)
+    }
+}
+
+impl FromStr for Every {
+    type Err = InvalidEvery;
+
+    fn from_str(s: &str) -> Result<Every, InvalidEvery> {
+        Every::parse(s)
+    }
+}
+
+impl Serialize for Every {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        Ref(self).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Every {
+    fn deserialize<D>(d: D) -> Result<Every, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Serde::deserialize(d).map(Serde::into_inner)
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod test {
+    use super::*;
+    use alloc::string::ToString;
+    #[cfg(feature = "chrono")]
+    use chrono::Utc;
+
+    #[test]
+    fn parse() {
+        assert_eq!("every 5m".parse(), Ok(Every(Duration::from_secs(300))));
+        assert_eq!("every 0s".parse::<Every>(), Err(InvalidEvery));
+        assert_eq!("5m".parse::<Every>(), Err(InvalidEvery));
+        assert_eq!(Every::new(Duration::ZERO), None);
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn next_after() {
+        let every: Every = "every 10s".parse().unwrap();
+        let anchor = DateTime::<Utc>::UNIX_EPOCH + Duration::from_secs(5);
+        let at = |s| DateTime::<Utc>::UNIX_EPOCH + Duration::from_secs(s);
+        assert_eq!(every.next_after(anchor, &at(5)), Some(at(15)));
+        assert_eq!(every.next_after(anchor, &at(14)), Some(at(15)));
+        assert_eq!(every.next_after(anchor, &at(0)), Some(at(5)));
+    }
+
+    #[test]
+    fn serde() {
+        let every: Every = serde_json::from_str("\"every 90s\"").unwrap();
+        assert_eq!(serde_json::to_string(&every).unwrap(), "\"every 1m 30s\"");
+        let err = serde_json::from_str::<Every>("\"hourly\"").unwrap_err();
+        assert!(err.to_string().contains("expected a recurrence"));
+    }
+}
@@ -0,0 +1,148 @@
+use core::fmt;
+use core::str::FromStr;
+use core::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use super::format::{HumantimeFormat, Ref};
+use super::{DeadlineError, DurationOrTimestamp, Serde};
+
+/// When something expires: `"never"`, at an RFC 3339 timestamp, or after a
+/// duration such as `"1h"`.
+///
+/// It serializes back to the form it was written in. A relative expiry is
+/// measured from a start time supplied when checking it, typically when the
+/// entry was created.
+///
+/// # Example
+///
+/// ```
+/// use core::time::Duration;
+/// use chrono::{DateTime, Utc};
+/// use serde::{Serialize, Deserialize};
+/// use humantime_serde::Expiry;
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct Cache {
+///     ttl: Expiry,
+/// }
+///
+/// let created = DateTime::<Utc>::UNIX_EPOCH;
+/// let cache: Cache = serde_json::from_str(r#"{"ttl": "1h"}"#).unwrap();
+/// assert!(!cache.ttl.is_expired(created, created + Duration::from_secs(60)));
+/// assert!(cache.ttl.is_expired(created, created + Duration::from_secs(3600)));
+///
+/// let cache: Cache = serde_json::from_str(r#"{"ttl": "never"}"#).unwrap();
+/// assert_eq!(cache.ttl, Expiry::Never);
+/// assert_eq!(serde_json::to_string(&cache).unwrap(), r#"{"ttl":"never"}"#);
+/// ```
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub enum Expiry {
+    /// Never expires.
+    Never,
+    /// Expires at a point in time.
+    At(DateTime<Utc>),
+    /// Expires a duration after the start time.
+    In(Duration),
+}
+
+impl Expiry {
+    /// Returns when the expiry falls, measuring durations from `since`, or
+    /// `None` if it never does.
+    pub fn expires_at(&self, since: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        match *self {
+            Expiry::Never => None,
+            Expiry::At(t) => Some(t),
+            Expiry::In(d) => Some(DurationOrTimestamp::Duration(d).resolve(since)),
+        }
+    }
+
+    /// Returns whether the expiry has passed at `now`, measuring durations
+    /// from `since`.
+    pub fn is_expired(&self, since: DateTime<Utc>, now: DateTime<Utc>) -> bool {
+        self.expires_at(since).map_or(false, |at| now >= at)
+    }
+}
+
+impl HumantimeFormat for Expiry {
+    const EXPECTING: &'static str = "\"never\", a duration or a timestamp";
+
+    type Err = DeadlineError;
+
+    fn parse(s: &str) -> Result<Expiry, DeadlineError> {
+        if s == "never" {
+            return Ok(Expiry::Never);
+        }
+        DurationOrTimestamp::parse(s).map(|v| match v {
+            DurationOrTimestamp::Duration(d) => Expiry::In(d),
+            DurationOrTimestamp::Timestamp(t) => Expiry::At(t),
+        })
+    }
+
+    fn format(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Expiry::Never => f.write_str("never"),
+            Expiry::At(t) => HumantimeFormat::format(t, f),
+            Expiry::In(d) => d.format(f),
+        }
+    }
+}
+
+impl fmt::Display for Expiry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.format(f)
+    }
+}
+
+impl FromStr for Expiry {
+    type Err = DeadlineError;
+
+    fn from_str(s: &str) -> Result<Expiry, DeadlineError> {
+        Expiry::parse(s)
+    }
+}
+
+impl Serialize for Expiry {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        Ref(self).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Expiry {
+    fn deserialize<D>(d: D) -> Result<Expiry, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Serde::deserialize(d).map(Serde::into_inner)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloc::string::ToString;
+
+    #[test]
+    fn serde() {
+        for s in &["\"never\"", "\"15m\"", "\"2018-05-11T18:28:30Z\""] {
+            let v: Expiry = serde_json::from_str(s).unwrap();
+            assert_eq!(serde_json::to_string(&v).unwrap(), *s);
+        }
+        let err = serde_json::from_str::<Expiry>("\"Never\"").unwrap_err();
+        assert!(err.to_string().contains("expected \"never\", a duration or a timestamp"));
+    }
+
+    #[test]
+    fn expired() {
+        let since = DateTime::<Utc>::UNIX_EPOCH;
+        let at = since + Duration::from_secs(10);
+        assert!(!Expiry::Never.is_expired(since, DateTime::<Utc>::MAX_UTC));
+        assert!(!Expiry::At(at).is_expired(since, since));
+        assert!(Expiry::At(at).is_expired(since, at));
+        assert_eq!(Expiry::In(Duration::from_secs(10)).expires_at(since), Some(at));
+    }
+}
@@ -0,0 +1,117 @@
+//! A single pass duration parser for the common case.
+//!
+//! It accepts a subset of humantime's syntax, ASCII input with the units of
+//! [`grammar`](crate::grammar), and gives up on anything else so humantime
+//! can produce the value or the exact error.
+
+use core::convert::TryFrom;
+use core::time::Duration;
+
+use super::grammar::DURATION_UNITS;
+
+const NANOS_PER_SEC: u128 = 1_000_000_000;
+
+/// Parses `s` as humantime would, or returns `None` if `s` is invalid or
+/// outside the subset handled here.
+pub(crate) fn parse_duration(s: &str) -> Option<Duration> {
+    let b = s.as_bytes();
+    let mut i = 0;
+    let mut total: u128 = 0;
+    let mut any = false;
+    loop {
+        while i < b.len() && b[i].is_ascii_whitespace() {
+            i += 1;
+        }
+        if i == b.len() {
+            break;
+        }
+        if !b[i].is_ascii_digit() {
+            return None;
+        }
+        // humantime allows whitespace between the digits of a number.
+        let mut n: u64 = 0;
+        while i < b.len() {
+            match b[i] {
+                c @ b'0'..=b'9' => n = n.checked_mul(10)?.checked_add(u64::from(c - b'0'))?,
+                c if c.is_ascii_whitespace() => {}
+                _ => break,
+            }
+            i += 1;
+        }
+        let start = i;
+        while i < b.len() && b[i].is_ascii_alphabetic() {
+            i += 1;
+        }
+        total = total.checked_add(u128::from(n) * unit_nanos(&b[start..i])?)?;
+        any = true;
+        if i < b.len() && !b[i].is_ascii_whitespace() && !b[i].is_ascii_digit() {
+            return None;
+        }
+    }
+    if !any {
+        return None;
+    }
+    let secs = u64::try_from(total / NANOS_PER_SEC).ok()?;
+    Some(Duration::new(secs, (total % NANOS_PER_SEC) as u32))
+}
+
+fn unit_nanos(unit: &[u8]) -> Option<u128> {
+    DURATION_UNITS
+        .iter()
+        .find(|u| u.suffixes.iter().any(|s| s.as_bytes() == unit))
+        .map(|u| u.length.as_nanos())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn check(s: &str) {
+        if let Some(d) = parse_duration(s) {
+            assert_eq!(humantime::parse_duration(s).ok(), Some(d), "{:?}", s);
+        }
+    }
+
+    #[test]
+    fn common() {
+        for s in ["15s", "1h 30m", "2 days", "500ms", "1year 6months", "1 0s", " 5m ", "3us2ns"] {
+            assert_eq!(parse_duration(s), humantime::parse_duration(s).ok(), "{:?}", s);
+        }
+        for s in ["", " ", "5", "5 x", "5s,", "-5s", "5µs", "18446744073709551616s"] {
+            assert_eq!(parse_duration(s), None, "{:?}", s);
+        }
+        assert_eq!(parse_duration("18446744073709551615s"), Some(Duration::from_secs(u64::MAX)));
+        assert_eq!(parse_duration("18446744073709551615s 1s"), None);
+    }
+
+    // Compares with humantime on strings of pieces of its grammar glued
+    // together at random.
+    #[test]
+    fn equivalence() {
+        const PIECES: &[&str] = &[
+            "0", "1", "7", "42", "999999999", "18446744073709551615", " ", "  ", "\t", "s", "ms",
+            "m", "M", "min", "mins", "h", "hr", "days", "w", "y", "years", "ns", "nsec", "us",
+            "µs", "x", ".", ",", "-", "+",
+        ];
+        let mut state = 0x2545_f491_4f6c_dd1du64;
+        let mut next = || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        };
+        let mut buf = [0u8; 64];
+        for _ in 0..100_000 {
+            let mut len = 0;
+            for _ in 0..next() % 8 {
+                let piece = PIECES[(next() % PIECES.len() as u64) as usize].as_bytes();
+                if len + piece.len() > buf.len() {
+                    break;
+                }
+                buf[len..len + piece.len()].copy_from_slice(piece);
+                len += piece.len();
+            }
+            check(core::str::from_utf8(&buf[..len]).unwrap());
+        }
+    }
+}
@@ -0,0 +1,500 @@
+//! The `HumantimeFormat` trait, which the `Serialize`/`Deserialize` impls of
+//! `Serde` and the `serialize`/`deserialize` functions are written against.
+//!
+//! Supporting a new type only takes an implementation of the trait below;
+//! `Serde<T>`, `Serde<Option<T>>` and the `with` modules then follow.
+//!
+//! The trait is sealed unless the `unstable-format` feature is enabled, in
+//! which case other crates may implement it for their own types. The trait
+//! is not covered by semver while unsealed: its items may change in minor
+//! releases.
+//!
+#![cfg_attr(feature = "unstable-format", doc = "```")]
+#![cfg_attr(not(feature = "unstable-format"), doc = "```ignore")]
+//! use core::fmt;
+//! use serde::{Serialize, Deserialize};
+//! use humantime_serde::format::HumantimeFormat;
+//! use humantime_serde::Serde;
+//!
+//! /// A number of whole seconds.
+//! #[derive(Debug, PartialEq)]
+//! struct Seconds(u64);
+//!
+//! impl HumantimeFormat for Seconds {
+//!     const EXPECTING: &'static str = "a whole number of seconds";
+//!
+//!     type Err = humantime::DurationError;
+//!
+//!     fn parse(s: &str) -> Result<Seconds, humantime::DurationError> {
+//!         humantime::parse_duration(s).map(|d| Seconds(d.as_secs()))
+//!     }
+//!
+//!     fn format(&self, f: &mut fmt::Formatter) -> fmt::Result {
+//!         write!(f, "{}s", self.0)
+//!     }
+//! }
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Foo {
+//!     #[serde(with = "humantime_serde")]
+//!     timeout: Seconds,
+//!     #[serde(with = "humantime_serde::option", default)]
+//!     idle: Option<Seconds>,
+//!     retries: Vec<Serde<Seconds>>,
+//! }
+//! ```
+
+use core::fmt;
+use core::time::Duration;
+
+#[cfg(feature = "chrono")]
+use chrono::{DateTime, Datelike, FixedOffset, Offset, TimeZone, Timelike, Utc};
+use serde::{Serialize, Serializer};
+
+use crate::backend::{Backend, Selected};
+
+#[cfg(not(feature = "unstable-format"))]
+mod sealed {
+    #[cfg(feature = "chrono")]
+    use chrono::{DateTime, FixedOffset, Utc};
+    use core::time::Duration;
+
+    pub trait Sealed {}
+
+    impl Sealed for Duration {}
+    #[cfg(feature = "chrono")]
+    impl Sealed for crate::Age {}
+    impl Sealed for crate::BackoffSpec {}
+    #[cfg(feature = "alloc")]
+    impl Sealed for crate::Buckets {}
+    #[cfg(feature = "chrono")]
+    impl Sealed for DateTime<Utc> {}
+    #[cfg(feature = "chrono")]
+    impl Sealed for DateTime<FixedOffset> {}
+    #[cfg(feature = "chrono")]
+    impl Sealed for crate::DurationOrTimestamp {}
+    impl Sealed for crate::Every {}
+    #[cfg(feature = "chrono")]
+    impl Sealed for crate::Expiry {}
+    #[cfg(feature = "chrono")]
+    impl Sealed for crate::iso::Interval {}
+    #[cfg(feature = "chrono")]
+    impl Sealed for crate::iso::RepeatingInterval {}
+    impl Sealed for crate::Jittered {}
+    impl Sealed for crate::Period {}
+    impl Sealed for crate::Rate {}
+    #[cfg(feature = "alloc")]
+    impl Sealed for crate::RawDuration {}
+    #[cfg(feature = "natural")]
+    impl Sealed for crate::Recurrence {}
+    #[cfg(feature = "cron")]
+    impl Sealed for crate::Schedule {}
+    #[cfg(feature = "chrono")]
+    impl Sealed for crate::TimeOfDay {}
+    #[cfg(feature = "chrono")]
+    impl Sealed for crate::TimeWindow {}
+    impl<const DEFAULT_MS: u64> Sealed for crate::Timeout<DEFAULT_MS> {}
+}
+
+#[cfg(feature = "unstable-format")]
+mod sealed {
+    pub trait Sealed {}
+
+    impl<T: ?Sized> Sealed for T {}
+}
+
+/// A type with a humantime string representation.
+///
+/// See the [module documentation](self) for implementing it on other types.
+pub trait HumantimeFormat: Sized + sealed::Sealed {
+    /// What a deserializer expects, e.g. `"a duration"`.
+    const EXPECTING: &'static str;
+
+    /// The error returned when parsing fails.
+    type Err: fmt::Display;
+
+    /// Parses the string representation.
+    fn parse(s: &str) -> Result<Self, Self::Err>;
+
+    /// Writes the string representation.
+    fn format(&self, f: &mut fmt::Formatter) -> fmt::Result;
+
+    /// Whether `err` means the input was well-formed but too large, which
+    /// deserialization reports separately from invalid input.
+    fn is_overflow(err: &Self::Err) -> bool {
+        let _ = err;
+        false
+    }
+
+    /// A known unit spelled like the one `err` rejected in `s`, which
+    /// deserialization appends to its message.
+    fn suggestion(s: &str, err: &Self::Err) -> Option<&'static str> {
+        let _ = (s, err);
+        None
+    }
+}
+
+/// A borrowed value serialized through its `HumantimeFormat` impl.
+///
+/// This is what the `serialize` functions go through, since `Serde<&T>`
+/// cannot be implemented generically next to `Serde<T>`. It is implemented
+/// for `T` and `Option<T>` where `T: HumantimeFormat`.
+pub struct Ref<'a, T: ?Sized>(pub &'a T);
+
+impl<T> fmt::Display for Ref<'_, T>
+where
+    T: HumantimeFormat,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.format(f)
+    }
+}
+
+impl<T> Serialize for Ref<'_, T>
+where
+    T: HumantimeFormat,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        // Formats straight into the serializer rather than a `String`.
+        #[cfg(feature = "std")]
+        {
+            crate::scratch::serialize(self, serializer)
+        }
+        #[cfg(not(feature = "std"))]
+        {
+            serializer.collect_str(self)
+        }
+    }
+}
+
+impl<T> Serialize for Ref<'_, Option<T>>
+where
+    T: HumantimeFormat,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self.0 {
+            Some(v) => serializer.serialize_some(&Ref(v)),
+            None => serializer.serialize_none(),
+        }
+    }
+}
+
+/// A `fmt::Write` filling a byte slice, failing once it is full.
+pub(crate) struct SliceWriter<'b> {
+    buf: &'b mut [u8],
+    len: usize,
+}
+
+impl<'b> SliceWriter<'b> {
+    pub(crate) fn new(buf: &'b mut [u8]) -> SliceWriter<'b> {
+        SliceWriter { buf, len: 0 }
+    }
+
+    pub(crate) fn into_str(self) -> Result<&'b str, fmt::Error> {
+        core::str::from_utf8(&self.buf[..self.len]).map_err(|_| fmt::Error)
+    }
+}
+
+impl fmt::Write for SliceWriter<'_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let end = self.len + s.len();
+        let dst = self.buf.get_mut(self.len..end).ok_or(fmt::Error)?;
+        dst.copy_from_slice(s.as_bytes());
+        self.len = end;
+        Ok(())
+    }
+}
+
+/// Displays a value exactly as it is serialized, without allocating.
+///
+/// # Example
+///
+/// ```
+/// use core::time::Duration;
+/// use humantime_serde::HumanDisplay;
+///
+/// let timeout = Duration::from_secs(90);
+/// assert_eq!(format!("timeout={}", HumanDisplay::duration(&timeout)), "timeout=1m 30s");
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct HumanDisplay<'a, T: ?Sized>(&'a T);
+
+impl<'a, T> HumanDisplay<'a, T>
+where
+    T: HumantimeFormat,
+{
+    /// Displays any supported value.
+    pub fn new(v: &'a T) -> HumanDisplay<'a, T> {
+        HumanDisplay(v)
+    }
+}
+
+impl<'a> HumanDisplay<'a, Duration> {
+    /// Displays a `Duration`.
+    pub fn duration(d: &'a Duration) -> HumanDisplay<'a, Duration> {
+        HumanDisplay(d)
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl<'a> HumanDisplay<'a, DateTime<Utc>> {
+    /// Displays a `DateTime<Utc>`.
+    pub fn timestamp(t: &'a DateTime<Utc>) -> HumanDisplay<'a, DateTime<Utc>> {
+        HumanDisplay(t)
+    }
+}
+
+impl<T> fmt::Display for HumanDisplay<'_, T>
+where
+    T: HumantimeFormat,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.format(f)
+    }
+}
+
+impl HumantimeFormat for Duration {
+    const EXPECTING: &'static str = "a duration";
+
+    type Err = humantime::DurationError;
+
+    fn parse(s: &str) -> Result<Duration, humantime::DurationError> {
+        Selected::parse_duration(s)
+    }
+
+    fn format(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        Selected::format_duration(*self, f)
+    }
+
+    fn is_overflow(err: &humantime::DurationError) -> bool {
+        matches!(err, humantime::DurationError::NumberOverflow)
+    }
+
+    fn suggestion(s: &str, err: &humantime::DurationError) -> Option<&'static str> {
+        match *err {
+            humantime::DurationError::UnknownUnit { start, end, .. } => {
+                s.get(start..end).and_then(crate::grammar::suggest_unit)
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl HumantimeFormat for DateTime<Utc> {
+    const EXPECTING: &'static str = "a timestamp";
+
+    type Err = chrono::ParseError;
+
+    fn parse(s: &str) -> Result<DateTime<Utc>, chrono::ParseError> {
+        DateTime::<FixedOffset>::parse(s).map(|t| t.to_utc())
+    }
+
+    fn format(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        #[cfg(feature = "alloc")]
+        let write = crate::config::format;
+        #[cfg(not(feature = "alloc"))]
+        let write = Selected::format_timestamp;
+        write(self, f)
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl HumantimeFormat for DateTime<FixedOffset> {
+    const EXPECTING: &'static str = "a timestamp";
+
+    type Err = chrono::ParseError;
+
+    fn parse(s: &str) -> Result<DateTime<FixedOffset>, chrono::ParseError> {
+        // Consults the process-wide defaults where there are any.
+        #[cfg(feature = "alloc")]
+        let parse = crate::config::parse;
+        #[cfg(not(feature = "alloc"))]
+        let parse = Selected::parse_timestamp;
+        parse(s)
+    }
+
+    fn format(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        #[cfg(feature = "alloc")]
+        let write = crate::config::format;
+        #[cfg(not(feature = "alloc"))]
+        let write = Selected::format_timestamp;
+        write(self, f)
+    }
+}
+
+/// Writes `t` as `to_rfc3339_opts(SecondsFormat::Secs, true)` would, without
+/// going through a `String` or `fmt` machinery for the individual fields.
+#[cfg(feature = "chrono")]
+pub(crate) fn write_rfc3339<Tz>(t: &DateTime<Tz>, f: &mut fmt::Formatter) -> fmt::Result
+where
+    Tz: TimeZone,
+{
+    let mut buf = Rfc3339Buf::new();
+    buf.push_timestamp(t);
+    f.write_str(buf.as_str()?)
+}
+
+/// A fixed-width stack buffer for `[+-]YYYYYY-MM-DDTHH:MM:SS+HH:MM`, the
+/// longest timestamp `write_rfc3339` produces.
+#[cfg(feature = "chrono")]
+struct Rfc3339Buf {
+    bytes: [u8; 32],
+    len: usize,
+}
+
+#[cfg(feature = "chrono")]
+impl Rfc3339Buf {
+    fn new() -> Rfc3339Buf {
+        Rfc3339Buf {
+            bytes: [0; 32],
+            len: 0,
+        }
+    }
+
+    fn push(&mut self, b: u8) {
+        self.bytes[self.len] = b;
+        self.len += 1;
+    }
+
+    /// Pushes `n` in decimal, zero-padded to `width` digits.
+    fn push_num(&mut self, mut n: u32, width: usize) {
+        let mut digits = 1;
+        let mut rest = n / 10;
+        while rest > 0 {
+            digits += 1;
+            rest /= 10;
+        }
+        let digits = digits.max(width);
+        for i in (0..digits).rev() {
+            self.bytes[self.len + i] = b'0' + (n % 10) as u8;
+            n /= 10;
+        }
+        self.len += digits;
+    }
+
+    fn push_timestamp<Tz>(&mut self, t: &DateTime<Tz>)
+    where
+        Tz: TimeZone,
+    {
+        let local = t.naive_local();
+        let year = local.year();
+        if !(0..=9999).contains(&year) {
+            self.push(if year < 0 { b'-' } else { b'+' });
+        }
+        self.push_num(year.unsigned_abs(), 4);
+        self.push(b'-');
+        self.push_num(local.month(), 2);
+        self.push(b'-');
+        self.push_num(local.day(), 2);
+        self.push(b'T');
+        self.push_num(local.hour(), 2);
+        self.push(b':');
+        self.push_num(local.minute(), 2);
+        self.push(b':');
+        // Leap seconds are stored as an extra second of nanoseconds.
+        self.push_num(local.second() + local.nanosecond() / 1_000_000_000, 2);
+        let offset = t.offset().fix().local_minus_utc();
+        if offset == 0 {
+            return self.push(b'Z');
+        }
+        self.push(if offset < 0 { b'-' } else { b'+' });
+        let minutes = (offset.unsigned_abs() + 30) / 60;
+        self.push_num(minutes / 60, 2);
+        self.push(b':');
+        self.push_num(minutes % 60, 2);
+    }
+
+    fn as_str(&self) -> Result<&str, fmt::Error> {
+        core::str::from_utf8(&self.bytes[..self.len]).map_err(|_| fmt::Error)
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod test {
+    use super::*;
+    use alloc::format;
+    #[cfg(feature = "chrono")]
+    use chrono::{NaiveDate, SecondsFormat};
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn rfc3339() {
+        let utc = DateTime::<Utc>::UNIX_EPOCH + Duration::new(1526063310, 500);
+        let east = utc.with_timezone(&FixedOffset::east_opt(5 * 3600 + 45 * 60).unwrap());
+        let west = utc.with_timezone(&FixedOffset::west_opt(3 * 3600).unwrap());
+        let far = NaiveDate::from_ymd_opt(12345, 1, 2).unwrap().and_hms_opt(3, 4, 5).unwrap().and_utc();
+        let bc = NaiveDate::from_ymd_opt(-44, 3, 15).unwrap().and_hms_opt(12, 0, 0).unwrap().and_utc();
+        let leap = NaiveDate::from_ymd_opt(2016, 12, 31)
+            .unwrap()
+            .and_hms_nano_opt(23, 59, 59, 1_000_000_000)
+            .unwrap()
+            .and_utc();
+        for t in &[utc, far, bc, leap] {
+            assert_eq!(format!("{}", HumanDisplay::timestamp(t)), t.to_rfc3339_opts(SecondsFormat::Secs, true));
+        }
+        for t in &[east, west] {
+            assert_eq!(format!("{}", HumanDisplay::new(t)), t.to_rfc3339_opts(SecondsFormat::Secs, true));
+        }
+    }
+
+    #[test]
+    fn duration() {
+        let d = Duration::from_millis(1500);
+        assert_eq!(format!("{}", HumanDisplay::duration(&d)), "1s 500ms");
+    }
+
+    #[cfg(feature = "unstable-format")]
+    mod extension {
+        use super::*;
+        use alloc::string::ToString;
+        use alloc::vec::Vec;
+        use serde::Deserialize;
+
+        use crate::Serde;
+
+        #[derive(Debug, PartialEq)]
+        struct Seconds(u64);
+
+        impl HumantimeFormat for Seconds {
+            const EXPECTING: &'static str = "a whole number of seconds";
+
+            type Err = humantime::DurationError;
+
+            fn parse(s: &str) -> Result<Seconds, humantime::DurationError> {
+                humantime::parse_duration(s).map(|d| Seconds(d.as_secs()))
+            }
+
+            fn format(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                write!(f, "{}s", self.0)
+            }
+        }
+
+        #[test]
+        fn extension() {
+            #[derive(Serialize, Deserialize)]
+            struct Foo {
+                #[serde(with = "crate")]
+                timeout: Seconds,
+                #[serde(with = "crate::option", default)]
+                idle: Option<Seconds>,
+                retries: Vec<Serde<Seconds>>,
+            }
+
+            let json = r#"{"timeout": "1m", "retries": ["1s", "2s"]}"#;
+            let foo = serde_json::from_str::<Foo>(json).unwrap();
+            assert_eq!(foo.timeout, Seconds(60));
+            assert_eq!(foo.idle, None);
+            assert_eq!(*foo.retries[1], Seconds(2));
+            let reverse = serde_json::to_string(&foo).unwrap();
+            assert_eq!(reverse, r#"{"timeout":"60s","idle":null,"retries":["1s","2s"]}"#);
+            assert_eq!("90s".parse::<Serde<Seconds>>().unwrap().to_string(), "90s");
+        }
+    }
+}
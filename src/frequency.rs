@@ -0,0 +1,241 @@
+//! A `with` module for `Duration` periods written as frequencies, such as
+//! `10Hz` for 100ms.
+//!
+//! Frequencies are a decimal number followed by `Hz`, `kHz`, `MHz` or `GHz`,
+//! optionally separated by spaces. The period is rounded to the nearest
+//! nanosecond, so frequencies above 2GHz are rejected.
+//!
+//! Periods are written back in the largest unit keeping the number at least
+//! one, with the fewest decimals that parse back to the same period.
+//!
+//! # Example
+//!
+//! ```
+//! use core::time::Duration;
+//! use serde::{Serialize, Deserialize};
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Sampler {
+//!     #[serde(with = "humantime_serde::frequency")]
+//!     period: Duration,
+//! }
+//!
+//! let sampler: Sampler = serde_json::from_str(r#"{"period": "2.5kHz"}"#).unwrap();
+//! assert_eq!(sampler.period, Duration::from_micros(400));
+//!
+//! let sampler = Sampler { period: Duration::from_millis(100) };
+//! assert_eq!(serde_json::to_string(&sampler).unwrap(), r#"{"period":"10Hz"}"#);
+//! ```
+
+use core::convert::TryFrom;
+use core::fmt;
+use core::time::Duration;
+
+use serde::{de, ser, Deserializer, Serializer};
+
+use crate::Error;
+
+const NANOS_PER_SEC: u128 = 1_000_000_000;
+
+/// The units, each a thousand times the previous.
+const UNITS: [&str; 4] = ["Hz", "kHz", "MHz", "GHz"];
+
+/// The most decimals read, enough to tell apart the frequencies of periods
+/// up to a century.
+const MAX_DECIMALS: u32 = 29;
+
+/// The period of `mantissa / 10^decimals` of the unit at index `unit`, in
+/// nanoseconds, rounded to the nearest.
+fn period_nanos(mantissa: u128, decimals: u32, unit: usize) -> u128 {
+    let num = NANOS_PER_SEC * 10u128.pow(decimals);
+    let den = mantissa * 1000u128.pow(unit as u32);
+    (num + den / 2) / den
+}
+
+/// Parses a frequency into its period.
+pub fn parse(s: &str) -> Result<Duration, Error> {
+    if s.is_empty() {
+        return Err(Error::Empty);
+    }
+    let end = s.find(|c: char| !c.is_ascii_digit() && c != '.').unwrap_or(s.len());
+    let (int, frac) = match s[..end].split_once('.') {
+        Some((int, frac)) => (int, frac),
+        None => (&s[..end], ""),
+    };
+    if int.is_empty() {
+        return Err(Error::NumberExpected(0));
+    }
+    if let Some(i) = frac.find('.') {
+        return Err(Error::InvalidCharacter(int.len() + 1 + i));
+    }
+    if frac.len() > MAX_DECIMALS as usize {
+        return Err(Error::NumberOverflow);
+    }
+    let mut mantissa: u128 = 0;
+    for b in int.bytes().chain(frac.bytes()) {
+        mantissa = mantissa
+            .checked_mul(10)
+            .and_then(|m| m.checked_add(u128::from(b - b'0')))
+            .filter(|&m| m <= u128::from(u64::MAX))
+            .ok_or(Error::NumberOverflow)?;
+    }
+    let start = end + (s.len() - end - s[end..].trim_start_matches(' ').len());
+    let unit = UNITS.iter().position(|&u| u == &s[start..]).ok_or(Error::UnknownUnit {
+        start,
+        end: s.len(),
+        suggestion: None,
+    })?;
+    if mantissa == 0 {
+        return Err(Error::Invalid("a frequency above zero"));
+    }
+    match period_nanos(mantissa, frac.len() as u32, unit) {
+        0 => Err(Error::Invalid("a frequency with a period of at least 1ns")),
+        nanos => {
+            let secs = u64::try_from(nanos / NANOS_PER_SEC).map_err(|_| Error::NumberOverflow)?;
+            Ok(Duration::new(secs, (nanos % NANOS_PER_SEC) as u32))
+        }
+    }
+}
+
+/// Formats the frequency of the period `d`.
+///
+/// Fails on a zero period. Periods too long to be told apart from their
+/// neighbours, beyond a century, are written as the closest frequency.
+pub fn format(d: Duration) -> Result<impl fmt::Display, Error> {
+    let nanos = d.as_nanos();
+    if nanos == 0 {
+        return Err(Error::Invalid("a period above zero"));
+    }
+    let unit = (1..UNITS.len())
+        .rev()
+        .find(|&unit| nanos * 1000u128.pow(unit as u32) <= NANOS_PER_SEC)
+        .unwrap_or(0);
+    let scale = nanos * 1000u128.pow(unit as u32);
+    let mut closest = None;
+    for decimals in 0..=MAX_DECIMALS {
+        let num = NANOS_PER_SEC * 10u128.pow(decimals);
+        let mantissa = (num + scale / 2) / scale;
+        if mantissa > u128::from(u64::MAX) {
+            break;
+        }
+        if mantissa == 0 {
+            continue;
+        }
+        closest = Some(Frequency { mantissa, decimals, unit });
+        if period_nanos(mantissa, decimals, unit) == nanos {
+            break;
+        }
+    }
+    closest.ok_or(Error::NumberOverflow)
+}
+
+struct Frequency {
+    mantissa: u128,
+    decimals: u32,
+    unit: usize,
+}
+
+impl fmt::Display for Frequency {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let scale = 10u128.pow(self.decimals);
+        write!(f, "{}", self.mantissa / scale)?;
+        if self.decimals != 0 {
+            let width = self.decimals as usize;
+            write!(f, ".{:0width$}", self.mantissa % scale, width = width)?;
+        }
+        f.write_str(UNITS[self.unit])
+    }
+}
+
+/// Serializes a `Duration` as the frequency it is the period of.
+pub fn serialize<S>(d: &Duration, s: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    match format(*d) {
+        Ok(f) => s.collect_str(&f),
+        Err(err) => Err(ser::Error::custom(format_args!(
+            "cannot write {:?} as a frequency: {}",
+            d, err
+        ))),
+    }
+}
+
+/// Deserializes a `Duration` from a frequency.
+pub fn deserialize<'a, D>(d: D) -> Result<Duration, D::Error>
+where
+    D: Deserializer<'a>,
+{
+    struct V;
+
+    impl de::Visitor<'_> for V {
+        type Value = Duration;
+
+        fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+            fmt.write_str("a frequency")
+        }
+
+        fn visit_str<E>(self, v: &str) -> Result<Duration, E>
+        where
+            E: de::Error,
+        {
+            crate::check_len(v, crate::DEFAULT_MAX_LEN, "a frequency")?;
+            parse(v).map_err(|err| {
+                E::invalid_value(de::Unexpected::Str(v), &crate::Because(&self, &err))
+            })
+        }
+    }
+
+    d.deserialize_str(V)
+}
+
+crate::__option_module!([] ::core::time::Duration, []);
+
+#[cfg(all(test, feature = "alloc"))]
+mod test {
+    use super::*;
+    use alloc::string::ToString;
+
+    #[test]
+    fn parsing() {
+        assert_eq!(parse("10Hz"), Ok(Duration::from_millis(100)));
+        assert_eq!(parse("2.5kHz"), Ok(Duration::from_micros(400)));
+        assert_eq!(parse("1 MHz"), Ok(Duration::from_micros(1)));
+        assert_eq!(parse("0.1Hz"), Ok(Duration::from_secs(10)));
+        assert_eq!(parse("3Hz"), Ok(Duration::from_nanos(333_333_333)));
+        assert_eq!(parse("2GHz"), Ok(Duration::from_nanos(1)));
+        assert!(parse("3GHz").is_err());
+        assert!(parse("0Hz").is_err());
+        assert_eq!(parse(""), Err(Error::Empty));
+        assert_eq!(parse("Hz"), Err(Error::NumberExpected(0)));
+        assert_eq!(parse("1.2.3Hz"), Err(Error::InvalidCharacter(3)));
+        assert_eq!(parse("10 hz"), Err(Error::UnknownUnit { start: 3, end: 5, suggestion: None }));
+    }
+
+    #[test]
+    fn overflow() {
+        let secs = 10_000_000_000_000_000_000;
+        assert_eq!(parse("0.0000000000000000001Hz"), Ok(Duration::from_secs(secs)));
+        assert_eq!(parse("0.00000000000000000001Hz"), Err(Error::NumberOverflow));
+    }
+
+    #[test]
+    fn formatting() {
+        let format = |nanos| format(Duration::from_nanos(nanos)).unwrap().to_string();
+        assert_eq!(format(100_000_000), "10Hz");
+        assert_eq!(format(400_000), "2.5kHz");
+        assert_eq!(format(1), "1GHz");
+        assert_eq!(format(10_000_000_000), "0.1Hz");
+        assert_eq!(format(333_333_333), "3Hz");
+        assert_eq!(format(3_000), "333.3kHz");
+        assert!(super::format(Duration::ZERO).is_err());
+
+        let day = 86_400_000_000_000;
+        assert_eq!(format(day), "0.0000115740740740741Hz");
+        let century = 100 * 365 * day;
+        for nanos in [1, 7, 999, 12_345, 333_333_333, 1_000_000_007, day, century] {
+            let d = Duration::from_nanos(nanos);
+            assert_eq!(parse(&super::format(d).unwrap().to_string()), Ok(d), "{}", nanos);
+        }
+    }
+}
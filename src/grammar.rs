@@ -0,0 +1,232 @@
+//! Machine readable descriptions of the accepted input syntax.
+//!
+//! These let documentation generators and user interfaces render hints such
+//! as the list of duration units without hard-coding them.
+//!
+//! # Example
+//!
+//! ```
+//! use humantime_serde::grammar;
+//!
+//! let grammar = grammar::duration_grammar();
+//! for unit in grammar.units {
+//!     println!("{}: {}", unit.name, unit.suffixes.join(", "));
+//! }
+//! assert!(grammar::duration_examples().contains(&"1h 30m"));
+//! ```
+
+use core::time::Duration;
+
+/// Describes an accepted input syntax.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct GrammarDescription {
+    /// Short name of the syntax.
+    pub name: &'static str,
+    /// One-line summary of the syntax.
+    pub summary: &'static str,
+    /// Units accepted by the syntax, empty if it has none.
+    pub units: &'static [UnitDescription],
+    /// Valid inputs.
+    pub examples: &'static [&'static str],
+}
+
+/// Describes a unit of a duration.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct UnitDescription {
+    /// Name of the unit.
+    pub name: &'static str,
+    /// Accepted spellings of the unit.
+    pub suffixes: &'static [&'static str],
+    /// Length of one unit.
+    pub length: Duration,
+}
+
+pub(crate) const DURATION_UNITS: &[UnitDescription] = &[
+    UnitDescription {
+        name: "nanoseconds",
+        suffixes: &["nanos", "nsec", "ns"],
+        length: Duration::from_nanos(1),
+    },
+    UnitDescription {
+        name: "microseconds",
+        suffixes: &["usec", "us"],
+        length: Duration::from_micros(1),
+    },
+    UnitDescription {
+        name: "milliseconds",
+        suffixes: &["millis", "msec", "ms"],
+        length: Duration::from_millis(1),
+    },
+    UnitDescription {
+        name: "seconds",
+        suffixes: &["seconds", "second", "secs", "sec", "s"],
+        length: Duration::from_secs(1),
+    },
+    UnitDescription {
+        name: "minutes",
+        suffixes: &["minutes", "minute", "min", "mins", "m"],
+        length: Duration::from_secs(60),
+    },
+    UnitDescription {
+        name: "hours",
+        suffixes: &["hours", "hour", "hr", "hrs", "h"],
+        length: Duration::from_secs(3_600),
+    },
+    UnitDescription {
+        name: "days",
+        suffixes: &["days", "day", "d"],
+        length: Duration::from_secs(86_400),
+    },
+    UnitDescription {
+        name: "weeks",
+        suffixes: &["weeks", "week", "w"],
+        length: Duration::from_secs(604_800),
+    },
+    UnitDescription {
+        name: "months",
+        suffixes: &["months", "month", "M"],
+        length: Duration::from_secs(2_630_016),
+    },
+    UnitDescription {
+        name: "years",
+        suffixes: &["years", "year", "y"],
+        length: Duration::from_secs(31_557_600),
+    },
+];
+
+const DURATION_EXAMPLES: &[&str] = &["15s", "1h 30m", "2 days", "500ms", "1year 6months"];
+
+const TIMESTAMP_EXAMPLES: &[&str] = &[
+    "2018-05-11T18:28:30Z",
+    "2018-05-11T18:28:30.5Z",
+    "2018-05-11T20:28:30+02:00",
+];
+
+/// Describes the syntax accepted for durations.
+pub fn duration_grammar() -> GrammarDescription {
+    GrammarDescription {
+        name: "humantime duration",
+        summary: "a sequence of numbers each followed by a unit, \
+                  optionally separated by spaces",
+        units: DURATION_UNITS,
+        examples: DURATION_EXAMPLES,
+    }
+}
+
+/// Valid duration inputs.
+pub fn duration_examples() -> &'static [&'static str] {
+    DURATION_EXAMPLES
+}
+
+/// Returns the duration unit spelled most like `unit`, for error messages.
+///
+/// Spellings up to two edits away are considered, fewer for short inputs,
+/// and never one that would replace the input outright, so that a stray
+/// letter is not "corrected" into an unrelated unit.
+///
+/// # Example
+///
+/// ```
+/// use humantime_serde::grammar::suggest_unit;
+///
+/// assert_eq!(suggest_unit("mins."), Some("mins"));
+/// assert_eq!(suggest_unit("secnds"), Some("seconds"));
+/// assert_eq!(suggest_unit("parsecs"), None);
+/// ```
+pub fn suggest_unit(unit: &str) -> Option<&'static str> {
+    let max = if unit.len() > 4 { 2 } else { 1 };
+    let mut best = None;
+    for description in DURATION_UNITS {
+        for &suffix in description.suffixes {
+            match edit_distance(unit.as_bytes(), suffix.as_bytes()) {
+                Some(d) if d <= max && d < unit.len() && d < suffix.len() => {
+                    if best.map_or(true, |(b, _)| d < b) {
+                        best = Some((d, suffix));
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+    best.map(|(_, suffix)| suffix)
+}
+
+// Levenshtein distance, or `None` if `a` is too long to bother comparing;
+// every unit spelling is shorter than the buffer.
+fn edit_distance(a: &[u8], b: &[u8]) -> Option<usize> {
+    const MAX: usize = 16;
+    if a.len() >= MAX || b.len() >= MAX {
+        return None;
+    }
+    let mut row = [0; MAX];
+    for (j, cell) in row.iter_mut().enumerate().take(b.len() + 1) {
+        *cell = j;
+    }
+    for (i, &ca) in a.iter().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, &cb) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(ca != cb);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+    Some(row[b.len()])
+}
+
+/// Describes the syntax accepted for timestamps.
+pub fn timestamp_grammar() -> GrammarDescription {
+    GrammarDescription {
+        name: "RFC 3339 timestamp",
+        summary: "a date and time with an optional fractional second, \
+                  followed by `Z` or a UTC offset",
+        units: &[],
+        examples: TIMESTAMP_EXAMPLES,
+    }
+}
+
+/// Valid timestamp inputs.
+pub fn timestamp_examples() -> &'static [&'static str] {
+    TIMESTAMP_EXAMPLES
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod test {
+    use super::*;
+    use alloc::format;
+
+    #[test]
+    fn examples_parse() {
+        for example in duration_examples() {
+            humantime::parse_duration(example).unwrap();
+        }
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn timestamp_examples_parse() {
+        for example in timestamp_examples() {
+            chrono::DateTime::parse_from_rfc3339(example).unwrap();
+        }
+    }
+
+    #[test]
+    fn units_parse() {
+        for unit in duration_grammar().units {
+            for suffix in unit.suffixes {
+                let dur = humantime::parse_duration(&format!("1{}", suffix)).unwrap();
+                assert_eq!(dur, unit.length, "{}", suffix);
+            }
+        }
+    }
+
+    #[test]
+    fn suggestions() {
+        assert_eq!(suggest_unit("mins."), Some("mins"));
+        assert_eq!(suggest_unit("hours."), Some("hours"));
+        assert_eq!(suggest_unit("minuets"), Some("minutes"));
+        assert_eq!(suggest_unit("x"), None);
+        assert_eq!(suggest_unit("fortnights"), None);
+        assert_eq!(suggest_unit("a very long and unrelated word"), None);
+    }
+}
@@ -0,0 +1,165 @@
+//! Duration formats requiring a multiple of a base unit, generated with
+//! [`multiple_of!`].
+//!
+//! Schedulers ticking at a coarse resolution can't honour a duration that
+//! falls between two ticks; the generated module rejects such durations on
+//! deserialization instead of silently rounding them.
+//!
+//! The step is written in humantime syntax and parsed at compile time, so
+//! an invalid or zero step fails the build.
+//!
+//! # Example
+//!
+//! ```
+//! use core::time::Duration;
+//! use serde::{Serialize, Deserialize};
+//!
+//! humantime_serde::multiple_of!(pub mod tick: "250ms");
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Foo {
+//!     #[serde(with = "tick")]
+//!     interval: Duration,
+//! }
+//!
+//! let foo: Foo = serde_json::from_str(r#"{"interval": "1s 750ms"}"#).unwrap();
+//! assert_eq!(foo.interval, Duration::from_millis(1750));
+//!
+//! let err = serde_json::from_str::<Foo>(r#"{"interval": "1s 100ms"}"#).unwrap_err();
+//! assert!(err.to_string().starts_with("1s 100ms is not a multiple of 250ms"));
+//! ```
+//!
+//! ```compile_fail
+//! humantime_serde::multiple_of!(pub mod never: "0s");
+//! ```
+//!
+//! [`multiple_of!`]: crate::multiple_of
+
+use core::time::Duration;
+
+use serde::{de, Deserialize, Deserializer};
+
+use super::format::Ref;
+use super::Serde;
+
+/// Parses a step.
+///
+/// # Panics
+///
+/// Panics, and so fails compilation in a const, if `s` is not a valid
+/// non-zero duration.
+pub const fn step(s: &str) -> Duration {
+    let step = crate::literal::parse_duration(s);
+    if step.as_nanos() == 0 {
+        panic!("invalid step: zero");
+    }
+    step
+}
+
+/// Returns whether `d` is a whole multiple of `step`. Only zero is a
+/// multiple of a zero step.
+pub fn is_multiple_of(d: Duration, step: Duration) -> bool {
+    match step.as_nanos() {
+        0 => d == Duration::ZERO,
+        step => d.as_nanos() % step == 0,
+    }
+}
+
+/// Deserializes a `Duration`, rejecting it unless it is a multiple of
+/// `step`.
+pub fn deserialize<'a, D>(step: Duration, d: D) -> Result<Duration, D::Error>
+where
+    D: Deserializer<'a>,
+{
+    let v = Serde::<Duration>::deserialize(d)?.into_inner();
+    if is_multiple_of(v, step) {
+        Ok(v)
+    } else {
+        Err(de::Error::custom(format_args!(
+            "{} is not a multiple of {}",
+            Ref(&v),
+            Ref(&step),
+        )))
+    }
+}
+
+/// Generates a module for `#[serde(with = "...")]` accepting durations that
+/// are a multiple of a step.
+///
+/// The generated module has an `option` submodule for `Option` fields.
+///
+/// See the [`granularity`](mod@crate::granularity) module for details.
+#[macro_export]
+macro_rules! multiple_of {
+    ($(#[$attr:meta])* $vis:vis mod $name:ident : $step:literal) => {
+        $(#[$attr])*
+        $vis mod $name {
+            const STEP: ::core::time::Duration = $crate::granularity::step($step);
+
+            /// Serializes a `Duration`.
+            pub fn serialize<S>(
+                d: &::core::time::Duration,
+                s: S,
+            ) -> ::core::result::Result<S::Ok, S::Error>
+            where
+                S: $crate::re::serde::Serializer,
+            {
+                $crate::serialize(d, s)
+            }
+
+            /// Deserializes a `Duration`, rejecting it unless it is a
+            /// multiple of the step.
+            pub fn deserialize<'a, D>(
+                d: D,
+            ) -> ::core::result::Result<::core::time::Duration, D::Error>
+            where
+                D: $crate::re::serde::Deserializer<'a>,
+            {
+                $crate::granularity::deserialize(STEP, d)
+            }
+
+            $crate::__option_module!([] ::core::time::Duration, []);
+        }
+    };
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod test {
+    use super::*;
+    use alloc::string::ToString;
+    use serde::Serialize;
+
+    crate::multiple_of!(mod whole_secs: "1s");
+
+    #[derive(Debug, Serialize, Deserialize)]
+    struct Foo {
+        #[serde(with = "whole_secs")]
+        interval: Duration,
+    }
+
+    #[test]
+    fn with() {
+        let foo: Foo = serde_json::from_str(r#"{"interval": "2m"}"#).unwrap();
+        assert_eq!(foo.interval, Duration::from_secs(120));
+        assert_eq!(serde_json::to_string(&foo).unwrap(), r#"{"interval":"2m"}"#);
+
+        let err = serde_json::from_str::<Foo>(r#"{"interval": "1500ms"}"#).unwrap_err();
+        assert!(err.to_string().starts_with("1s 500ms is not a multiple of 1s"));
+    }
+
+    #[test]
+    fn multiple() {
+        let quarter = step("250ms");
+        assert!(is_multiple_of(Duration::ZERO, quarter));
+        assert!(is_multiple_of(Duration::from_millis(750), quarter));
+        assert!(!is_multiple_of(Duration::from_millis(700), quarter));
+        assert!(is_multiple_of(Duration::ZERO, Duration::ZERO));
+        assert!(!is_multiple_of(Duration::from_secs(1), Duration::ZERO));
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid step: zero")]
+    fn zero_step() {
+        step("0s");
+    }
+}
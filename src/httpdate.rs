@@ -0,0 +1,133 @@
+//! A `with` module for HTTP dates, as in `Sun, 06 Nov 1994 08:49:37 GMT`.
+//!
+//! Timestamps are written in the IMF-fixdate form of RFC 7231, in GMT. On
+//! input, the obsolete RFC 850 (`Sunday, 06-Nov-94 08:49:37 GMT`) and
+//! asctime (`Sun Nov  6 08:49:37 1994`) forms are accepted too, as HTTP
+//! recipients must. Two-digit RFC 850 years from 69 up are in the 1900s.
+//!
+//! # Example
+//!
+//! ```
+//! use chrono::{DateTime, Utc};
+//! use serde::{Serialize, Deserialize};
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Headers {
+//!     #[serde(with = "humantime_serde::httpdate")]
+//!     last_modified: DateTime<Utc>,
+//! }
+//!
+//! let json = r#"{"last_modified": "Sunday, 06-Nov-94 08:49:37 GMT"}"#;
+//! let headers: Headers = serde_json::from_str(json).unwrap();
+//! let json = serde_json::to_string(&headers).unwrap();
+//! assert_eq!(json, r#"{"last_modified":"Sun, 06 Nov 1994 08:49:37 GMT"}"#);
+//! ```
+
+use core::fmt;
+
+use chrono::{DateTime, Datelike, NaiveDateTime, TimeZone, Timelike, Utc};
+use serde::{de, Deserializer, Serializer};
+
+const IMF_FIXDATE: &str = "%a, %d %b %Y %H:%M:%S GMT";
+const RFC_850: &str = "%A, %d-%b-%y %H:%M:%S GMT";
+const ASCTIME: &str = "%a %b %e %H:%M:%S %Y";
+
+const WEEKDAYS: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+const MONTHS: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+/// Parses an HTTP date in any of the three forms.
+pub fn parse(s: &str) -> Result<DateTime<Utc>, crate::Error> {
+    let t = NaiveDateTime::parse_from_str(s, IMF_FIXDATE)
+        .or_else(|_| NaiveDateTime::parse_from_str(s, RFC_850))
+        .or_else(|_| NaiveDateTime::parse_from_str(s, ASCTIME))?;
+    Ok(t.and_utc())
+}
+
+/// Formats `t` as an IMF-fixdate.
+pub fn format<Tz>(t: &DateTime<Tz>) -> impl fmt::Display
+where
+    Tz: TimeZone,
+{
+    HttpDate(t.naive_utc())
+}
+
+struct HttpDate(NaiveDateTime);
+
+impl fmt::Display for HttpDate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let t = &self.0;
+        write!(
+            f,
+            "{}, {:02} {} {:04} {:02}:{:02}:{:02} GMT",
+            WEEKDAYS[t.weekday().num_days_from_monday() as usize],
+            t.day(),
+            MONTHS[t.month0() as usize],
+            t.year(),
+            t.hour(),
+            t.minute(),
+            t.second(),
+        )
+    }
+}
+
+/// Serializes a `DateTime<Tz>` as an IMF-fixdate.
+pub fn serialize<Tz, S>(t: &DateTime<Tz>, s: S) -> Result<S::Ok, S::Error>
+where
+    Tz: TimeZone,
+    S: Serializer,
+{
+    s.collect_str(&format(t))
+}
+
+/// Deserializes a `DateTime<Utc>` from an HTTP date in any of the three
+/// forms.
+pub fn deserialize<'a, D>(d: D) -> Result<DateTime<Utc>, D::Error>
+where
+    D: Deserializer<'a>,
+{
+    struct V;
+
+    impl de::Visitor<'_> for V {
+        type Value = DateTime<Utc>;
+
+        fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+            fmt.write_str("an HTTP date")
+        }
+
+        fn visit_str<E>(self, v: &str) -> Result<DateTime<Utc>, E>
+        where
+            E: de::Error,
+        {
+            crate::check_len(v, crate::DEFAULT_MAX_LEN, "an HTTP date")?;
+            parse(v).map_err(|err| {
+                E::invalid_value(de::Unexpected::Str(v), &crate::Because(&self, &err))
+            })
+        }
+    }
+
+    d.deserialize_str(V)
+}
+
+crate::__option_module!([] ::chrono::DateTime<::chrono::Utc>, []);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloc::string::ToString;
+
+    #[test]
+    fn forms() {
+        let expected = Utc.with_ymd_and_hms(1994, 11, 6, 8, 49, 37).unwrap();
+        assert_eq!(parse("Sun, 06 Nov 1994 08:49:37 GMT"), Ok(expected));
+        assert_eq!(parse("Sunday, 06-Nov-94 08:49:37 GMT"), Ok(expected));
+        assert_eq!(parse("Sun Nov  6 08:49:37 1994"), Ok(expected));
+        assert!(parse("Sun, 06 Nov 1994 08:49:37 +0200").is_err());
+        assert!(parse("1994-11-06T08:49:37Z").is_err());
+
+        assert_eq!(format(&expected).to_string(), "Sun, 06 Nov 1994 08:49:37 GMT");
+        let t = Utc.with_ymd_and_hms(2024, 2, 29, 23, 0, 5).unwrap();
+        assert_eq!(format(&t).to_string(), "Thu, 29 Feb 2024 23:00:05 GMT");
+    }
+}
@@ -0,0 +1,500 @@
+//! ISO 8601 durations (`PT1H30M`), intervals (`2018-05-11T18:28:30Z/PT1H`)
+//! and repeating intervals (`R5/2018-05-11T18:28:30Z/PT1H`).
+//!
+//! Only durations of a fixed length are supported: weeks, days, hours,
+//! minutes and seconds, the latter possibly fractional. Years and months,
+//! whose length depends on the calendar, are rejected.
+//!
+//! # Example
+//!
+//! ```
+//! use core::time::Duration;
+//! use humantime_serde::iso::{self, RepeatingInterval};
+//!
+//! assert_eq!(iso::parse_duration("PT1H30M").unwrap(), Duration::from_secs(5400));
+//! assert_eq!(iso::format_duration(Duration::from_millis(1500)).to_string(), "PT1.5S");
+//!
+//! let r: RepeatingInterval = "R3/2018-05-11T18:00:00Z/PT1H".parse().unwrap();
+//! let starts: Vec<_> = r.occurrences().map(|t| t.to_rfc3339()).collect();
+//! assert_eq!(starts, [
+//!     "2018-05-11T18:00:00+00:00",
+//!     "2018-05-11T19:00:00+00:00",
+//!     "2018-05-11T20:00:00+00:00",
+//! ]);
+//! ```
+
+use core::fmt;
+#[cfg(feature = "chrono")]
+use core::str::FromStr;
+use core::time::Duration;
+
+#[cfg(feature = "chrono")]
+use chrono::{DateTime, TimeDelta, Utc};
+#[cfg(feature = "chrono")]
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+#[cfg(feature = "chrono")]
+use super::format::{HumantimeFormat, Ref};
+#[cfg(feature = "chrono")]
+use super::Serde;
+
+/// The error returned when parsing ISO 8601 syntax fails.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct InvalidIso;
+
+impl fmt::Display for InvalidIso {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("invalid ISO 8601 duration or interval")
+    }
+}
+
+impl core::error::Error for InvalidIso {}
+
+/// Parses an ISO 8601 duration such as `P1DT12H` or `PT0.5S`.
+pub fn parse_duration(s: &str) -> Result<Duration, InvalidIso> {
+    let rest = s.strip_prefix('P').ok_or(InvalidIso)?;
+    let (date, time) = match rest.split_once('T') {
+        Some((date, time)) if !time.is_empty() => (date, Some(time)),
+        Some(_) => return Err(InvalidIso),
+        None => (rest, None),
+    };
+    if date.is_empty() && time.is_none() {
+        return Err(InvalidIso);
+    }
+    let mut total = Duration::ZERO;
+    for (designators, part) in [("WD", Some(date)), ("HMS", time)] {
+        let mut part = match part {
+            Some(part) => part,
+            None => continue,
+        };
+        let mut allowed = designators;
+        while !part.is_empty() {
+            let end = part
+                .find(|c: char| !c.is_ascii_digit() && c != '.' && c != ',')
+                .ok_or(InvalidIso)?;
+            let (number, unit) = (&part[..end], part[end..].chars().next().ok_or(InvalidIso)?);
+            // Designators must appear in order, each at most once.
+            let pos = allowed.find(unit).ok_or(InvalidIso)?;
+            allowed = &allowed[pos + 1..];
+            let value = if unit == 'S' {
+                parse_seconds(number)?
+            } else {
+                let n: u64 = number.parse().map_err(|_| InvalidIso)?;
+                let unit_secs = match unit {
+                    'W' => 604_800,
+                    'D' => 86_400,
+                    'H' => 3_600,
+                    _ => 60,
+                };
+                Duration::from_secs(n.checked_mul(unit_secs).ok_or(InvalidIso)?)
+            };
+            total = total.checked_add(value).ok_or(InvalidIso)?;
+            part = &part[end + 1..];
+        }
+    }
+    Ok(total)
+}
+
+fn parse_seconds(s: &str) -> Result<Duration, InvalidIso> {
+    let (secs, frac) = match s.find(|c| c == '.' || c == ',') {
+        Some(i) => (&s[..i], &s[i + 1..]),
+        None => (s, ""),
+    };
+    if secs.is_empty() || frac.len() > 9 || !frac.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(InvalidIso);
+    }
+    let secs: u64 = secs.parse().map_err(|_| InvalidIso)?;
+    let mut nanos = 0;
+    for (i, b) in frac.bytes().enumerate() {
+        nanos += u32::from(b - b'0') * 10u32.pow(8 - i as u32);
+    }
+    Ok(Duration::new(secs, nanos))
+}
+
+/// Formats a duration in ISO 8601 syntax, using hours as the largest unit.
+pub fn format_duration(d: Duration) -> impl fmt::Display {
+    FormattedDuration(d)
+}
+
+struct FormattedDuration(Duration);
+
+impl fmt::Display for FormattedDuration {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let secs = self.0.as_secs();
+        let nanos = self.0.subsec_nanos();
+        let (h, m, s) = (secs / 3600, secs / 60 % 60, secs % 60);
+        f.write_str("PT")?;
+        if h != 0 {
+            write!(f, "{}H", h)?;
+        }
+        if m != 0 {
+            write!(f, "{}M", m)?;
+        }
+        if s != 0 || nanos != 0 || (h == 0 && m == 0) {
+            write!(f, "{}", s)?;
+            if nanos != 0 {
+                let mut frac = nanos;
+                let mut width = 9;
+                while frac % 10 == 0 {
+                    frac /= 10;
+                    width -= 1;
+                }
+                write!(f, ".{:0width$}", frac, width = width)?;
+            }
+            f.write_str("S")?;
+        }
+        Ok(())
+    }
+}
+
+/// An ISO 8601 interval written as its start and its length, as in
+/// `2018-05-11T18:00:00Z/PT1H`.
+///
+/// It serializes back to the same form. The [`interval`](self::interval)
+/// module reads and writes `(DateTime<Utc>, Duration)` pairs the same way.
+///
+/// # Example
+///
+/// ```
+/// use core::time::Duration;
+/// use humantime_serde::iso::Interval;
+///
+/// let window: Interval = "2024-05-01T00:00:00Z/PT1H".parse().unwrap();
+/// assert_eq!(window.duration, Duration::from_secs(3600));
+/// assert_eq!(window.end().unwrap().to_rfc3339(), "2024-05-01T01:00:00+00:00");
+/// assert_eq!(window.to_string(), "2024-05-01T00:00:00Z/PT1H");
+/// ```
+#[cfg(feature = "chrono")]
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Interval {
+    /// The start of the interval, included.
+    pub start: DateTime<Utc>,
+    /// The length of the interval.
+    pub duration: Duration,
+}
+
+#[cfg(feature = "chrono")]
+impl Interval {
+    /// The end of the interval, excluded, or `None` if it is out of range.
+    pub fn end(&self) -> Option<DateTime<Utc>> {
+        self.start.checked_add_signed(TimeDelta::from_std(self.duration).ok()?)
+    }
+
+    /// Returns whether `t` is within the interval.
+    pub fn contains(&self, t: &DateTime<Utc>) -> bool {
+        self.start <= *t && self.end().map_or(true, |end| *t < end)
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl HumantimeFormat for Interval {
+    const EXPECTING: &'static str = "an ISO 8601 interval";
+
+    type Err = InvalidIso;
+
+    fn parse(s: &str) -> Result<Interval, InvalidIso> {
+        let (start, duration) = s.split_once('/').ok_or(InvalidIso)?;
+        Ok(Interval {
+            start: DateTime::<Utc>::parse(start).map_err(|_| InvalidIso)?,
+            duration: parse_duration(duration)?,
+        })
+    }
+
+    fn format(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}/{}", Ref(&self.start), format_duration(self.duration))
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl fmt::Display for Interval {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.format(f)
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl FromStr for Interval {
+    type Err = InvalidIso;
+
+    fn from_str(s: &str) -> Result<Interval, InvalidIso> {
+        Interval::parse(s)
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl From<(DateTime<Utc>, Duration)> for Interval {
+    fn from((start, duration): (DateTime<Utc>, Duration)) -> Interval {
+        Interval { start, duration }
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl From<Interval> for (DateTime<Utc>, Duration) {
+    fn from(i: Interval) -> (DateTime<Utc>, Duration) {
+        (i.start, i.duration)
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl Serialize for Interval {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        Ref(self).serialize(serializer)
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl<'de> Deserialize<'de> for Interval {
+    fn deserialize<D>(d: D) -> Result<Interval, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Serde::deserialize(d).map(Serde::into_inner)
+    }
+}
+
+/// Reads and writes a `(DateTime<Utc>, Duration)` pair as an [`Interval`].
+///
+/// # Example
+///
+/// ```
+/// use core::time::Duration;
+/// use chrono::{DateTime, Utc};
+/// use serde::{Serialize, Deserialize};
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct Event {
+///     #[serde(with = "humantime_serde::iso::interval")]
+///     when: (DateTime<Utc>, Duration),
+/// }
+///
+/// let event: Event = serde_json::from_str(r#"{"when": "2024-05-01T00:00:00Z/PT1H30M"}"#).unwrap();
+/// assert_eq!(event.when.1, Duration::from_secs(5400));
+/// let json = serde_json::to_string(&event).unwrap();
+/// assert_eq!(json, r#"{"when":"2024-05-01T00:00:00Z/PT1H30M"}"#);
+/// ```
+#[cfg(feature = "chrono")]
+pub mod interval {
+    use super::*;
+
+    /// Serializes a start and a duration as an ISO 8601 interval.
+    pub fn serialize<S>(v: &(DateTime<Utc>, Duration), s: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        Interval::from(*v).serialize(s)
+    }
+
+    /// Deserializes a start and a duration from an ISO 8601 interval.
+    pub fn deserialize<'a, D>(d: D) -> Result<(DateTime<Utc>, Duration), D::Error>
+    where
+        D: Deserializer<'a>,
+    {
+        Interval::deserialize(d).map(Into::into)
+    }
+
+    crate::__option_module!([] (::chrono::DateTime<::chrono::Utc>, ::core::time::Duration), []);
+}
+
+/// What a repeating interval is anchored to.
+#[cfg(feature = "chrono")]
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub enum Anchor {
+    /// The start of the first interval, as in `R5/2018-05-11T18:00:00Z/PT1H`.
+    Start(DateTime<Utc>),
+    /// The end of the last interval, as in `R5/PT1H/2018-05-11T18:00:00Z`.
+    End(DateTime<Utc>),
+    /// Nothing, as in `R5/PT1H`.
+    None,
+}
+
+/// An ISO 8601 repeating interval, `R[n]/start/duration`,
+/// `R[n]/duration/end` or `R[n]/duration`.
+///
+/// It serializes back to the same form.
+#[cfg(feature = "chrono")]
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub struct RepeatingInterval {
+    /// The number of intervals, or `None` if they repeat forever.
+    pub repetitions: Option<u32>,
+    /// What the intervals are anchored to.
+    pub anchor: Anchor,
+    /// The length of each interval.
+    pub period: Duration,
+}
+
+#[cfg(feature = "chrono")]
+impl RepeatingInterval {
+    /// The start of the first interval, if it is known.
+    pub fn first(&self) -> Option<DateTime<Utc>> {
+        match self.anchor {
+            Anchor::Start(start) => Some(start),
+            Anchor::End(end) => {
+                let total = self.period.checked_mul(self.repetitions?)?;
+                end.checked_sub_signed(TimeDelta::from_std(total).ok()?)
+            }
+            Anchor::None => None,
+        }
+    }
+
+    /// The starts of the intervals, or nothing if the first is not known.
+    pub fn occurrences(&self) -> impl Iterator<Item = DateTime<Utc>> {
+        let period = TimeDelta::from_std(self.period).ok();
+        let occurrences = core::iter::successors(self.first(), move |t| {
+            t.checked_add_signed(period?)
+        });
+        occurrences.take(self.repetitions.map_or(usize::MAX, |n| n as usize))
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl HumantimeFormat for RepeatingInterval {
+    const EXPECTING: &'static str = "an ISO 8601 repeating interval";
+
+    type Err = InvalidIso;
+
+    fn parse(s: &str) -> Result<RepeatingInterval, InvalidIso> {
+        let mut parts = s.split('/');
+        let count = parts.next().and_then(|r| r.strip_prefix('R')).ok_or(InvalidIso)?;
+        let repetitions = match count {
+            "" | "-1" => None,
+            n => Some(n.parse().map_err(|_| InvalidIso)?),
+        };
+        let parse_time = |s: &str| DateTime::<Utc>::parse(s).map_err(|_| InvalidIso);
+        let (anchor, period) = match (parts.next(), parts.next(), parts.next()) {
+            (Some(period), None, None) => (Anchor::None, parse_duration(period)?),
+            (Some(a), Some(b), None) if a.starts_with('P') => {
+                (Anchor::End(parse_time(b)?), parse_duration(a)?)
+            }
+            (Some(a), Some(b), None) => (Anchor::Start(parse_time(a)?), parse_duration(b)?),
+            _ => return Err(InvalidIso),
+        };
+        Ok(RepeatingInterval { repetitions, anchor, period })
+    }
+
+    fn format(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("R")?;
+        if let Some(n) = self.repetitions {
+            write!(f, "{}", n)?;
+        }
+        let period = format_duration(self.period);
+        match &self.anchor {
+            Anchor::Start(start) => write!(f, "/{}/{}", Ref(start), period),
+            Anchor::End(end) => write!(f, "/{}/{}", period, Ref(end)),
+            Anchor::None => write!(f, "/{}", period),
+        }
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl fmt::Display for RepeatingInterval {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.format(f)
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl FromStr for RepeatingInterval {
+    type Err = InvalidIso;
+
+    fn from_str(s: &str) -> Result<RepeatingInterval, InvalidIso> {
+        RepeatingInterval::parse(s)
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl Serialize for RepeatingInterval {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        Ref(self).serialize(serializer)
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl<'de> Deserialize<'de> for RepeatingInterval {
+    fn deserialize<D>(d: D) -> Result<RepeatingInterval, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Serde::deserialize(d).map(Serde::into_inner)
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod test {
+    use super::*;
+    use alloc::string::ToString;
+    #[cfg(feature = "chrono")]
+    use alloc::vec::Vec;
+
+    #[test]
+    fn duration() {
+        assert_eq!(parse_duration("P1W"), Ok(Duration::from_secs(604_800)));
+        assert_eq!(parse_duration("P1DT1S"), Ok(Duration::from_secs(86_401)));
+        assert_eq!(parse_duration("PT0,25S"), Ok(Duration::from_millis(250)));
+        assert_eq!(parse_duration("PT1M1H"), Err(InvalidIso));
+        assert_eq!(parse_duration("P1Y"), Err(InvalidIso));
+        assert_eq!(parse_duration("PT"), Err(InvalidIso));
+        assert_eq!(parse_duration("P"), Err(InvalidIso));
+        assert_eq!(parse_duration("1H"), Err(InvalidIso));
+
+        for (d, s) in &[(0, "PT0S"), (90_000, "PT1M30S"), (3_600_000, "PT1H"), (1_001, "PT1.001S")] {
+            let d = Duration::from_millis(*d);
+            assert_eq!(format_duration(d).to_string(), *s);
+            assert_eq!(parse_duration(s), Ok(d));
+        }
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn interval() {
+        for s in &["R5/2018-05-11T18:00:00Z/PT1H", "R/PT10M", "R2/PT30M/2018-05-11T18:00:00Z"] {
+            let r: RepeatingInterval = s.parse().unwrap();
+            assert_eq!(r.to_string(), *s);
+        }
+
+        let r: RepeatingInterval = "R2/PT30M/2018-05-11T18:00:00Z".parse().unwrap();
+        let starts: Vec<_> = r.occurrences().map(|t| t.to_string()).collect();
+        assert_eq!(starts, ["2018-05-11 17:00:00 UTC", "2018-05-11 17:30:00 UTC"]);
+        assert_eq!("R/PT10M".parse::<RepeatingInterval>().unwrap().occurrences().count(), 0);
+        assert_eq!("R5/PT1H/PT1H".parse::<RepeatingInterval>(), Err(InvalidIso));
+        assert_eq!("5/PT1H".parse::<RepeatingInterval>(), Err(InvalidIso));
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn start_duration() {
+        let i: Interval = "2018-05-11T20:00:00+02:00/P1DT12H".parse().unwrap();
+        assert_eq!(i.to_string(), "2018-05-11T18:00:00Z/PT36H");
+        assert!(i.contains(&i.start));
+        assert!(!i.contains(&i.end().unwrap()));
+        assert_eq!("2018-05-11T18:00:00Z".parse::<Interval>(), Err(InvalidIso));
+        assert_eq!("PT1H/2018-05-11T18:00:00Z".parse::<Interval>(), Err(InvalidIso));
+        assert_eq!("2018-05-11T18:00:00Z/PT1H/PT1H".parse::<Interval>(), Err(InvalidIso));
+
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct Foo {
+            #[serde(with = "super::interval::option", default)]
+            when: Option<(DateTime<Utc>, Duration)>,
+        }
+
+        let foo: Foo = serde_json::from_str(r#"{"when": "2018-05-11T18:00:00Z/PT90M"}"#).unwrap();
+        assert_eq!(serde_json::to_string(&foo).unwrap(), r#"{"when":"2018-05-11T18:00:00Z/PT1H30M"}"#);
+        assert_eq!(serde_json::from_str::<Foo>("{}").unwrap(), Foo { when: None });
+        let err = serde_json::from_str::<Foo>(r#"{"when": "R/PT1H"}"#).unwrap_err();
+        assert!(err.to_string().contains("expected an ISO 8601 interval"));
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn serde() {
+        let r: RepeatingInterval = serde_json::from_str("\"R/2018-05-11T18:00:00Z/P1D\"").unwrap();
+        assert_eq!(r.repetitions, None);
+        assert_eq!(r.occurrences().nth(2).unwrap().to_string(), "2018-05-13 18:00:00 UTC");
+        assert_eq!(serde_json::to_string(&r).unwrap(), "\"R/2018-05-11T18:00:00Z/PT24H\"");
+    }
+}
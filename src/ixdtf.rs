@@ -0,0 +1,307 @@
+//! RFC 9557 (Internet Extended Date/Time Format) timestamps, such as
+//! `2024-05-01T12:00:00+02:00[Europe/Paris][u-ca=iso8601]`.
+//!
+//! An RFC 3339 timestamp may be followed by a time zone suffix and by
+//! extension suffixes in brackets. The `with` functions here accept them
+//! and keep only the timestamp, writing plain RFC 3339. [`Zoned`] also
+//! keeps the time zone name and writes it back.
+//!
+//! Suffixes marked critical with `!` must be understood. A critical time
+//! zone offset has to agree with the timestamp's offset, and the only
+//! critical extension understood is the ISO 8601 calendar, `u-ca=iso8601`.
+//! Time zone names are not checked against a database.
+//!
+//! # Example
+//!
+//! ```
+//! use chrono::{DateTime, FixedOffset};
+//! use serde::{Serialize, Deserialize};
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Event {
+//!     #[serde(with = "humantime_serde::ixdtf")]
+//!     at: DateTime<FixedOffset>,
+//! }
+//!
+//! let json = r#"{"at": "2024-05-01T12:00:00+02:00[Europe/Paris][u-ca=iso8601]"}"#;
+//! let event: Event = serde_json::from_str(json).unwrap();
+//! let json = serde_json::to_string(&event).unwrap();
+//! assert_eq!(json, r#"{"at":"2024-05-01T12:00:00+02:00"}"#);
+//! ```
+
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+use core::fmt;
+
+use chrono::{DateTime, FixedOffset, TimeZone};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+use super::format::Ref;
+use super::Error;
+
+const EXPECTING: &str = "an RFC 9557 timestamp";
+
+/// Parses an RFC 9557 timestamp, returning it with the time zone name of
+/// its suffix, if any.
+///
+/// # Example
+///
+/// ```
+/// use humantime_serde::ixdtf;
+///
+/// let (t, zone) = ixdtf::parse("2024-05-01T10:00:00Z[!Europe/Paris]").unwrap();
+/// assert_eq!(t.to_rfc3339(), "2024-05-01T10:00:00+00:00");
+/// assert_eq!(zone, Some("Europe/Paris"));
+/// assert!(ixdtf::parse("2024-05-01T10:00:00Z[!u-ca=hebrew]").is_err());
+/// ```
+pub fn parse(s: &str) -> Result<(DateTime<FixedOffset>, Option<&str>), Error> {
+    let (time, mut rest) = s.find('[').map_or((s, ""), |i| s.split_at(i));
+    let t = DateTime::parse_from_rfc3339(time)?;
+    let mut zone = None;
+    let mut first = true;
+    while !rest.is_empty() {
+        let end = rest.find(']').ok_or(Error::Invalid(EXPECTING))?;
+        let suffix = rest.get(1..end).ok_or(Error::Invalid(EXPECTING))?;
+        rest = &rest[end + 1..];
+        if !rest.is_empty() && !rest.starts_with('[') {
+            return Err(Error::TrailingInput);
+        }
+        let (critical, suffix) = match suffix.strip_prefix('!') {
+            Some(suffix) => (true, suffix),
+            None => (false, suffix),
+        };
+        match suffix.split_once('=') {
+            Some((key, value)) => {
+                if !is_key(key) || !value.split('-').all(is_value) {
+                    return Err(Error::Invalid(EXPECTING));
+                }
+                if critical && !(key == "u-ca" && value == "iso8601") {
+                    return Err(Error::Invalid(EXPECTING));
+                }
+            }
+            None if first => {
+                if let Ok(offset) = parse_offset(suffix) {
+                    if critical && offset != *t.offset() {
+                        return Err(Error::Invalid(EXPECTING));
+                    }
+                } else if is_zone_name(suffix) {
+                    zone = Some(suffix);
+                } else {
+                    return Err(Error::Invalid(EXPECTING));
+                }
+            }
+            None => return Err(Error::Invalid(EXPECTING)),
+        }
+        first = false;
+    }
+    Ok((t, zone))
+}
+
+fn is_key(key: &str) -> bool {
+    let mut bytes = key.bytes();
+    matches!(bytes.next(), Some(b'a'..=b'z' | b'_'))
+        && bytes.all(|b| matches!(b, b'a'..=b'z' | b'_' | b'0'..=b'9' | b'-'))
+}
+
+fn is_value(value: &str) -> bool {
+    !value.is_empty() && value.bytes().all(|b| b.is_ascii_alphanumeric())
+}
+
+fn is_zone_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.split('/').all(|part| {
+            let mut bytes = part.bytes();
+            matches!(bytes.next(), Some(b) if b.is_ascii_alphabetic() || b == b'.' || b == b'_')
+                && bytes.all(|b| b.is_ascii_alphanumeric() || matches!(b, b'.' | b'_' | b'-' | b'+'))
+                && part != "."
+                && part != ".."
+        })
+}
+
+fn parse_offset(s: &str) -> Result<FixedOffset, ()> {
+    let b = s.as_bytes();
+    let sign = match b.first() {
+        Some(b'+') => 1,
+        Some(b'-') => -1,
+        _ => return Err(()),
+    };
+    if b.len() != 6 || b[3] != b':' {
+        return Err(());
+    }
+    let num = |i: usize| match (b[i], b[i + 1]) {
+        (h @ b'0'..=b'9', l @ b'0'..=b'9') => Ok(i32::from(h - b'0') * 10 + i32::from(l - b'0')),
+        _ => Err(()),
+    };
+    let (hours, minutes) = (num(1)?, num(4)?);
+    if hours > 23 || minutes > 59 {
+        return Err(());
+    }
+    FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60)).ok_or(())
+}
+
+/// Serializes a `DateTime<Tz>` as RFC 3339, without suffixes.
+pub fn serialize<Tz, S>(t: &DateTime<Tz>, s: S) -> Result<S::Ok, S::Error>
+where
+    Tz: TimeZone,
+    S: Serializer,
+{
+    crate::serialize(&t.fixed_offset(), s)
+}
+
+/// Deserializes a `DateTime<Tz>` from an RFC 9557 timestamp, dropping its
+/// suffixes.
+pub fn deserialize<'a, Tz, D>(d: D) -> Result<DateTime<Tz>, D::Error>
+where
+    Tz: TimeZone,
+    DateTime<Tz>: From<DateTime<FixedOffset>>,
+    D: Deserializer<'a>,
+{
+    struct V;
+
+    impl de::Visitor<'_> for V {
+        type Value = DateTime<FixedOffset>;
+
+        fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+            fmt.write_str(EXPECTING)
+        }
+
+        fn visit_str<E>(self, v: &str) -> Result<DateTime<FixedOffset>, E>
+        where
+            E: de::Error,
+        {
+            crate::check_len(v, crate::DEFAULT_MAX_LEN, EXPECTING)?;
+            parse(v).map(|(t, _)| t).map_err(|err| {
+                E::invalid_value(de::Unexpected::Str(v), &crate::Because(&self, &err))
+            })
+        }
+    }
+
+    d.deserialize_str(V).map(DateTime::from)
+}
+
+crate::__option_module!([Tz] ::chrono::DateTime<Tz>, [
+    Tz: ::chrono::TimeZone,
+    ::chrono::DateTime<Tz>: ::core::convert::From<::chrono::DateTime<::chrono::FixedOffset>>,
+]);
+
+/// A timestamp with the time zone name of its RFC 9557 suffix.
+///
+/// It is written with the name in brackets, such as
+/// `2024-05-01T12:00:00+02:00[Europe/Paris]`, or as plain RFC 3339 without
+/// one. Extension suffixes are not kept.
+///
+/// # Example
+///
+/// ```
+/// use humantime_serde::ixdtf::Zoned;
+///
+/// let json = r#""2024-05-01T12:00:00+02:00[Europe/Paris][u-ca=iso8601]""#;
+/// let zoned: Zoned = serde_json::from_str(json).unwrap();
+/// assert_eq!(zoned.zone.as_deref(), Some("Europe/Paris"));
+/// let json = serde_json::to_string(&zoned).unwrap();
+/// assert_eq!(json, r#""2024-05-01T12:00:00+02:00[Europe/Paris]""#);
+/// ```
+#[cfg(feature = "alloc")]
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Zoned {
+    /// The timestamp.
+    pub time: DateTime<FixedOffset>,
+    /// The time zone name, such as `Europe/Paris`.
+    pub zone: Option<String>,
+}
+
+#[cfg(feature = "alloc")]
+impl fmt::Display for Zoned {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&Ref(&self.time), f)?;
+        match &self.zone {
+            Some(zone) => write!(f, "[{}]", zone),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl Serialize for Zoned {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'de> Deserialize<'de> for Zoned {
+    fn deserialize<D>(d: D) -> Result<Zoned, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct V;
+
+        impl de::Visitor<'_> for V {
+            type Value = Zoned;
+
+            fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+                fmt.write_str(EXPECTING)
+            }
+
+            fn visit_str<E>(self, v: &str) -> Result<Zoned, E>
+            where
+                E: de::Error,
+            {
+                crate::check_len(v, crate::DEFAULT_MAX_LEN, EXPECTING)?;
+                match parse(v) {
+                    Ok((time, zone)) => Ok(Zoned {
+                        time,
+                        zone: zone.map(String::from),
+                    }),
+                    Err(err) => Err(E::invalid_value(
+                        de::Unexpected::Str(v),
+                        &crate::Because(&self, &err),
+                    )),
+                }
+            }
+        }
+
+        d.deserialize_str(V)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn suffixes() {
+        let base = "2024-05-01T12:00:00+02:00";
+        let ok = |s: &str| parse(s).map(|(t, zone)| (t.to_rfc3339(), zone));
+        assert_eq!(ok(base), Ok((String::from("2024-05-01T12:00:00+02:00"), None)));
+        assert_eq!(ok("2024-05-01T12:00:00+02:00[Europe/Paris]").unwrap().1, Some("Europe/Paris"));
+        assert_eq!(ok("2024-05-01T12:00:00+02:00[America/Argentina/Buenos_Aires]").unwrap().1,
+            Some("America/Argentina/Buenos_Aires"));
+        assert!(parse("2024-05-01T12:00:00+02:00[!+02:00]").is_ok());
+        assert!(parse("2024-05-01T12:00:00+02:00[+01:00]").is_ok());
+        assert!(parse("2024-05-01T12:00:00+02:00[!+01:00]").is_err());
+        assert!(parse("2024-05-01T12:00:00+02:00[u-ca=iso8601][_foo=bar-baz]").is_ok());
+        assert!(parse("2024-05-01T12:00:00+02:00[!u-ca=iso8601]").is_ok());
+        assert!(parse("2024-05-01T12:00:00+02:00[!_foo=bar]").is_err());
+        assert!(parse("2024-05-01T12:00:00+02:00[u-ca=iso8601][Europe/Paris]").is_err());
+        assert!(parse("2024-05-01T12:00:00+02:00[Europe/Paris").is_err());
+        assert!(parse("2024-05-01T12:00:00+02:00[Europe/Paris]x").is_err());
+        assert!(parse("2024-05-01T12:00:00+02:00[]").is_err());
+        assert!(parse("2024-05-01T12:00:00+02:00[Foo=bar]").is_err());
+        assert!(parse("2024-05-01T12:00:00+02:00[a=]").is_err());
+    }
+
+    #[test]
+    fn zoned() {
+        let zoned = Zoned {
+            time: DateTime::parse_from_rfc3339("2024-05-01T12:00:00Z").unwrap(),
+            zone: None,
+        };
+        assert_eq!(serde_json::to_string(&zoned).unwrap(), r#""2024-05-01T12:00:00Z""#);
+        let back: Zoned = serde_json::from_str(r#""2024-05-01T12:00:00Z""#).unwrap();
+        assert_eq!(back, zoned);
+    }
+}
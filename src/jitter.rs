@@ -0,0 +1,237 @@
+use core::fmt;
+use core::str::FromStr;
+use core::time::Duration;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use super::format::{HumantimeFormat, Ref};
+use super::Serde;
+
+/// How far a [`Jittered`] duration may stray from its base.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Spread {
+    /// A fixed amount either way.
+    Duration(Duration),
+    /// A percentage of the base either way, from 0 to 100. Percentages
+    /// outside that range, which [`Spread::percent`] rejects, are clamped
+    /// to it.
+    Percent(f64),
+}
+
+impl Spread {
+    /// A percentage of the base either way, or `None` unless `p` is
+    /// between 0 and 100.
+    pub fn percent(p: f64) -> Option<Spread> {
+        if (0.0..=100.0).contains(&p) {
+            Some(Spread::Percent(p))
+        } else {
+            None
+        }
+    }
+}
+
+/// A duration with explicit jitter bounds, written `"5s ± 1s"` or
+/// `"5s +/- 20%"`.
+///
+/// # Example
+///
+/// ```
+/// use core::time::Duration;
+/// use humantime_serde::Jittered;
+///
+/// let interval: Jittered = "5s +/- 20%".parse().unwrap();
+/// assert_eq!(interval.min(), Duration::from_secs(4));
+/// assert_eq!(interval.max(), Duration::from_secs(6));
+/// assert_eq!(interval.sample_with(0.5), Duration::from_secs(5));
+/// assert_eq!(interval.to_string(), "5s ± 20%");
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Jittered {
+    /// The duration without jitter.
+    pub base: Duration,
+    /// How far the duration may stray from `base`.
+    pub spread: Spread,
+}
+
+impl Jittered {
+    /// Creates a duration of `base` give or take `spread`.
+    pub const fn new(base: Duration, spread: Spread) -> Jittered {
+        Jittered { base, spread }
+    }
+
+    fn spread(&self) -> Duration {
+        match self.spread {
+            Spread::Duration(d) => d,
+            Spread::Percent(p) if p > 0.0 => {
+                let spread = self.base.as_secs_f64() * p.min(100.0) / 100.0;
+                Duration::try_from_secs_f64(spread).map_or(self.base, |d| d.min(self.base))
+            }
+            Spread::Percent(_) => Duration::ZERO,
+        }
+    }
+
+    /// The shortest duration, saturating at zero.
+    pub fn min(&self) -> Duration {
+        self.base.saturating_sub(self.spread())
+    }
+
+    /// The longest duration, saturating at `Duration::MAX`.
+    pub fn max(&self) -> Duration {
+        self.base.saturating_add(self.spread())
+    }
+
+    /// Picks the duration at `unit` between [`min`](Self::min), at 0, and
+    /// [`max`](Self::max), at 1, for a `unit` drawn from any random source.
+    pub fn sample_with(&self, unit: f64) -> Duration {
+        let range = self.max() - self.min();
+        let offset = range.as_secs_f64() * unit.max(0.0).min(1.0);
+        self.min() + Duration::try_from_secs_f64(offset).map_or(range, |d| d.min(range))
+    }
+
+    /// Picks a random duration between [`min`](Self::min) and
+    /// [`max`](Self::max).
+    #[cfg(feature = "rand")]
+    pub fn sample<R>(&self, rng: &mut R) -> Duration
+    where
+        R: rand::Rng + ?Sized,
+    {
+        self.sample_with(rng.gen())
+    }
+}
+
+/// The error returned when parsing a [`Jittered`] duration fails.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct InvalidJitter;
+
+impl fmt::Display for InvalidJitter {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("invalid jittered duration, expected e.g. 5s ± 1s")
+    }
+}
+
+impl core::error::Error for InvalidJitter {}
+
+impl HumantimeFormat for Jittered {
+    const EXPECTING: &'static str = "a duration with jitter";
+
+    type Err = InvalidJitter;
+
+    fn parse(s: &str) -> Result<Jittered, InvalidJitter> {
+        let (base, spread) = s
+            .split_once('±')
+            .or_else(|| s.split_once("+/-"))
+            .ok_or(InvalidJitter)?;
+        let base = Duration::parse(base.trim()).map_err(|_| InvalidJitter)?;
+        let spread = spread.trim();
+        let spread = match spread.strip_suffix('%') {
+            Some(p) => p
+                .trim()
+                .parse()
+                .ok()
+                .and_then(Spread::percent)
+                .ok_or(InvalidJitter)?,
+            None => Spread::Duration(Duration::parse(spread).map_err(|_| InvalidJitter)?),
+        };
+        Ok(Jittered::new(base, spread))
+    }
+
+    fn format(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.base.format(f)?;
+        f.write_str(" ± ")?;
+        match self.spread {
+            Spread::Duration(d) => d.format(f),
+            Spread::Percent(p) => write!(f, "{}%", p),
+        }
+    }
+}
+
+impl fmt::Display for Jittered {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.format(f)
+    }
+}
+
+impl FromStr for Jittered {
+    type Err = InvalidJitter;
+
+    fn from_str(s: &str) -> Result<Jittered, InvalidJitter> {
+        Jittered::parse(s)
+    }
+}
+
+impl Serialize for Jittered {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        Ref(self).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Jittered {
+    fn deserialize<D>(d: D) -> Result<Jittered, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Serde::deserialize(d).map(Serde::into_inner)
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod test {
+    use super::*;
+    use alloc::string::ToString;
+
+    #[test]
+    fn parse() {
+        let j: Jittered = "1m ± 10s".parse().unwrap();
+        assert_eq!(j.spread, Spread::Duration(Duration::from_secs(10)));
+        assert_eq!(j.min(), Duration::from_secs(50));
+        assert_eq!(j.max(), Duration::from_secs(70));
+
+        let j: Jittered = "1s+/-2s".parse().unwrap();
+        assert_eq!(j.min(), Duration::ZERO);
+        assert_eq!(j.to_string(), "1s ± 2s");
+
+        assert_eq!("1s".parse::<Jittered>(), Err(InvalidJitter));
+        assert_eq!("1s ± 150%".parse::<Jittered>(), Err(InvalidJitter));
+        assert_eq!("1s ± -5%".parse::<Jittered>(), Err(InvalidJitter));
+        assert_eq!("1s ± NaN%".parse::<Jittered>(), Err(InvalidJitter));
+        assert_eq!("1s ± inf%".parse::<Jittered>(), Err(InvalidJitter));
+    }
+
+    #[test]
+    fn saturate() {
+        let j: Jittered = "18446744073709551615s ± 100%".parse().unwrap();
+        assert_eq!(j.min(), Duration::ZERO);
+        assert_eq!(j.max(), Duration::MAX);
+        assert_eq!(j.sample_with(1.0), Duration::MAX);
+
+        let j = Jittered::new(Duration::from_secs(10), Spread::Percent(-50.0));
+        assert_eq!((j.min(), j.max()), (j.base, j.base));
+        let j = Jittered::new(Duration::from_secs(10), Spread::Percent(f64::NAN));
+        assert_eq!((j.min(), j.max()), (j.base, j.base));
+        let j = Jittered::new(Duration::from_secs(10), Spread::Percent(f64::INFINITY));
+        assert_eq!((j.min(), j.max()), (Duration::ZERO, Duration::from_secs(20)));
+        assert_eq!(Spread::percent(-1.0), None);
+        assert_eq!(Spread::percent(20.0), Some(Spread::Percent(20.0)));
+    }
+
+    #[test]
+    fn sample() {
+        let j: Jittered = "10s ± 2s".parse().unwrap();
+        assert_eq!(j.sample_with(0.0), Duration::from_secs(8));
+        assert_eq!(j.sample_with(1.0), Duration::from_secs(12));
+        assert_eq!(j.sample_with(7.0), Duration::from_secs(12));
+    }
+
+    #[test]
+    fn serde() {
+        for s in &["\"5s ± 1s\"", "\"5s ± 12.5%\""] {
+            let j: Jittered = serde_json::from_str(s).unwrap();
+            assert_eq!(serde_json::to_string(&j).unwrap(), *s);
+        }
+        let err = serde_json::from_str::<Jittered>("\"5s\"").unwrap_err();
+        assert!(err.to_string().contains("expected a duration with jitter"));
+    }
+}
@@ -0,0 +1,274 @@
+//! A `with` module for durations in the syntax of Go's `time.Duration`, as
+//! used by Kubernetes' `metav1.Duration`.
+//!
+//! Durations are written exactly as Go writes them, so fields round-trip
+//! byte for byte with YAML produced by `kubectl`: `1h30m0s`, `2m5.5s`,
+//! `1.5s`, `250ms`, `10µs` and `0s`. On input, any Go duration is accepted:
+//! a sequence of decimal numbers with the units `ns`, `us` (or `µs`), `ms`,
+//! `s`, `m` and `h`, without spaces, such as `1h30m` or `1.5h`.
+//!
+//! Go durations are signed 64-bit counts of nanoseconds, so negative
+//! durations and those beyond 292 years are rejected both ways.
+//!
+//! # Example
+//!
+//! ```
+//! use core::time::Duration;
+//! use serde::{Serialize, Deserialize};
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct ProbeSpec {
+//!     #[serde(with = "humantime_serde::kubernetes")]
+//!     period: Duration,
+//! }
+//!
+//! let spec: ProbeSpec = serde_json::from_str(r#"{"period": "1h30m"}"#).unwrap();
+//! assert_eq!(spec.period, Duration::from_secs(5400));
+//! assert_eq!(serde_json::to_string(&spec).unwrap(), r#"{"period":"1h30m0s"}"#);
+//! ```
+
+use core::fmt;
+use core::time::Duration;
+
+use serde::{de, ser, Deserializer, Serializer};
+
+use crate::Error;
+
+const NANOS_PER_SEC: u128 = 1_000_000_000;
+
+/// The longest duration Go represents, in nanoseconds.
+const MAX_NANOS: u128 = i64::MAX as u128;
+
+/// The units Go reads, with their length in nanoseconds.
+const UNITS: &[(&str, u128)] = &[
+    ("ns", 1),
+    ("us", 1_000),
+    ("\u{b5}s", 1_000),
+    ("\u{3bc}s", 1_000),
+    ("ms", 1_000_000),
+    ("s", NANOS_PER_SEC),
+    ("m", 60 * NANOS_PER_SEC),
+    ("h", 3_600 * NANOS_PER_SEC),
+];
+
+/// Parses a duration in Go's syntax.
+pub fn parse(s: &str) -> Result<Duration, Error> {
+    let (negative, mut rest) = match s.as_bytes().first() {
+        None => return Err(Error::Empty),
+        Some(b'-') => (true, &s[1..]),
+        Some(b'+') => (false, &s[1..]),
+        Some(_) => (false, s),
+    };
+    if rest == "0" {
+        return Ok(Duration::ZERO);
+    }
+    if rest.is_empty() {
+        return Err(Error::NumberExpected(s.len()));
+    }
+    let mut total: u128 = 0;
+    while !rest.is_empty() {
+        let pos = s.len() - rest.len();
+        let int_len = rest.find(|c: char| !c.is_ascii_digit()).unwrap_or(rest.len());
+        let (int, after) = rest.split_at(int_len);
+        let (frac, after) = match after.strip_prefix('.') {
+            Some(after) => {
+                after.split_at(after.find(|c: char| !c.is_ascii_digit()).unwrap_or(after.len()))
+            }
+            None => ("", after),
+        };
+        if int.is_empty() && frac.is_empty() {
+            return Err(Error::NumberExpected(pos));
+        }
+        let unit_len = after.find(|c: char| c.is_ascii_digit() || c == '.').unwrap_or(after.len());
+        let start = s.len() - after.len();
+        let unit = UNITS
+            .iter()
+            .find(|&&(name, _)| name == &after[..unit_len])
+            .map(|&(_, nanos)| nanos)
+            .ok_or(Error::UnknownUnit { start, end: start + unit_len, suggestion: None })?;
+        let mut value: u128 = 0;
+        for b in int.bytes() {
+            value = value * 10 + u128::from(b - b'0');
+            if value > MAX_NANOS {
+                return Err(Error::NumberOverflow);
+            }
+        }
+        // Digits past the 19th are below a nanosecond even for hours.
+        let (mut frac_value, mut scale) = (0u128, 1u128);
+        for b in frac.bytes().take(19) {
+            frac_value = frac_value * 10 + u128::from(b - b'0');
+            scale *= 10;
+        }
+        total = value
+            .checked_mul(unit)
+            .map(|v| v + frac_value * unit / scale)
+            .and_then(|v| total.checked_add(v))
+            .filter(|&v| v <= MAX_NANOS)
+            .ok_or(Error::NumberOverflow)?;
+        rest = &after[unit_len..];
+    }
+    if negative && total != 0 {
+        return Err(Error::Invalid("a duration that is not negative"));
+    }
+    Ok(Duration::new((total / NANOS_PER_SEC) as u64, (total % NANOS_PER_SEC) as u32))
+}
+
+/// Formats a duration exactly as Go's `Duration.String` does.
+///
+/// Durations beyond 292 years, which Go cannot hold, are written all the
+/// same.
+pub fn format(d: Duration) -> impl fmt::Display {
+    GoDuration(d)
+}
+
+struct GoDuration(Duration);
+
+/// Writes `.digits` for a fraction of `value / 10^width`, without trailing
+/// zeros, or nothing for a zero fraction.
+fn write_fraction(f: &mut fmt::Formatter, mut value: u128, mut width: usize) -> fmt::Result {
+    if value == 0 {
+        return Ok(());
+    }
+    while value % 10 == 0 {
+        value /= 10;
+        width -= 1;
+    }
+    write!(f, ".{:0width$}", value, width = width)
+}
+
+impl fmt::Display for GoDuration {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let nanos = self.0.as_nanos();
+        if nanos == 0 {
+            return f.write_str("0s");
+        }
+        if nanos < NANOS_PER_SEC {
+            let (unit, width) = match nanos {
+                0..=999 => ("ns", 0),
+                1_000..=999_999 => ("\u{b5}s", 3),
+                _ => ("ms", 6),
+            };
+            let scale = 10u128.pow(width as u32);
+            write!(f, "{}", nanos / scale)?;
+            write_fraction(f, nanos % scale, width)?;
+            return f.write_str(unit);
+        }
+        let secs = self.0.as_secs();
+        let (h, m, s) = (secs / 3600, secs / 60 % 60, secs % 60);
+        if h != 0 {
+            write!(f, "{}h", h)?;
+        }
+        if h != 0 || m != 0 {
+            write!(f, "{}m", m)?;
+        }
+        write!(f, "{}", s)?;
+        write_fraction(f, u128::from(self.0.subsec_nanos()), 9)?;
+        f.write_str("s")
+    }
+}
+
+/// Serializes a `Duration` in Go's syntax.
+pub fn serialize<S>(d: &Duration, s: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    if d.as_nanos() > MAX_NANOS {
+        return Err(ser::Error::custom(format_args!(
+            "{:?} is longer than a Go duration holds",
+            d
+        )));
+    }
+    s.collect_str(&format(*d))
+}
+
+/// Deserializes a `Duration` from Go's syntax.
+pub fn deserialize<'a, D>(d: D) -> Result<Duration, D::Error>
+where
+    D: Deserializer<'a>,
+{
+    struct V;
+
+    impl de::Visitor<'_> for V {
+        type Value = Duration;
+
+        fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+            fmt.write_str("a Go duration")
+        }
+
+        fn visit_str<E>(self, v: &str) -> Result<Duration, E>
+        where
+            E: de::Error,
+        {
+            crate::check_len(v, crate::DEFAULT_MAX_LEN, "a Go duration")?;
+            parse(v).map_err(|err| {
+                E::invalid_value(de::Unexpected::Str(v), &crate::Because(&self, &err))
+            })
+        }
+    }
+
+    d.deserialize_str(V)
+}
+
+crate::__option_module!([] ::core::time::Duration, []);
+
+#[cfg(all(test, feature = "alloc"))]
+mod test {
+    use super::*;
+    use alloc::string::ToString;
+
+    #[test]
+    fn parsing() {
+        let ms = Duration::from_millis;
+        assert_eq!(parse("1h30m"), Ok(ms(5_400_000)));
+        assert_eq!(parse("1.5h"), Ok(ms(5_400_000)));
+        assert_eq!(parse("+2m5.5s"), Ok(ms(125_500)));
+        assert_eq!(parse(".5s"), Ok(ms(500)));
+        assert_eq!(parse("1."), Err(Error::UnknownUnit { start: 2, end: 2, suggestion: None }));
+        assert_eq!(parse("1.s"), Ok(ms(1_000)));
+        assert_eq!(parse("10us1\u{3bc}s1\u{b5}s"), Ok(Duration::from_micros(12)));
+        assert_eq!(parse("1s1s"), Ok(ms(2_000)));
+        assert_eq!(parse("0"), Ok(Duration::ZERO));
+        assert_eq!(parse("-0s"), Ok(Duration::ZERO));
+        assert_eq!(parse("0.0000000000000000000001h"), Ok(Duration::ZERO));
+        assert_eq!(parse("2562047h47m16.854775807s"), Ok(Duration::new(9_223_372_036, 854_775_807)));
+        assert_eq!(parse("2562047h47m16.854775808s"), Err(Error::NumberOverflow));
+        assert_eq!(parse(""), Err(Error::Empty));
+        assert_eq!(parse("-"), Err(Error::NumberExpected(1)));
+        assert_eq!(parse("1"), Err(Error::UnknownUnit { start: 1, end: 1, suggestion: None }));
+        assert_eq!(parse("1h 30m"), Err(Error::UnknownUnit { start: 1, end: 3, suggestion: None }));
+        assert_eq!(parse("1d"), Err(Error::UnknownUnit { start: 1, end: 2, suggestion: None }));
+        assert_eq!(parse("s"), Err(Error::NumberExpected(0)));
+        assert!(parse("-1s").is_err());
+    }
+
+    #[test]
+    fn formatting() {
+        let format = |nanos| format(Duration::from_nanos(nanos)).to_string();
+        assert_eq!(format(0), "0s");
+        assert_eq!(format(1), "1ns");
+        assert_eq!(format(1_100), "1.1\u{b5}s");
+        assert_eq!(format(2_200_000), "2.2ms");
+        assert_eq!(format(3_300_000_000), "3.3s");
+        assert_eq!(format(60_000_000_000), "1m0s");
+        assert_eq!(format(4_000_000_001), "4.000000001s");
+        assert_eq!(format(5_400_000_000_000), "1h30m0s");
+        assert_eq!(format(86_400_000_000_000), "24h0m0s");
+        assert_eq!(format(i64::MAX as u64), "2562047h47m16.854775807s");
+    }
+
+    #[test]
+    fn serde() {
+        #[derive(serde::Serialize, serde::Deserialize)]
+        struct Probe {
+            #[serde(with = "super")]
+            timeout: Duration,
+        }
+
+        let probe: Probe = serde_json::from_str(r#"{"timeout": "1.5m"}"#).unwrap();
+        assert_eq!(serde_json::to_string(&probe).unwrap(), r#"{"timeout":"1m30s"}"#);
+        let err = serde_json::from_str::<Probe>(r#"{"timeout": "1 min"}"#).unwrap_err();
+        assert!(err.to_string().contains("expected a Go duration"));
+        let probe = Probe { timeout: Duration::from_secs(u64::MAX) };
+        assert!(serde_json::to_string(&probe).is_err());
+    }
+}
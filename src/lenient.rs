@@ -0,0 +1,397 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::time::Duration;
+
+use crate::backend::{Backend, Selected};
+use crate::Error;
+
+#[cfg(feature = "chrono")]
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, TimeDelta, Utc};
+
+/// Parses a duration, also accepting the separators people write by hand.
+///
+/// On top of the humantime syntax, components may be separated by commas
+/// and the word `and`, and surrounding whitespace is ignored, so
+/// `"1 hour, 30 minutes"` and `"2h and 15m"` are accepted. Units remain case
+/// sensitive, since `M` means months and `m` minutes.
+///
+/// # Example
+///
+/// ```
+/// use core::time::Duration;
+/// use humantime_serde::parse_duration_lenient;
+///
+/// let d = parse_duration_lenient(" 1 hour, 30 minutes and 15s ").unwrap();
+/// assert_eq!(d, Duration::from_secs(5415));
+/// ```
+pub fn parse_duration_lenient(s: &str) -> Result<Duration, Error> {
+    let mut normalized = String::with_capacity(s.len());
+    // Where each byte of `normalized` comes from in `s`, then where the last
+    // word ends, so errors point into the input as given.
+    let mut offsets = Vec::with_capacity(s.len() + 1);
+    let mut end = 0;
+    for word in s.split_whitespace() {
+        let start = word.as_ptr() as usize - s.as_ptr() as usize;
+        let word = word.trim_end_matches(',');
+        if word.is_empty() || word.eq_ignore_ascii_case("and") {
+            continue;
+        }
+        if !normalized.is_empty() {
+            normalized.push(' ');
+            offsets.push(end);
+        }
+        normalized.push_str(word);
+        offsets.extend(start..start + word.len());
+        end = start + word.len();
+    }
+    offsets.push(end);
+    Selected::parse_duration(&normalized).map_err(|err| {
+        let offset = |i: usize| offsets.get(i).copied().unwrap_or(end);
+        match Error::from(err).with_input(&normalized) {
+            Error::InvalidCharacter(i) => Error::InvalidCharacter(offset(i)),
+            Error::NumberExpected(i) => Error::NumberExpected(offset(i)),
+            Error::UnknownUnit { start, end, suggestion } => Error::UnknownUnit {
+                start: offset(start),
+                end: offset(end),
+                suggestion,
+            },
+            err => err,
+        }
+    })
+}
+
+/// Parses a timestamp, also accepting common deviations from RFC 3339.
+///
+/// A space may replace the `T`, the offset may omit its colon, and a
+/// timestamp without any offset is taken as UTC. Such a timestamp may also
+/// give its time on a 12-hour clock, as in `2024-05-01 6:30 PM`.
+///
+/// Durations may be added to and subtracted from the timestamp, as in
+/// `2024-01-01T00:00:00Z + 3 days - 1h`, with spaces around the operators.
+/// With the `std` feature the timestamp may also be `now`, the current
+/// time in UTC; see [`parse_timestamp_flexible_at`] to supply it otherwise.
+///
+/// [`custom_format!`](crate::custom_format)'s `lenient` option accepts the
+/// same timestamps, but neither arithmetic nor `now`, so that deserializing
+/// a value gives the same result whenever it runs.
+///
+/// # Example
+///
+/// ```
+/// use humantime_serde::parse_timestamp_flexible;
+///
+/// let t = parse_timestamp_flexible("2018-05-11 20:28:30+0200").unwrap();
+/// assert_eq!(t.to_rfc3339(), "2018-05-11T20:28:30+02:00");
+/// let t = parse_timestamp_flexible("2018-05-11 18:28:30").unwrap();
+/// assert_eq!(t.to_rfc3339(), "2018-05-11T18:28:30+00:00");
+/// let t = parse_timestamp_flexible("2018-05-11 6:28 pm").unwrap();
+/// assert_eq!(t.to_rfc3339(), "2018-05-11T18:28:00+00:00");
+/// let t = parse_timestamp_flexible("2018-05-11T18:28:00Z + 1 day - 30s").unwrap();
+/// assert_eq!(t.to_rfc3339(), "2018-05-12T18:27:30+00:00");
+/// ```
+#[cfg(feature = "chrono")]
+pub fn parse_timestamp_flexible(s: &str) -> Result<DateTime<FixedOffset>, Error> {
+    #[cfg(feature = "std")]
+    let now = || Some(<crate::clock::SystemClock as crate::clock::Clock>::now());
+    #[cfg(not(feature = "std"))]
+    let now = || None;
+    evaluate(s, now)
+}
+
+/// Parses a timestamp like [`parse_timestamp_flexible`], taking `now` to
+/// be the given time.
+///
+/// # Example
+///
+/// ```
+/// use chrono::{DateTime, Utc};
+/// use humantime_serde::parse_timestamp_flexible_at;
+///
+/// let now = DateTime::<Utc>::UNIX_EPOCH;
+/// let t = parse_timestamp_flexible_at("now - 1h", now).unwrap();
+/// assert_eq!(t.to_rfc3339(), "1969-12-31T23:00:00+00:00");
+/// ```
+#[cfg(feature = "chrono")]
+pub fn parse_timestamp_flexible_at(
+    s: &str,
+    now: DateTime<Utc>,
+) -> Result<DateTime<FixedOffset>, Error> {
+    evaluate(s, || Some(now))
+}
+
+/// Parses a timestamp followed by any number of `+ duration` and
+/// `- duration` terms.
+#[cfg(feature = "chrono")]
+pub(crate) fn evaluate<F>(s: &str, now: F) -> Result<DateTime<FixedOffset>, Error>
+where
+    F: FnOnce() -> Option<DateTime<Utc>>,
+{
+    let operator = |s: &str| [" + ", " - "].iter().filter_map(|op| s.find(op)).min();
+    let s = s.trim();
+    let (base, mut rest) = s.split_at(operator(s).unwrap_or(s.len()));
+    let now = if base.eq_ignore_ascii_case("now") { now() } else { None };
+    let mut t = match now {
+        Some(now) => now.fixed_offset(),
+        None => parse_single(base)?,
+    };
+    while !rest.is_empty() {
+        let negative = rest.starts_with(" -");
+        let term = &rest[3..];
+        let (duration, next) = term.split_at(operator(term).unwrap_or(term.len()));
+        let duration = parse_duration_lenient(duration)?;
+        let duration = TimeDelta::from_std(duration).map_err(|_| Error::NumberOverflow)?;
+        t = if negative {
+            t.checked_sub_signed(duration)
+        } else {
+            t.checked_add_signed(duration)
+        }
+        .ok_or(Error::NumberOverflow)?;
+        rest = next;
+    }
+    Ok(t)
+}
+
+/// Parses a single timestamp, without any arithmetic or `now`.
+#[cfg(feature = "chrono")]
+pub(crate) fn parse_single(s: &str) -> Result<DateTime<FixedOffset>, Error> {
+    s.parse::<DateTime<FixedOffset>>().or_else(|err| {
+        parse_naive(s)
+            .map(|t| t.and_utc().fixed_offset())
+            .map_err(|_| Error::from(err))
+    })
+}
+
+/// Parses a timestamp without an offset, with its time on either a 24-hour
+/// clock, a 12-hour one when it ends in `am` or `pm`, or given by one of
+/// the [`TimeOfDay`](crate::TimeOfDay) keywords such as `noon`.
+#[cfg(feature = "chrono")]
+fn parse_naive(s: &str) -> Result<NaiveDateTime, chrono::ParseError> {
+    s.parse::<NaiveDateTime>().or_else(|err| {
+        let (date, time) = s.split_once(|c| c == ' ' || c == 'T').ok_or(err)?;
+        let time = time.trim_start();
+        let suffix = time.len().checked_sub(2).and_then(|i| time.get(i..)).unwrap_or("");
+        let twelve_hour = suffix.eq_ignore_ascii_case("am") || suffix.eq_ignore_ascii_case("pm");
+        if !twelve_hour && crate::time_of_day::keyword(time).is_none() {
+            return Err(err);
+        }
+        let date = date.parse::<NaiveDate>()?;
+        let time = time.parse::<crate::TimeOfDay>().map_err(|_| err)?;
+        Ok(date.and_time(time.into_inner()))
+    })
+}
+
+/// A time zone abbreviation and the offsets it may stand for.
+#[cfg(feature = "chrono")]
+struct Abbreviation {
+    name: &'static str,
+    /// Offsets east of UTC in seconds, one per meaning.
+    offsets: &'static [i32],
+    /// What each offset is, for the error on ambiguous abbreviations.
+    meanings: &'static [&'static str],
+}
+
+#[cfg(feature = "chrono")]
+macro_rules! abbreviations {
+    ($($name:literal => [$($meaning:literal $offset:expr),+],)*) => {
+        &[$(Abbreviation {
+            name: $name,
+            offsets: &[$($offset),+],
+            meanings: &[$($meaning),+],
+        }),*]
+    };
+}
+
+#[cfg(feature = "chrono")]
+const H: i32 = 3600;
+
+#[cfg(feature = "chrono")]
+const ABBREVIATIONS: &[Abbreviation] = abbreviations! {
+    "UTC" => ["UTC" 0],
+    "GMT" => ["Greenwich Mean Time" 0],
+    "EST" => ["Eastern Standard Time" -5 * H],
+    "EDT" => ["Eastern Daylight Time" -4 * H],
+    "CST" => ["Central Standard Time (-06:00)" -6 * H, "China Standard Time (+08:00)" 8 * H, "Cuba Standard Time (-05:00)" -5 * H],
+    "CDT" => ["Central Daylight Time (-05:00)" -5 * H, "Cuba Daylight Time (-04:00)" -4 * H],
+    "MST" => ["Mountain Standard Time" -7 * H],
+    "MDT" => ["Mountain Daylight Time" -6 * H],
+    "PST" => ["Pacific Standard Time" -8 * H],
+    "PDT" => ["Pacific Daylight Time" -7 * H],
+    "AKST" => ["Alaska Standard Time" -9 * H],
+    "AKDT" => ["Alaska Daylight Time" -8 * H],
+    "HST" => ["Hawaii Standard Time" -10 * H],
+    "AST" => ["Atlantic Standard Time (-04:00)" -4 * H, "Arabia Standard Time (+03:00)" 3 * H],
+    "ADT" => ["Atlantic Daylight Time" -3 * H],
+    "NST" => ["Newfoundland Standard Time" -(3 * H + H / 2)],
+    "NDT" => ["Newfoundland Daylight Time" -(2 * H + H / 2)],
+    "WET" => ["Western European Time" 0],
+    "WEST" => ["Western European Summer Time" H],
+    "BST" => ["British Summer Time (+01:00)" H, "Bangladesh Standard Time (+06:00)" 6 * H],
+    "IST" => ["India Standard Time (+05:30)" 5 * H + H / 2, "Irish Standard Time (+01:00)" H, "Israel Standard Time (+02:00)" 2 * H],
+    "CET" => ["Central European Time" H],
+    "CEST" => ["Central European Summer Time" 2 * H],
+    "EET" => ["Eastern European Time" 2 * H],
+    "EEST" => ["Eastern European Summer Time" 3 * H],
+    "MSK" => ["Moscow Time" 3 * H],
+    "SGT" => ["Singapore Time" 8 * H],
+    "HKT" => ["Hong Kong Time" 8 * H],
+    "JST" => ["Japan Standard Time" 9 * H],
+    "KST" => ["Korea Standard Time" 9 * H],
+    "AWST" => ["Australian Western Standard Time" 8 * H],
+    "ACST" => ["Australian Central Standard Time" 9 * H + H / 2],
+    "AEST" => ["Australian Eastern Standard Time" 10 * H],
+    "AEDT" => ["Australian Eastern Daylight Time" 11 * H],
+    "NZST" => ["New Zealand Standard Time" 12 * H],
+    "NZDT" => ["New Zealand Daylight Time" 13 * H],
+};
+
+/// Parses a timestamp like [`parse_timestamp_flexible`], also accepting a
+/// time zone abbreviation such as `EST` or `CET` in place of the offset.
+/// Unlike it, neither arithmetic nor `now` is accepted.
+///
+/// Abbreviations standing for several offsets, such as `CST` or `IST`, are
+/// rejected with an [`Error::AmbiguousAbbreviation`] listing the meanings.
+/// This is the syntax of [`custom_format!`](crate::custom_format)'s
+/// `abbreviations` option.
+///
+/// # Example
+///
+/// ```
+/// use humantime_serde::{parse_timestamp_abbreviated, Error};
+///
+/// let t = parse_timestamp_abbreviated("2018-05-11 13:28:30 EST").unwrap();
+/// assert_eq!(t.to_rfc3339(), "2018-05-11T13:28:30-05:00");
+///
+/// let err = parse_timestamp_abbreviated("2018-05-11 13:28:30 IST").unwrap_err();
+/// assert!(matches!(err, Error::AmbiguousAbbreviation { name: "IST", .. }));
+/// ```
+#[cfg(feature = "chrono")]
+pub fn parse_timestamp_abbreviated(s: &str) -> Result<DateTime<FixedOffset>, Error> {
+    let s = s.trim();
+    let (rest, word) = match s.rsplit_once(' ') {
+        Some(split) => split,
+        None => return parse_single(s),
+    };
+    let abbreviation = match ABBREVIATIONS.iter().find(|a| a.name == word) {
+        Some(abbreviation) => abbreviation,
+        None => return parse_single(s),
+    };
+    let offset = match abbreviation.offsets {
+        [offset] => FixedOffset::east_opt(*offset).ok_or(Error::NumberOverflow)?,
+        _ => {
+            return Err(Error::AmbiguousAbbreviation {
+                name: abbreviation.name,
+                meanings: abbreviation.meanings,
+            })
+        }
+    };
+    let naive = parse_naive(rest.trim_end())?;
+    naive
+        .and_local_timezone(offset)
+        .single()
+        .ok_or(Error::NonexistentTime)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn duration() {
+        assert_eq!(parse_duration_lenient("2h and 15m").unwrap(), Duration::from_secs(8100));
+        assert_eq!(parse_duration_lenient("1m,30s").unwrap(), Duration::from_secs(90));
+        assert_eq!(parse_duration_lenient("1M").unwrap(), Duration::from_secs(2_630_016));
+        assert!(parse_duration_lenient("and").is_err());
+        assert_eq!(
+            parse_duration_lenient("15 parsecs"),
+            Err(Error::UnknownUnit { start: 3, end: 10, suggestion: None }),
+        );
+    }
+
+    #[test]
+    fn offsets() {
+        let s = " 1 hour, 30 parsecs";
+        let err = Error::UnknownUnit { start: 12, end: 19, suggestion: None };
+        assert_eq!(parse_duration_lenient(s), Err(err));
+        assert_eq!(&s[12..19], "parsecs");
+
+        let s = "1h,  and\t2 parsecs, ";
+        let err = Error::UnknownUnit { start: 11, end: 18, suggestion: None };
+        assert_eq!(parse_duration_lenient(s), Err(err));
+        assert_eq!(&s[11..18], "parsecs");
+
+        assert_eq!(parse_duration_lenient(" 1h and x"), Err(Error::NumberExpected(8)));
+
+        let s = "2 hours,  and 3 secnds";
+        let err = Error::UnknownUnit { start: 16, end: 22, suggestion: Some("seconds") };
+        assert_eq!(parse_duration_lenient(s), Err(err));
+        assert_eq!(&s[16..22], "secnds");
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn timestamp() {
+        let t = parse_timestamp_flexible("2018-05-11T18:28:30Z").unwrap();
+        assert_eq!(t.timestamp(), 1526063310);
+        assert!(parse_timestamp_flexible("2018-05-11").is_err());
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn twelve_hour() {
+        let t = parse_timestamp_flexible("2018-05-11 6:28:30 PM").unwrap();
+        assert_eq!(t.timestamp(), 1526063310);
+        let t = parse_timestamp_flexible("2018-05-11T12:00am").unwrap();
+        assert_eq!(t.to_rfc3339(), "2018-05-11T00:00:00+00:00");
+        let t = parse_timestamp_abbreviated("2018-05-11 2:28:30 pm EDT").unwrap();
+        assert_eq!(t.timestamp(), 1526063310);
+        assert!(parse_timestamp_flexible("2018-05-11 13:28 pm").is_err());
+        assert!(parse_timestamp_flexible("2018-05-11 pm").is_err());
+        assert!(parse_timestamp_flexible("6:28 pm").is_err());
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn arithmetic() {
+        let now = DateTime::<Utc>::UNIX_EPOCH;
+        let parse = |s| parse_timestamp_flexible_at(s, now).map(|t| t.to_rfc3339());
+        assert_eq!(parse("2024-01-01T00:00:00Z + 3 days").unwrap(), "2024-01-04T00:00:00+00:00");
+        assert_eq!(parse("2024-01-01 00:00:00-05:00 - 1h").unwrap(), "2023-12-31T23:00:00-05:00");
+        assert_eq!(parse("NOW + 1m - 30s").unwrap(), "1970-01-01T00:00:30+00:00");
+        assert_eq!(parse(" now ").unwrap(), "1970-01-01T00:00:00+00:00");
+        assert_eq!(parse("2024-01-01 6pm + 2h and 15m").unwrap(), "2024-01-01T20:15:00+00:00");
+        assert!(parse("now + ").is_err());
+        assert!(parse("now + 3 parsecs").is_err());
+        assert!(parse("now+1h").is_err());
+        assert_eq!(parse("now + 10000000000y"), Err(Error::NumberOverflow));
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn keywords() {
+        let t = parse_timestamp_flexible("2018-05-11 noon").unwrap();
+        assert_eq!(t.to_rfc3339(), "2018-05-11T12:00:00+00:00");
+        let t = parse_timestamp_abbreviated("2018-05-11 end of day CET").unwrap();
+        assert_eq!(t.to_rfc3339(), "2018-05-11T23:59:59.999999999+01:00");
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn abbreviated() {
+        let t = parse_timestamp_abbreviated("2018-05-11T20:28:30 CEST").unwrap();
+        assert_eq!(t.timestamp(), 1526063310);
+        let t = parse_timestamp_abbreviated("2018-05-11 15:58:30 NST").unwrap();
+        assert_eq!(t.timestamp(), 1526063310);
+        let t = parse_timestamp_abbreviated("2018-05-11 18:28:30+00:00").unwrap();
+        assert_eq!(t.timestamp(), 1526063310);
+        assert!(parse_timestamp_abbreviated("2018-05-11 18:28:30 XYZ").is_err());
+        assert!(parse_timestamp_abbreviated("now").is_err());
+        assert!(parse_timestamp_abbreviated("2018-05-11 18:28:30 + 1h").is_err());
+
+        let err = parse_timestamp_abbreviated("2018-05-11 18:28:30 CST").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "ambiguous time zone abbreviation CST, which may be Central Standard Time (-06:00), \
+             China Standard Time (+08:00) or Cuba Standard Time (-05:00)"
+        );
+    }
+}
@@ -1,6 +1,6 @@
 #![no_std]
 // #![doc(html_root_url = "https://docs.rs/humantime-serde/1.0")]
-#![forbid(unsafe_code)]
+#![deny(unsafe_code)]
 
 //! Serde support for the `humantime` crate.
 //!
@@ -24,6 +24,21 @@
 //! }
 //! ```
 //!
+//! Or use the `HumanDuration` and `HumanTimestamp` newtypes, which need no
+//! annotation at all:
+//!
+//! ```
+//! use serde::{Serialize, Deserialize};
+//! use humantime_serde::{HumanDuration, HumanTimestamp};
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Foo {
+//!     timeout: HumanDuration,
+//!     retries: Option<Vec<HumanDuration>>,
+//!     time: HumanTimestamp,
+//! }
+//! ```
+//!
 //! Or use the `Serde` wrapper type:
 //!
 //! ```
@@ -36,23 +51,344 @@
 //!     timeout: Vec<Serde<DateTime<Utc>>>,
 //! }
 //! ```
+//!
+//! # Allocation
+//!
+//! Serializing and deserializing a `Duration` or `DateTime` does not
+//! allocate: values are written with `Serializer::collect_str` and read from
+//! the borrowed string the deserializer hands over. [`HumanDisplay`] and
+//! [`format_into`] format without allocating either, for heapless targets.
+//!
+//! # Features
+//!
+//! * `alloc` (default): everything holding a `String` or `Vec`, namely
+//!   [`format()`], the [`vec`], [`option_vec`], [`vec_option`], [`memo`],
+//!   [`set`], [`sorted`], [`config`], [`custom`], [`strftime`] and [`seed`] modules, deserialization in [`delimited`],
+//!   `Buckets`, `RawDuration`, `WeeklySchedule` and the lenient parsers. Without it the
+//!   crate does not use a heap at all.
+//! * `chrono` (default): everything involving `chrono::DateTime`. Without
+//!   it only durations are supported, and chrono is not compiled at all.
+//! * `chrono-tz`: the `in_zone!` macro, generating a module converting
+//!   timestamps into a `chrono_tz` zone. Implies `chrono`.
+//! * `duration`: durations, which are always supported. It exists so a
+//!   chrono-free build can be spelled `default-features = false,
+//!   features = ["duration"]`.
+//! * `std`: conversions between `HumanTimestamp` and `std::time::SystemTime`,
+//!   the [`clock::future`] and [`clock::past`] modules using the system
+//!   clock, `now` in [`parse_timestamp_flexible`], and
+//!   [`with_scratch_buffer`].
+//! * `fast-parse`: selects the [`backend::Fast`] backend, which parses
+//!   durations in a single pass without humantime, falling back to
+//!   humantime for unusual input and for errors, so the result is the same
+//!   either way.
+//! * `unstable-format`: unseals the [`format::HumantimeFormat`] trait so
+//!   other crates can implement it. Not covered by semver.
+//! * `derive`: `#[derive(HumantimeSerde)]`, which implements `Serialize` and
+//!   `Deserialize` for a struct while routing every `Duration`, `DateTime`,
+//!   `Option` and `Vec` of those through this crate, and the `#[humantime]`
+//!   attribute, which adds the right `#[serde(with)]` to the fields marked
+//!   `#[humantime]`.
+//! * `testing`: the [`testing`] module with `serde_test` based assertions.
+//! * `borsh`: `BorshSerialize`/`BorshDeserialize` for the newtypes,
+//!   `Serde<Duration>` and `Serde<DateTime<Utc>>`.
+//! * `rkyv`: `Archive`/`Serialize`/`Deserialize` for the newtypes and the
+//!   `Serde` wrapper.
+//! * `defmt`: `defmt::Format` for `HumanDuration` and `Serde<Duration>`,
+//!   printing the humantime string.
+//! * `valuable`: `valuable::Valuable` for the newtypes and the `Serde`
+//!   wrappers, so they can be recorded as structured `tracing` fields holding
+//!   the serialized string.
+//! * `wasm`: the [`wasm`] module, converting `HumanTimestamp` and
+//!   `Serde<DateTime<Utc>>` to and from `js_sys::Date` and going through
+//!   `serde_wasm_bindgen`.
+//! * `sqlx`: `sqlx::Type`/`Encode`/`Decode` for `HumanDuration` and
+//!   `Serde<Duration>` (Postgres `interval`), and for `HumanTimestamp` and
+//!   `Serde<DateTime<Utc>>` (Postgres `timestamptz`).
+//! * `rand`: `Jittered::sample`, drawing a jittered duration from a
+//!   `rand::Rng`.
+//! * `cron`: `Schedule`, a cron expression validated on deserialization
+//!   with the `cron` crate. Implies `std`.
+//! * `natural`: `Recurrence`, a schedule written in simple English such as
+//!   `"every monday at 9am"`. Implies `alloc` and `chrono`.
+//! * `json`: the [`transcode`] module, converting durations and timestamps
+//!   inside a `serde_json::Value`.
 
+#[cfg(feature = "alloc")]
 extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
+// Lets the derive macro's `::humantime_serde` paths resolve in this crate.
+extern crate self as humantime_serde;
 
 /// Reexport module.
 pub mod re {
+    #[cfg(feature = "chrono")]
+    pub use chrono;
+    #[cfg(feature = "chrono-tz")]
+    pub use chrono_tz;
     pub use humantime;
+    pub use serde;
 }
 
+#[cfg(feature = "chrono")]
+mod age;
+#[cfg(feature = "chrono")]
+mod ambiguity;
+#[cfg(feature = "chrono")]
+pub mod as_utc;
+pub mod backend;
+mod backoff;
+#[cfg(feature = "alloc")]
+mod buckets;
+pub mod bounded;
+#[cfg(feature = "chrono")]
+pub mod clock;
+pub mod compat;
+#[cfg(all(feature = "alloc", feature = "chrono"))]
+pub mod config;
+#[cfg(all(feature = "alloc", feature = "chrono"))]
+pub mod custom;
+#[cfg(feature = "chrono")]
+mod deadline;
+pub mod delimited;
+pub mod dialect;
+mod duration;
+mod error;
+mod every;
+#[cfg(feature = "fast-parse")]
+mod fast;
+#[cfg(feature = "chrono")]
+mod expiry;
+pub mod format;
+pub mod frequency;
+pub mod grammar;
+pub mod granularity;
+#[cfg(feature = "chrono")]
+pub mod httpdate;
+pub mod iso;
+#[cfg(feature = "chrono")]
+pub mod ixdtf;
+pub mod kubernetes;
+mod jitter;
+#[cfg(feature = "alloc")]
+mod lenient;
+mod literal;
+#[cfg(feature = "alloc")]
+pub mod memo;
+mod ops;
 pub mod option;
+#[cfg(feature = "alloc")]
+pub mod option_vec;
+mod period;
+pub mod prometheus;
+mod rate;
+#[cfg(feature = "alloc")]
+mod raw;
+#[cfg(feature = "natural")]
+mod recurrence;
+#[cfg(all(feature = "alloc", feature = "chrono"))]
+mod schedule;
+pub mod saturating;
+#[cfg(feature = "std")]
+mod scratch;
+#[cfg(feature = "alloc")]
+pub mod seed;
+pub mod seq;
+#[cfg(feature = "alloc")]
+pub mod set;
+#[cfg(feature = "alloc")]
+pub mod sorted;
+mod spanned;
+#[cfg(all(feature = "alloc", feature = "chrono"))]
+pub mod strftime;
+#[cfg(feature = "chrono")]
+pub mod strict_utc;
+pub mod ticks;
+#[doc(hidden)]
+#[path = "private.rs"]
+pub mod __private;
+#[cfg(feature = "chrono")]
+mod time_of_day;
+mod timeout;
+#[cfg(feature = "chrono")]
+mod timestamp;
+#[cfg(feature = "alloc")]
+pub mod vec;
+#[cfg(feature = "alloc")]
+pub mod vec_option;
+#[cfg(feature = "chrono")]
+mod window;
+#[cfg(feature = "chrono")]
+pub mod zone;
+#[cfg(feature = "borsh")]
+mod borsh_impl;
+#[cfg(feature = "cron")]
+mod cron_impl;
+#[cfg(feature = "defmt")]
+mod defmt_impl;
+#[cfg(feature = "rkyv")]
+mod rkyv_impl;
+#[cfg(feature = "valuable")]
+mod valuable_impl;
+#[cfg(feature = "sqlx")]
+mod sqlx_impl;
+#[cfg(feature = "testing")]
+pub mod testing;
+#[cfg(feature = "json")]
+pub mod transcode;
+#[cfg(feature = "wasm")]
+pub mod wasm;
 
-use alloc::string::ToString;
+#[cfg(feature = "alloc")]
+use alloc::string::{String, ToString};
+use core::borrow::{Borrow, BorrowMut};
+use core::convert::TryFrom;
 use core::fmt;
+use core::marker::PhantomData;
 use core::ops::{Deref, DerefMut};
+use core::str::FromStr;
 use core::time::{Duration};
 
 use serde::{de, ser, Deserialize, Deserializer, Serialize, Serializer};
-use chrono::{DateTime, FixedOffset, SecondsFormat, Utc};
+#[cfg(feature = "chrono")]
+use chrono::{DateTime, FixedOffset, TimeZone, Utc};
+
+use format::{HumantimeFormat, Ref};
+
+#[cfg(feature = "chrono")]
+pub use age::Age;
+#[cfg(feature = "chrono")]
+pub use ambiguity::Ambiguity;
+pub use backoff::{BackoffKind, BackoffSpec, InvalidBackoff};
+#[cfg(feature = "alloc")]
+pub use buckets::{Buckets, InvalidBuckets};
+#[cfg(feature = "chrono")]
+pub use deadline::{DeadlineError, DurationOrTimestamp};
+pub use duration::HumanDuration;
+pub use error::Error;
+pub use every::{Every, InvalidEvery};
+#[cfg(feature = "chrono")]
+pub use expiry::Expiry;
+pub use format::HumanDisplay;
+pub use jitter::{InvalidJitter, Jittered, Spread};
+#[cfg(feature = "alloc")]
+pub use lenient::parse_duration_lenient;
+#[cfg(all(feature = "alloc", feature = "chrono"))]
+pub use lenient::{
+    parse_timestamp_abbreviated, parse_timestamp_flexible, parse_timestamp_flexible_at,
+};
+pub use period::{InvalidPeriod, Period, Unit};
+pub use rate::{InvalidRate, Rate};
+#[cfg(feature = "alloc")]
+pub use raw::RawDuration;
+#[cfg(feature = "natural")]
+pub use recurrence::{InvalidRecurrence, Recurrence};
+#[cfg(feature = "std")]
+pub use scratch::with_scratch_buffer;
+#[cfg(all(feature = "alloc", feature = "chrono"))]
+pub use schedule::WeeklySchedule;
+pub use spanned::Spanned;
+#[cfg(feature = "chrono")]
+pub use time_of_day::{InvalidTimeOfDay, TimeOfDay};
+pub use timeout::Timeout;
+#[cfg(feature = "chrono")]
+pub use timestamp::HumanTimestamp;
+#[cfg(feature = "chrono")]
+pub use window::{InvalidTimeWindow, TimeWindow};
+
+#[cfg(feature = "cron")]
+pub use cron_impl::Schedule;
+/// `#[humantime]` only accepts the standard `Duration` and chrono's
+/// `DateTime`, so it rejects a foreign type of the same name:
+///
+/// ```compile_fail
+/// use humantime_serde::humantime;
+/// use serde::{Deserialize, Serialize};
+///
+/// mod units {
+///     #[derive(serde::Serialize, serde::Deserialize)]
+///     pub struct Duration(pub u64);
+/// }
+///
+/// #[humantime]
+/// #[derive(Serialize, Deserialize)]
+/// struct Config {
+///     #[humantime]
+///     ticks: units::Duration,
+/// }
+/// ```
+#[cfg(feature = "derive")]
+pub use humantime_serde_derive::{humantime, HumantimeSerde};
+
+/// The longest string deserialization accepts by default, in bytes.
+///
+/// Longer input is rejected before it is parsed, so an untrusted document
+/// cannot have the parser walk through megabytes of digits. The seeds and
+/// [`custom_format!`] can set their own limit.
+pub const DEFAULT_MAX_LEN: usize = 256;
+
+/// Rejects `v` if it is longer than `max` bytes.
+pub(crate) fn check_len<E>(v: &str, max: usize, expecting: &str) -> Result<(), E>
+where
+    E: de::Error,
+{
+    if v.len() <= max {
+        return Ok(());
+    }
+    Err(E::custom(format_args!(
+        "input of {} bytes is too long for {}, the limit is {}",
+        v.len(),
+        expecting,
+        max,
+    )))
+}
+
+/// What a deserializer expected, followed by why the input did not parse,
+/// as in `expected a duration: unknown time unit "parsecs"`.
+pub(crate) struct Because<'a, R>(pub &'a dyn de::Expected, pub &'a R);
+
+impl<R> de::Expected for Because<'_, R>
+where
+    R: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {}", self.0, self.1)
+    }
+}
+
+/// A parse error followed by a suggested unit, if there is one, as in
+/// `unknown time unit "secnds", ..., did you mean "seconds"?`.
+pub(crate) struct DidYouMean<'a, R>(pub &'a R, pub Option<&'static str>);
+
+impl<R> fmt::Display for DidYouMean<'_, R>
+where
+    R: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(self.0, f)?;
+        match self.1 {
+            Some(unit) => write!(f, ", did you mean {:?}?", unit),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Parses `v`, reporting an overflow, or else what `expected` wanted and
+/// why `v` did not parse, as the deserializers of this crate do.
+pub(crate) fn parse_str<T, E>(v: &str, expected: &dyn de::Expected) -> Result<T, E>
+where
+    T: HumantimeFormat,
+    E: de::Error,
+{
+    check_len(v, DEFAULT_MAX_LEN, T::EXPECTING)?;
+    T::parse(v).map_err(|err| {
+        if T::is_overflow(&err) {
+            return E::custom(format_args!("{:?} is too large for {}", v, T::EXPECTING));
+        }
+        let err = DidYouMean(&err, T::suggestion(v, &err));
+        E::invalid_value(de::Unexpected::Str(v), &Because(expected, &err))
+    })
+}
 
 /// Deserializes a `Duration` or `DateTime<Tz>` via the humantime crate.
 ///
@@ -72,15 +408,102 @@ where
 /// `serialize_with` annotations.
 pub fn serialize<T, S>(d: &T, s: S) -> Result<S::Ok, S::Error>
 where
-    for<'a> Serde<&'a T>: Serialize,
+    T: ?Sized,
+    for<'a> Ref<'a, T>: Serialize,
     S: Serializer,
 {
-    Serde::from(d).serialize(s)
+    Ref(d).serialize(s)
+}
+
+/// Parses a `Duration` or `DateTime<Tz>` exactly as deserialization does.
+///
+/// The [`Error`] tells why the input was rejected.
+///
+/// # Example
+///
+/// ```
+/// use core::time::Duration;
+///
+/// let d: Duration = humantime_serde::parse("1m 30s").unwrap();
+/// assert_eq!(d, Duration::from_secs(90));
+/// ```
+pub fn parse<T>(s: &str) -> Result<T, Error>
+where
+    T: HumantimeFormat,
+    Error: From<T::Err>,
+{
+    T::parse(s).map_err(|err| Error::from(err).with_input(s))
+}
+
+/// Formats a `Duration` or `DateTime<Tz>` exactly as serialization does.
+///
+/// # Example
+///
+/// ```
+/// use core::time::Duration;
+///
+/// assert_eq!(humantime_serde::format(&Duration::from_secs(90)), "1m 30s");
+/// ```
+#[cfg(feature = "alloc")]
+pub fn format<T>(d: &T) -> String
+where
+    T: HumantimeFormat,
+{
+    Ref(d).to_string()
+}
+
+/// Formats a `Duration` or `DateTime<Tz>` into `buf` without allocating.
+///
+/// Returns the written part of `buf`, or an error if it is too short.
+///
+/// # Example
+///
+/// ```
+/// use core::time::Duration;
+///
+/// let mut buf = [0; 32];
+/// let s = humantime_serde::format_into(&Duration::from_secs(90), &mut buf).unwrap();
+/// assert_eq!(s, "1m 30s");
+/// ```
+pub fn format_into<'b, T>(d: &T, buf: &'b mut [u8]) -> Result<&'b str, fmt::Error>
+where
+    T: HumantimeFormat,
+{
+    let mut w = format::SliceWriter::new(buf);
+    fmt::write(&mut w, format_args!("{}", Ref(d)))?;
+    w.into_str()
+}
+
+/// Rewrites a duration in the form serialization writes, so configuration
+/// files and linters can normalize input the way a round-trip would.
+///
+/// # Example
+///
+/// ```
+/// assert_eq!(humantime_serde::canonicalize_duration("90 sec").unwrap(), "1m 30s");
+/// ```
+#[cfg(feature = "alloc")]
+pub fn canonicalize_duration(s: &str) -> Result<String, Error> {
+    parse::<Duration>(s).map(|d| format(&d))
+}
+
+/// Rewrites a timestamp in the form serialization writes, keeping its
+/// offset.
+///
+/// # Example
+///
+/// ```
+/// let s = humantime_serde::canonicalize_timestamp("2018-05-11T20:28:30.000+02:00").unwrap();
+/// assert_eq!(s, "2018-05-11T20:28:30+02:00");
+/// ```
+#[cfg(all(feature = "alloc", feature = "chrono"))]
+pub fn canonicalize_timestamp(s: &str) -> Result<String, Error> {
+    parse::<DateTime<FixedOffset>>(s).map(|t| format(&t))
 }
 
 /// A wrapper type which implements `Serialize` and `Deserialize` for
 /// types involving `DateTime<Tz>` and `Duration`.
-#[derive(Copy, Clone, Eq, Hash, PartialEq)]
+#[derive(Copy, Clone, Default, Eq, Hash, PartialEq, Ord, PartialOrd)]
 pub struct Serde<T>(T);
 
 impl<T> fmt::Debug for Serde<T>
@@ -92,6 +515,37 @@ where
     }
 }
 
+impl<T> fmt::Display for Serde<T>
+where
+    T: HumantimeFormat,
+{
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        self.0.format(formatter)
+    }
+}
+
+impl<T> FromStr for Serde<T>
+where
+    T: HumantimeFormat,
+{
+    type Err = T::Err;
+
+    fn from_str(s: &str) -> Result<Serde<T>, T::Err> {
+        T::parse(s).map(Serde)
+    }
+}
+
+impl<T> TryFrom<&str> for Serde<T>
+where
+    T: HumantimeFormat,
+{
+    type Error = T::Err;
+
+    fn try_from(s: &str) -> Result<Serde<T>, T::Err> {
+        T::parse(s).map(Serde)
+    }
+}
+
 impl<T> Deref for Serde<T> {
     type Target = T;
 
@@ -106,6 +560,55 @@ impl<T> DerefMut for Serde<T> {
     }
 }
 
+impl<T> AsRef<T> for Serde<T> {
+    fn as_ref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> AsMut<T> for Serde<T> {
+    fn as_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+impl<T> Borrow<T> for Serde<T> {
+    fn borrow(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> BorrowMut<T> for Serde<T> {
+    fn borrow_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+impl<T> PartialEq<T> for Serde<T>
+where
+    T: PartialEq,
+{
+    fn eq(&self, other: &T) -> bool {
+        self.0 == *other
+    }
+}
+
+impl PartialEq<Serde<Duration>> for Duration {
+    fn eq(&self, other: &Serde<Duration>) -> bool {
+        *self == other.0
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl<Tz> PartialEq<Serde<DateTime<Tz>>> for DateTime<Tz>
+where
+    Tz: TimeZone,
+{
+    fn eq(&self, other: &Serde<DateTime<Tz>>) -> bool {
+        *self == other.0
+    }
+}
+
 impl<T> Serde<T> {
     /// Consumes the `De`, returning the inner value.
     pub fn into_inner(self) -> T {
@@ -119,256 +622,131 @@ impl<T> From<T> for Serde<T> {
     }
 }
 
-impl<'de> Deserialize<'de> for Serde<Duration> {
-    fn deserialize<D>(d: D) -> Result<Serde<Duration>, D::Error>
+impl<'de, T> Deserialize<'de> for Serde<T>
+where
+    T: HumantimeFormat,
+{
+    fn deserialize<D>(d: D) -> Result<Serde<T>, D::Error>
     where
         D: Deserializer<'de>,
     {
-        struct V;
+        struct V<T>(PhantomData<T>);
 
-        impl de::Visitor<'_> for V {
-            type Value = Duration;
+        impl<'de, T> de::Visitor<'de> for V<T>
+        where
+            T: HumantimeFormat,
+        {
+            type Value = T;
 
             fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
-                fmt.write_str("a duration")
+                fmt.write_str(T::EXPECTING)
             }
 
-            fn visit_str<E>(self, v: &str) -> Result<Duration, E>
+            fn visit_str<E>(self, v: &str) -> Result<T, E>
             where
                 E: de::Error,
             {
-                humantime::parse_duration(v).map_err(|_| {
-                    E::invalid_value(de::Unexpected::Str(v), &self)
-                })
+                parse_str(v, &self)
             }
-        }
 
-        d.deserialize_str(V).map(Serde)
-    }
-}
-
-impl<'de> Deserialize<'de> for Serde<DateTime<Utc>> {
-    fn deserialize<D>(d: D) -> Result<Serde<DateTime<Utc>>, D::Error>
-    where
-        D: Deserializer<'de>,
-    {
-        struct V;
-
-        impl de::Visitor<'_> for V {
-            type Value = DateTime<Utc>;
-
-            fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
-                fmt.write_str("a timestamp")
-            }
-
-            fn visit_str<E>(self, v: &str) -> Result<DateTime<Utc>, E>
+            // Parses the input where the deserializer lends it, without
+            // copying it first.
+            fn visit_borrowed_str<E>(self, v: &'de str) -> Result<T, E>
             where
                 E: de::Error,
             {
-                Ok(
-                    DateTime::parse_from_rfc3339(v).map_err(
-                        |_| E::invalid_value(de::Unexpected::Str(v), &self)
-                    )?.to_utc()
-                )
+                self.visit_str(v)
             }
-        }
-
-        d.deserialize_str(V).map(Serde)
-    }
-}
 
-impl<'de> Deserialize<'de> for Serde<DateTime<FixedOffset>> {
-    fn deserialize<D>(d: D) -> Result<Serde<DateTime<FixedOffset>>, D::Error>
-    where
-        D: Deserializer<'de>,
-    {
-        struct V;
-
-        impl de::Visitor<'_> for V {
-            type Value = DateTime<FixedOffset>;
-
-            fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
-                fmt.write_str("a timestamp")
-            }
-
-            fn visit_str<E>(self, v: &str) -> Result<DateTime<FixedOffset>, E>
+            // Parses an owned buffer in place; the error borrows it rather
+            // than copying it.
+            #[cfg(feature = "alloc")]
+            fn visit_string<E>(self, v: String) -> Result<T, E>
             where
                 E: de::Error,
             {
-                DateTime::parse_from_rfc3339(v).map_err(
-                    |_| E::invalid_value(de::Unexpected::Str(v), &self)
-                )
+                self.visit_str(&v)
             }
         }
 
-        d.deserialize_str(V).map(Serde)
-    }
-}
-
-impl<'de> Deserialize<'de> for Serde<Option<Duration>> {
-    fn deserialize<D>(d: D) -> Result<Serde<Option<Duration>>, D::Error>
-    where
-        D: Deserializer<'de>,
-    {
-        match Option::<Serde<Duration>>::deserialize(d)? {
-            Some(Serde(dur)) => Ok(Serde(Some(dur))),
-            None => Ok(Serde(None)),
-        }
-    }
-}
-
-impl<'de> Deserialize<'de> for Serde<Option<DateTime<Utc>>> {
-    fn deserialize<D>(d: D) -> Result<Serde<Option<DateTime<Utc>>>, D::Error>
-    where
-        D: Deserializer<'de>,
-    {
-        match Option::<Serde<DateTime<Utc>>>::deserialize(d)? {
-            Some(Serde(dur)) => Ok(Serde(Some(dur))),
-            None => Ok(Serde(None)),
-        }
+        d.deserialize_str(V(PhantomData)).map(Serde)
     }
 }
 
-impl<'de> Deserialize<'de> for Serde<Option<DateTime<FixedOffset>>> {
-    fn deserialize<D>(d: D) -> Result<Serde<Option<DateTime<FixedOffset>>>, D::Error>
+impl<'de, T> Deserialize<'de> for Serde<Option<T>>
+where
+    T: HumantimeFormat,
+{
+    fn deserialize<D>(d: D) -> Result<Serde<Option<T>>, D::Error>
     where
         D: Deserializer<'de>,
     {
-        match Option::<Serde<DateTime<FixedOffset>>>::deserialize(d)? {
-            Some(Serde(dur)) => Ok(Serde(Some(dur))),
+        match Option::<Serde<T>>::deserialize(d)? {
+            Some(Serde(v)) => Ok(Serde(Some(v))),
             None => Ok(Serde(None)),
         }
     }
 }
 
-impl ser::Serialize for Serde<&Duration> {
-    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
-    where
-        S: ser::Serializer,
-    {
-        humantime::format_duration(*self.0)
-            .to_string()
-            .serialize(serializer)
-    }
-}
-
-impl ser::Serialize for Serde<Duration> {
-    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
-    where
-        S: ser::Serializer,
-    {
-        humantime::format_duration(self.0)
-            .to_string()
-            .serialize(serializer)
-    }
-}
-
-impl ser::Serialize for Serde<&DateTime<Utc>> {
-    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
-    where
-        S: ser::Serializer,
-    {
-        self.0.to_rfc3339_opts(SecondsFormat::Secs, true)
-            .serialize(serializer)
-    }
-}
-
-impl ser::Serialize for Serde<&DateTime<FixedOffset>> {
-    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
-    where
-        S: ser::Serializer,
-    {
-        self.0.to_rfc3339_opts(SecondsFormat::Secs, true)
-            .serialize(serializer)
-    }
-}
-
-impl ser::Serialize for Serde<DateTime<Utc>> {
-    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
-    where
-        S: ser::Serializer,
-    {
-        self.0.to_rfc3339_opts(SecondsFormat::Secs, true)
-            .serialize(serializer)
-    }
-}
-
-impl ser::Serialize for Serde<DateTime<FixedOffset>> {
-    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
-    where
-        S: ser::Serializer,
-    {
-        self.0.to_rfc3339_opts(SecondsFormat::Secs, true)
-            .serialize(serializer)
-    }
-}
-
-impl ser::Serialize for Serde<&Option<Duration>> {
+impl<T> ser::Serialize for Serde<T>
+where
+    T: HumantimeFormat,
+{
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: ser::Serializer,
     {
-        match *self.0 {
-            Some(dur) => serializer.serialize_some(&Serde(dur)),
-            None => serializer.serialize_none(),
-        }
+        Ref(&self.0).serialize(serializer)
     }
 }
 
-impl ser::Serialize for Serde<Option<Duration>> {
+impl<T> ser::Serialize for Serde<Option<T>>
+where
+    T: HumantimeFormat,
+{
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: ser::Serializer,
     {
-        Serde(&self.0).serialize(serializer)
+        Ref(&self.0).serialize(serializer)
     }
 }
 
-impl ser::Serialize for Serde<&Option<DateTime<Utc>>> {
+impl<T> ser::Serialize for Serde<&Option<T>>
+where
+    T: HumantimeFormat,
+{
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: ser::Serializer,
     {
-        match *self.0 {
-            Some(tm) => serializer.serialize_some(&Serde(tm)),
-            None => serializer.serialize_none(),
-        }
+        Ref(self.0).serialize(serializer)
     }
 }
 
-impl ser::Serialize for Serde<&Option<DateTime<FixedOffset>>> {
-    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
-    where
-        S: ser::Serializer,
-    {
-        match *self.0 {
-            Some(tm) => serializer.serialize_some(&Serde(tm)),
-            None => serializer.serialize_none(),
+// `Serde<&T>` cannot be implemented generically next to `Serde<T>`, since
+// `&T` could implement `HumantimeFormat` as far as coherence is concerned.
+macro_rules! impl_serialize_ref {
+    ($($ty:ty),*) => {$(
+        impl ser::Serialize for Serde<&$ty> {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: ser::Serializer,
+            {
+                Ref(self.0).serialize(serializer)
+            }
         }
-    }
+    )*};
 }
 
-impl ser::Serialize for Serde<Option<DateTime<Utc>>> {
-    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
-    where
-        S: ser::Serializer,
-    {
-        Serde(&self.0).serialize(serializer)
-    }
-}
+impl_serialize_ref!(Duration);
+#[cfg(feature = "chrono")]
+impl_serialize_ref!(DateTime<Utc>, DateTime<FixedOffset>);
 
-impl ser::Serialize for Serde<Option<DateTime<FixedOffset>>> {
-    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
-    where
-        S: ser::Serializer,
-    {
-        Serde(&self.0).serialize(serializer)
-    }
-}
-
-#[cfg(test)]
+#[cfg(all(test, feature = "alloc"))]
 mod test {
     use super::*;
+    use alloc::string::ToString;
 
     #[test]
     fn with() {
@@ -410,6 +788,7 @@ mod test {
         assert_eq!(foo.time, None);
     }
 
+    #[cfg(feature = "chrono")]
     #[test]
     fn time() {
         #[derive(Serialize, Deserialize)]
@@ -425,6 +804,7 @@ mod test {
         assert_eq!(reverse, r#"{"time":"2018-05-11T18:28:30Z"}"#);
     }
 
+    #[cfg(feature = "chrono")]
     #[test]
     fn time_with_option() {
         #[derive(Serialize, Deserialize)]
@@ -450,6 +830,273 @@ mod test {
         assert_eq!(foo.time, None);
     }
 
+    #[test]
+    fn borrowed_and_owned() {
+        use serde::de::value::{BorrowedStrDeserializer, Error, StringDeserializer};
+
+        let d = BorrowedStrDeserializer::<Error>::new("90s");
+        assert_eq!(Serde::<Duration>::deserialize(d).unwrap(), Duration::from_secs(90));
+        let d = StringDeserializer::<Error>::new("90s".to_string());
+        assert_eq!(Serde::<Duration>::deserialize(d).unwrap(), Duration::from_secs(90));
+        let d = StringDeserializer::<Error>::new("90 parsecs".to_string());
+        let err = Serde::<Duration>::deserialize(d).unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with(r#"invalid value: string "90 parsecs", expected a duration: unknown time unit "parsecs""#));
+    }
+
+    #[test]
+    fn max_len() {
+        let long = alloc::format!("\"{}s\"", "1".repeat(DEFAULT_MAX_LEN));
+        let err = serde_json::from_str::<Serde<Duration>>(&long).unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with("input of 258 bytes is too long for a duration, the limit is 256"));
+    }
+
+    #[test]
+    fn overflow() {
+        let err = serde_json::from_str::<Serde<Duration>>(r#""1000000000000000000000 years""#)
+            .unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with(r#""1000000000000000000000 years" is too large for a duration"#));
+    }
+
+    #[test]
+    fn suggestion() {
+        let err = serde_json::from_str::<Serde<Duration>>(r#""10 secnds""#).unwrap_err();
+        assert!(err.to_string().contains(r#", did you mean "seconds"?"#), "{}", err);
+    }
+
+    #[test]
+    fn display() {
+        let dur = Serde::from(Duration::from_secs(90));
+        assert_eq!(dur.to_string(), serde_json::to_value(dur).unwrap());
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn display_time() {
+        let time = Serde::from(DateTime::UNIX_EPOCH + Duration::new(1526063310, 0));
+        assert_eq!(time.to_string(), "2018-05-11T18:28:30Z");
+
+        let offset = DateTime::parse_from_rfc3339("2018-05-11T20:28:30+02:00").unwrap();
+        assert_eq!(Serde::from(offset).to_string(), "2018-05-11T20:28:30+02:00");
+    }
+
+    #[test]
+    fn from_str() {
+        let dur = "15s".parse::<Serde<Duration>>().unwrap();
+        assert_eq!(*dur, Duration::from_secs(15));
+        assert!("15 parsecs".parse::<Serde<Duration>>().is_err());
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn from_str_time() {
+        let time = "2018-05-11T20:28:30+02:00".parse::<Serde<DateTime<Utc>>>().unwrap();
+        assert_eq!(*time, DateTime::UNIX_EPOCH + Duration::new(1526063310, 0));
+        let offset = Serde::<DateTime<FixedOffset>>::try_from("2018-05-11T20:28:30+02:00").unwrap();
+        assert_eq!(offset.offset().local_minus_utc(), 7200);
+        assert!(Serde::<DateTime<Utc>>::try_from("2018-05-11").is_err());
+    }
+
+    #[test]
+    fn parse_format() {
+        let dur: Duration = parse("1h 15s").unwrap();
+        assert_eq!(format(&dur), serde_json::to_value(Serde::from(dur)).unwrap());
+        assert!(parse::<Duration>("1 fortnight").is_err());
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn parse_format_time() {
+        let time: DateTime<Utc> = parse("2018-05-11T20:28:30+02:00").unwrap();
+        assert_eq!(format(&time), "2018-05-11T18:28:30Z");
+        let offset: DateTime<FixedOffset> = parse("2018-05-11T20:28:30+02:00").unwrap();
+        assert_eq!(format(&offset), "2018-05-11T20:28:30+02:00");
+    }
+
+    #[test]
+    fn format_into_slice() {
+        let mut buf = [0; 20];
+        assert_eq!(format_into(&Duration::from_millis(1500), &mut buf), Ok("1s 500ms"));
+        assert!(format_into(&Duration::new(1526063310, 1), &mut buf).is_err());
+        #[cfg(feature = "chrono")]
+        {
+            let time = DateTime::<Utc>::UNIX_EPOCH + Duration::new(1526063310, 0);
+            assert_eq!(format_into(&time, &mut buf), Ok("2018-05-11T18:28:30Z"));
+        }
+    }
+
+    #[test]
+    fn canonicalize() {
+        assert_eq!(canonicalize_duration("90 sec").unwrap(), "1m 30s");
+        assert_eq!(canonicalize_duration("1h 30m").unwrap(), "1h 30m");
+        assert!(canonicalize_duration("soon").is_err());
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn canonicalize_time() {
+        assert_eq!(canonicalize_timestamp("2018-05-11T18:28:30+00:00").unwrap(), "2018-05-11T18:28:30Z");
+        assert!(canonicalize_timestamp("2018-05-11").is_err());
+    }
+
+    #[test]
+    fn ordering() {
+        use alloc::collections::BTreeMap;
+
+        let mut map = BTreeMap::new();
+        map.insert(Serde::from(Duration::from_secs(60)), "slow");
+        map.insert(Serde::from(Duration::from_secs(1)), "fast");
+        assert_eq!(map.values().next(), Some(&"fast"));
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn ordering_time() {
+        use alloc::vec;
+
+        let mut times = vec![
+            Serde::from(DateTime::<Utc>::UNIX_EPOCH + Duration::from_secs(2)),
+            Serde::from(DateTime::<Utc>::UNIX_EPOCH),
+        ];
+        times.sort();
+        assert_eq!(*times[0], DateTime::<Utc>::UNIX_EPOCH);
+    }
+
+    #[test]
+    fn default() {
+        #[derive(Default, Serialize, Deserialize)]
+        struct Foo {
+            #[serde(default)]
+            time: Serde<Duration>,
+        }
+
+        let foo = serde_json::from_str::<Foo>("{}").unwrap();
+        assert_eq!(*foo.time, Duration::ZERO);
+        assert_eq!(*Foo::default().time, Duration::ZERO);
+    }
+
+    #[test]
+    fn compare_inner() {
+        use alloc::collections::BTreeSet;
+
+        let dur = Serde::from(Duration::from_secs(15));
+        assert_eq!(dur, Duration::from_secs(15));
+        assert_eq!(Duration::from_secs(15), dur);
+        #[cfg(feature = "chrono")]
+        {
+            let time = Serde::from(DateTime::<Utc>::UNIX_EPOCH);
+            assert_eq!(time, DateTime::<Utc>::UNIX_EPOCH);
+            assert_eq!(DateTime::<Utc>::UNIX_EPOCH, time);
+        }
+
+        let set: BTreeSet<_> = [dur].iter().copied().collect();
+        assert!(set.contains(&Duration::from_secs(15)));
+        assert_eq!(AsRef::<Duration>::as_ref(&dur).as_secs(), 15);
+    }
+
+    #[cfg(all(feature = "derive", feature = "chrono"))]
+    #[test]
+    fn derive() {
+        use alloc::string::String;
+        use alloc::vec::Vec;
+
+        #[derive(HumantimeSerde)]
+        #[serde(rename_all = "kebab-case")]
+        struct Foo {
+            read_timeout: Duration,
+            time: DateTime<Utc>,
+            idle: Option<Duration>,
+            retries: Vec<Duration>,
+            #[serde(default)]
+            name: String,
+        }
+
+        let json = r#"{
+            "read-timeout": "15 seconds",
+            "time": "2018-05-11T18:28:30Z",
+            "retries": ["1s", "1m"]
+        }"#;
+        let foo = serde_json::from_str::<Foo>(json).unwrap();
+        assert_eq!(foo.read_timeout, Duration::from_secs(15));
+        assert_eq!(foo.idle, None);
+        assert_eq!(foo.retries[1], Duration::from_secs(60));
+        let reverse = serde_json::to_string(&foo).unwrap();
+        assert_eq!(
+            reverse,
+            r#"{"read-timeout":"15s","time":"2018-05-11T18:28:30Z","idle":null,"retries":["1s","1m"],"name":""}"#
+        );
+    }
+
+    #[cfg(feature = "derive")]
+    #[test]
+    fn derive_foreign_duration() {
+        mod units {
+            #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+            pub struct Duration(pub u64);
+        }
+
+        #[derive(HumantimeSerde)]
+        struct Foo {
+            timeout: core::time::Duration,
+            ticks: units::Duration,
+            idle: Option<units::Duration>,
+        }
+
+        let json = r#"{"timeout": "15 seconds", "ticks": 3, "idle": null}"#;
+        let foo = serde_json::from_str::<Foo>(json).unwrap();
+        assert_eq!(foo.timeout, Duration::from_secs(15));
+        assert_eq!(foo.ticks, units::Duration(3));
+        let reverse = serde_json::to_string(&foo).unwrap();
+        assert_eq!(reverse, r#"{"timeout":"15s","ticks":3,"idle":null}"#);
+    }
+
+    #[cfg(feature = "derive")]
+    #[test]
+    fn derive_skip() {
+        use units::Duration;
+
+        mod units {
+            #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+            pub struct Duration(pub u64);
+        }
+
+        #[derive(HumantimeSerde)]
+        struct Foo {
+            #[humantime_serde(skip)]
+            ticks: Duration,
+        }
+
+        let foo = serde_json::from_str::<Foo>(r#"{"ticks": 3}"#).unwrap();
+        assert_eq!(foo.ticks, Duration(3));
+        assert_eq!(serde_json::to_string(&foo).unwrap(), r#"{"ticks":3}"#);
+    }
+
+    #[cfg(feature = "derive")]
+    #[test]
+    fn humantime_attribute() {
+        #[crate::humantime]
+        #[derive(Serialize, Deserialize)]
+        struct Foo {
+            #[humantime]
+            timeout: Duration,
+            #[humantime]
+            idle: Option<Duration>,
+            retries: u32,
+        }
+
+        let json = r#"{"timeout": "15 seconds", "retries": 3}"#;
+        let foo = serde_json::from_str::<Foo>(json).unwrap();
+        assert_eq!(foo.timeout, Duration::from_secs(15));
+        assert_eq!(foo.idle, None);
+        let reverse = serde_json::to_string(&foo).unwrap();
+        assert_eq!(reverse, r#"{"timeout":"15s","idle":null,"retries":3}"#);
+    }
+
     #[test]
     fn test_readme_deps() {
         version_sync::assert_markdown_deps_updated!("README.md");
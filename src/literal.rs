@@ -0,0 +1,248 @@
+//! Durations parsed at compile time with [`duration!`], and default value
+//! functions declared with [`default_fn!`].
+//!
+//! [`duration!`]: crate::duration
+//! [`default_fn!`]: crate::default_fn
+
+use core::time::Duration;
+
+use super::grammar::DURATION_UNITS;
+
+const NANOS_PER_SEC: u64 = 1_000_000_000;
+
+/// Parses a humantime duration in a const context, panicking (and so failing
+/// compilation) if it is invalid.
+pub const fn parse_duration(s: &str) -> Duration {
+    let b = s.as_bytes();
+    let mut i = 0;
+    let mut secs: u64 = 0;
+    let mut nanos: u64 = 0;
+    let mut empty = true;
+    loop {
+        i = skip_spaces(b, i);
+        if i == b.len() {
+            break;
+        }
+        if !b[i].is_ascii_digit() {
+            panic!("invalid duration: expected a number");
+        }
+        let mut n: u64 = 0;
+        while i < b.len() && b[i].is_ascii_digit() {
+            n = match n.checked_mul(10) {
+                Some(n) => n,
+                None => panic!("invalid duration: number is too large"),
+            };
+            n = match n.checked_add((b[i] - b'0') as u64) {
+                Some(n) => n,
+                None => panic!("invalid duration: number is too large"),
+            };
+            i += 1;
+        }
+        i = skip_spaces(b, i);
+        let start = i;
+        while i < b.len() && b[i].is_ascii_alphabetic() {
+            i += 1;
+        }
+        if start == i {
+            panic!("invalid duration: expected a unit after the number");
+        }
+        let unit = match unit_length(b, start, i) {
+            Some(unit) => unit,
+            None => panic!("invalid duration: unknown unit"),
+        };
+        let add = match n.checked_mul(unit.as_secs()) {
+            Some(add) => add,
+            None => panic!("invalid duration: overflow"),
+        };
+        secs = match secs.checked_add(add) {
+            Some(secs) => secs,
+            None => panic!("invalid duration: overflow"),
+        };
+        // Sub-second units are below a second each, so `n` of them carry
+        // over into whole seconds before they can overflow.
+        let sub = unit.subsec_nanos() as u64;
+        if sub != 0 {
+            secs = match secs.checked_add(n / NANOS_PER_SEC * sub) {
+                Some(secs) => secs,
+                None => panic!("invalid duration: overflow"),
+            };
+            nanos += n % NANOS_PER_SEC * sub;
+            secs = match secs.checked_add(nanos / NANOS_PER_SEC) {
+                Some(secs) => secs,
+                None => panic!("invalid duration: overflow"),
+            };
+            nanos %= NANOS_PER_SEC;
+        }
+        empty = false;
+    }
+    if empty {
+        panic!("invalid duration: value is empty");
+    }
+    Duration::new(secs, nanos as u32)
+}
+
+const fn skip_spaces(b: &[u8], mut i: usize) -> usize {
+    while i < b.len() && b[i] == b' ' {
+        i += 1;
+    }
+    i
+}
+
+const fn unit_length(b: &[u8], start: usize, end: usize) -> Option<Duration> {
+    let mut u = 0;
+    while u < DURATION_UNITS.len() {
+        let suffixes = DURATION_UNITS[u].suffixes;
+        let mut k = 0;
+        while k < suffixes.len() {
+            if word_eq(b, start, end, suffixes[k].as_bytes()) {
+                return Some(DURATION_UNITS[u].length);
+            }
+            k += 1;
+        }
+        u += 1;
+    }
+    None
+}
+
+const fn word_eq(b: &[u8], start: usize, end: usize, word: &[u8]) -> bool {
+    if end - start != word.len() {
+        return false;
+    }
+    let mut i = 0;
+    while i < word.len() {
+        if b[start + i] != word[i] {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+/// Parses a humantime duration at compile time.
+///
+/// Expands to a `core::time::Duration` constant, so a typo in the string
+/// fails the build instead of surfacing at runtime.
+///
+/// # Example
+///
+/// ```
+/// use core::time::Duration;
+///
+/// const TIMEOUT: Duration = humantime_serde::duration!("15 min");
+/// assert_eq!(TIMEOUT, Duration::from_secs(900));
+/// ```
+///
+/// ```compile_fail
+/// const TIMEOUT: core::time::Duration = humantime_serde::duration!("15 mni");
+/// ```
+#[macro_export]
+macro_rules! duration {
+    ($s:literal) => {{
+        const DURATION: ::core::time::Duration = $crate::__private::parse_duration($s);
+        DURATION
+    }};
+}
+
+/// Generates the zero-argument functions used by `#[serde(default = "...")]`
+/// from humantime strings.
+///
+/// `name = "30s"` generates a function returning a `Duration`, parsed at
+/// compile time like [`duration!`](crate::duration). `name: Type = "..."`
+/// generates one returning any other supported type, such as a
+/// `DateTime<Utc>`, parsed when called and panicking if invalid.
+///
+/// # Example
+///
+/// ```
+/// use core::time::Duration;
+/// use chrono::{DateTime, Utc};
+/// use serde::{Serialize, Deserialize};
+///
+/// humantime_serde::default_fn! {
+///     thirty_secs = "30s";
+///     epoch: DateTime<Utc> = "1970-01-01T00:00:00Z";
+/// }
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct Foo {
+///     #[serde(with = "humantime_serde", default = "thirty_secs")]
+///     timeout: Duration,
+///     #[serde(with = "humantime_serde", default = "epoch")]
+///     since: DateTime<Utc>,
+/// }
+///
+/// let foo: Foo = serde_json::from_str("{}").unwrap();
+/// assert_eq!(foo.timeout, Duration::from_secs(30));
+/// assert_eq!(foo.since.timestamp(), 0);
+/// ```
+#[macro_export]
+macro_rules! default_fn {
+    () => {};
+    ($(#[$attr:meta])* $vis:vis $name:ident = $s:literal $(; $($rest:tt)*)?) => {
+        $(#[$attr])*
+        $vis fn $name() -> ::core::time::Duration {
+            $crate::duration!($s)
+        }
+        $($crate::default_fn!($($rest)*);)?
+    };
+    ($(#[$attr:meta])* $vis:vis $name:ident : $ty:ty = $s:literal $(; $($rest:tt)*)?) => {
+        $(#[$attr])*
+        $vis fn $name() -> $ty {
+            match <$ty as $crate::format::HumantimeFormat>::parse($s) {
+                ::core::result::Result::Ok(v) => v,
+                ::core::result::Result::Err(e) => {
+                    ::core::panic!("invalid default {:?}: {}", $s, e)
+                }
+            }
+        }
+        $($crate::default_fn!($($rest)*);)?
+    };
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::format::HumantimeFormat;
+
+    #[test]
+    fn matches_runtime() {
+        for s in &["15s", "1h 30m", "2 days", "500ms", "1year 6months", "1s 1ns", "90 min"] {
+            assert_eq!(parse_duration(s), Duration::parse(s).unwrap(), "{}", s);
+        }
+    }
+
+    #[test]
+    fn in_const() {
+        const D: Duration = crate::duration!("1m 30s 250ms");
+        assert_eq!(D, Duration::from_millis(90_250));
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn default_fn() {
+        use chrono::{DateTime, FixedOffset};
+
+        crate::default_fn!(one_minute = "1m"; offset: DateTime<FixedOffset> = "2018-05-11T20:28:30+02:00");
+
+        assert_eq!(one_minute(), Duration::from_secs(60));
+        assert_eq!(offset().offset().local_minus_utc(), 7200);
+    }
+
+    #[test]
+    fn carries_nanos() {
+        assert_eq!(parse_duration("1500000000ns"), Duration::from_millis(1500));
+        assert_eq!(parse_duration("999ms 999ms"), Duration::from_millis(1998));
+    }
+
+    #[test]
+    #[should_panic(expected = "unknown unit")]
+    fn unknown_unit() {
+        parse_duration("15 mni");
+    }
+
+    #[test]
+    #[should_panic(expected = "empty")]
+    fn empty() {
+        parse_duration(" ");
+    }
+}
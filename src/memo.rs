@@ -0,0 +1,205 @@
+//! Deserializes a `Vec` of durations or timestamps, skipping the parse of
+//! strings repeated among the last few elements.
+//!
+//! Large arrays often repeat the same value, as in bucketed metrics where
+//! every sample of a bucket carries its timestamp. [`Memo`] remembers the
+//! last `N` distinct strings and their values, and [`deserialize`] uses one
+//! remembering eight. Values are cloned out of the cache, so this pays off
+//! for types whose parse costs more than a comparison and a clone, such as
+//! timestamps.
+//!
+//! # Example
+//!
+//! ```
+//! use chrono::{DateTime, Utc};
+//! use serde::Deserialize;
+//!
+//! #[derive(Deserialize)]
+//! struct Samples {
+//!     #[serde(deserialize_with = "humantime_serde::memo::deserialize")]
+//!     times: Vec<DateTime<Utc>>,
+//! }
+//!
+//! let json = r#"{"times": ["2018-05-11T18:28:30Z", "2018-05-11T18:28:30Z"]}"#;
+//! let samples: Samples = serde_json::from_str(json).unwrap();
+//! assert_eq!(samples.times[0], samples.times[1]);
+//! ```
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+
+use serde::de::{self, DeserializeSeed, Deserializer, SeqAccess, Visitor};
+
+use super::format::HumantimeFormat;
+
+/// A cache of the last `N` distinct strings parsed and their values.
+///
+/// Once full, the oldest entry is replaced, reusing its `String`.
+#[derive(Clone, Debug)]
+pub struct Memo<T, const N: usize> {
+    entries: Vec<(String, T)>,
+    next: usize,
+}
+
+impl<T, const N: usize> Memo<T, N>
+where
+    T: HumantimeFormat + Clone,
+{
+    /// An empty cache.
+    pub fn new() -> Memo<T, N> {
+        Memo {
+            entries: Vec::with_capacity(N),
+            next: 0,
+        }
+    }
+
+    /// Parses `s`, or returns the value it was parsed to recently.
+    pub fn parse(&mut self, s: &str) -> Result<T, T::Err> {
+        if let Some((_, v)) = self.entries.iter().find(|(k, _)| k == s) {
+            return Ok(v.clone());
+        }
+        let v = T::parse(s)?;
+        if N == 0 {
+            return Ok(v);
+        }
+        if self.entries.len() < N {
+            self.entries.push((String::from(s), v.clone()));
+        } else {
+            let (k, old) = &mut self.entries[self.next];
+            k.clear();
+            k.push_str(s);
+            *old = v.clone();
+            self.next = (self.next + 1) % N;
+        }
+        Ok(v)
+    }
+}
+
+impl<T, const N: usize> Default for Memo<T, N>
+where
+    T: HumantimeFormat + Clone,
+{
+    fn default() -> Memo<T, N> {
+        Memo::new()
+    }
+}
+
+impl<'de, T, const N: usize> DeserializeSeed<'de> for &mut Memo<T, N>
+where
+    T: HumantimeFormat + Clone,
+{
+    type Value = T;
+
+    fn deserialize<D>(self, d: D) -> Result<T, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct V<'m, T, const N: usize>(&'m mut Memo<T, N>);
+
+        impl<T, const N: usize> Visitor<'_> for V<'_, T, N>
+        where
+            T: HumantimeFormat + Clone,
+        {
+            type Value = T;
+
+            fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+                fmt.write_str(T::EXPECTING)
+            }
+
+            fn visit_str<E>(self, v: &str) -> Result<T, E>
+            where
+                E: de::Error,
+            {
+                crate::check_len(v, crate::DEFAULT_MAX_LEN, T::EXPECTING)?;
+                self.0.parse(v).map_err(|err| {
+                    let expected: &dyn de::Expected = &T::EXPECTING;
+                    E::invalid_value(de::Unexpected::Str(v), &crate::Because(expected, &err))
+                })
+            }
+        }
+
+        d.deserialize_str(V(self))
+    }
+}
+
+/// Deserializes a `Vec<Duration>` or `Vec<DateTime<Tz>>`, remembering the
+/// last eight distinct strings.
+pub fn deserialize<'a, T, D>(d: D) -> Result<Vec<T>, D::Error>
+where
+    T: HumantimeFormat + Clone,
+    D: Deserializer<'a>,
+{
+    deserialize_with(&mut Memo::<T, 8>::new(), d)
+}
+
+/// Deserializes a `Vec<Duration>` or `Vec<DateTime<Tz>>` through `memo`,
+/// which may be kept across calls.
+pub fn deserialize_with<'a, T, D, const N: usize>(
+    memo: &mut Memo<T, N>,
+    d: D,
+) -> Result<Vec<T>, D::Error>
+where
+    T: HumantimeFormat + Clone,
+    D: Deserializer<'a>,
+{
+    struct V<'m, T, const N: usize>(&'m mut Memo<T, N>);
+
+    impl<'de, T, const N: usize> Visitor<'de> for V<'_, T, N>
+    where
+        T: HumantimeFormat + Clone,
+    {
+        type Value = Vec<T>;
+
+        fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+            fmt.write_str("a sequence")
+        }
+
+        fn visit_seq<A>(self, mut seq: A) -> Result<Vec<T>, A::Error>
+        where
+            A: SeqAccess<'de>,
+        {
+            // Don't trust the hint with more than a page worth of elements.
+            let mut out = Vec::with_capacity(seq.size_hint().unwrap_or(0).min(4096));
+            while let Some(v) = seq.next_element_seed(&mut *self.0)? {
+                out.push(v);
+            }
+            Ok(out)
+        }
+    }
+
+    d.deserialize_seq(V(memo))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use core::time::Duration;
+
+    #[test]
+    fn memo() {
+        let mut memo = Memo::<Duration, 2>::new();
+        assert_eq!(memo.parse("1s"), Ok(Duration::from_secs(1)));
+        assert_eq!(memo.parse("2s"), Ok(Duration::from_secs(2)));
+        assert_eq!(memo.parse("1s"), Ok(Duration::from_secs(1)));
+        assert_eq!(memo.parse("3s"), Ok(Duration::from_secs(3)));
+        assert_eq!(memo.entries.len(), 2);
+        assert!(memo.entries.iter().all(|(k, _)| k != "1s"));
+        assert!(memo.parse("soon").is_err());
+
+        let mut none = Memo::<Duration, 0>::new();
+        assert_eq!(none.parse("1s"), Ok(Duration::from_secs(1)));
+        assert!(none.entries.is_empty());
+    }
+
+    #[test]
+    fn deserialize_vec() {
+        let mut de = serde_json::Deserializer::from_str(r#"["1s", "1s", "1m", "1s"]"#);
+        let v: Vec<Duration> = deserialize(&mut de).unwrap();
+        assert_eq!(v, [1, 1, 60, 1].map(Duration::from_secs));
+
+        let mut de = serde_json::Deserializer::from_str(r#"["1s", "soon"]"#);
+        let err = deserialize::<Duration, _>(&mut de).unwrap_err();
+        assert!(err.to_string().starts_with(r#"invalid value: string "soon", expected a duration"#));
+    }
+}
@@ -0,0 +1,134 @@
+//! Arithmetic on `Serde<Duration>`, mirroring the operators of `Duration`.
+
+use core::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Sub, SubAssign};
+use core::time::Duration;
+
+use super::Serde;
+
+impl Serde<Duration> {
+    /// Checked addition, returning `None` on overflow.
+    pub fn checked_add(self, rhs: Serde<Duration>) -> Option<Serde<Duration>> {
+        self.0.checked_add(rhs.0).map(Serde)
+    }
+
+    /// Checked subtraction, returning `None` if `rhs` is larger than `self`.
+    pub fn checked_sub(self, rhs: Serde<Duration>) -> Option<Serde<Duration>> {
+        self.0.checked_sub(rhs.0).map(Serde)
+    }
+
+    /// Checked multiplication, returning `None` on overflow.
+    pub fn checked_mul(self, rhs: u32) -> Option<Serde<Duration>> {
+        self.0.checked_mul(rhs).map(Serde)
+    }
+
+    /// Checked division, returning `None` if `rhs` is zero.
+    pub fn checked_div(self, rhs: u32) -> Option<Serde<Duration>> {
+        self.0.checked_div(rhs).map(Serde)
+    }
+
+    /// Saturating addition, returning `Duration::MAX` on overflow.
+    pub fn saturating_add(self, rhs: Serde<Duration>) -> Serde<Duration> {
+        Serde(self.0.saturating_add(rhs.0))
+    }
+
+    /// Saturating subtraction, returning zero if `rhs` is larger than `self`.
+    pub fn saturating_sub(self, rhs: Serde<Duration>) -> Serde<Duration> {
+        Serde(self.0.saturating_sub(rhs.0))
+    }
+
+    /// Saturating multiplication, returning `Duration::MAX` on overflow.
+    pub fn saturating_mul(self, rhs: u32) -> Serde<Duration> {
+        Serde(self.0.saturating_mul(rhs))
+    }
+}
+
+impl Add for Serde<Duration> {
+    type Output = Serde<Duration>;
+
+    fn add(self, rhs: Serde<Duration>) -> Serde<Duration> {
+        Serde(self.0 + rhs.0)
+    }
+}
+
+impl AddAssign for Serde<Duration> {
+    fn add_assign(&mut self, rhs: Serde<Duration>) {
+        self.0 += rhs.0;
+    }
+}
+
+impl Sub for Serde<Duration> {
+    type Output = Serde<Duration>;
+
+    fn sub(self, rhs: Serde<Duration>) -> Serde<Duration> {
+        Serde(self.0 - rhs.0)
+    }
+}
+
+impl SubAssign for Serde<Duration> {
+    fn sub_assign(&mut self, rhs: Serde<Duration>) {
+        self.0 -= rhs.0;
+    }
+}
+
+impl Mul<u32> for Serde<Duration> {
+    type Output = Serde<Duration>;
+
+    fn mul(self, rhs: u32) -> Serde<Duration> {
+        Serde(self.0 * rhs)
+    }
+}
+
+impl MulAssign<u32> for Serde<Duration> {
+    fn mul_assign(&mut self, rhs: u32) {
+        self.0 *= rhs;
+    }
+}
+
+impl Div<u32> for Serde<Duration> {
+    type Output = Serde<Duration>;
+
+    fn div(self, rhs: u32) -> Serde<Duration> {
+        Serde(self.0 / rhs)
+    }
+}
+
+impl DivAssign<u32> for Serde<Duration> {
+    fn div_assign(&mut self, rhs: u32) {
+        self.0 /= rhs;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn operators() {
+        let d = Serde::from(Duration::from_secs(10));
+        assert_eq!(d + d, Duration::from_secs(20));
+        assert_eq!(d - d, Duration::ZERO);
+        assert_eq!(d * 3, Duration::from_secs(30));
+        assert_eq!(d / 4, Duration::from_millis(2500));
+
+        let mut e = d;
+        e += d;
+        e *= 2;
+        e -= d;
+        e /= 3;
+        assert_eq!(e, d);
+    }
+
+    #[test]
+    fn checked_saturating() {
+        let max = Serde::from(Duration::MAX);
+        let one = Serde::from(Duration::from_secs(1));
+        assert_eq!(max.checked_add(one), None);
+        assert_eq!(one.checked_sub(max), None);
+        assert_eq!(max.checked_mul(2), None);
+        assert_eq!(one.checked_div(0), None);
+        assert_eq!(one.checked_div(2), Some(Serde::from(Duration::from_millis(500))));
+        assert_eq!(max.saturating_add(one), max);
+        assert_eq!(one.saturating_sub(max), Duration::ZERO);
+        assert_eq!(max.saturating_mul(2), max);
+    }
+}
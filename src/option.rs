@@ -19,6 +19,7 @@
 //! }
 //! ```
 
+use super::format::Ref;
 use super::Serde;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
@@ -28,11 +29,10 @@ use serde::{Deserialize, Deserializer, Serialize, Serializer};
 /// `deserialize_with` annotations.
 pub fn serialize<T, S>(d: &Option<T>, s: S) -> Result<S::Ok, S::Error>
 where
-    for<'a> Serde<&'a T>: Serialize,
+    for<'a> Ref<'a, Option<T>>: Serialize,
     S: Serializer,
 {
-    let nested: Option<Serde<&T>> = d.as_ref().map(Into::into);
-    nested.serialize(s)
+    Ref(d).serialize(s)
 }
 
 /// Deserialize an `Option<Duration>` or `Option<chrono::DateTime>`
@@ -47,3 +47,79 @@ where
     let got: Option<Serde<T>> = Deserialize::deserialize(d)?;
     Ok(got.map(Serde::into_inner))
 }
+
+/// Generates the `option` submodule of a `with` module, wrapping its
+/// `serialize`/`deserialize` functions for `Option` fields.
+///
+/// Takes the generic parameters of the wrapped functions, the type they
+/// handle, and the bounds on those parameters, e.g.
+/// `[T] T, [T: HumantimeFormat]`. Paths in the bounds must be absolute.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __option_module {
+    ([$($gen:ident),*] $ty:ty, [$($bounds:tt)*]) => {
+        /// The same format for `Option` fields, which also need
+        /// `#[serde(default)]` to be omitted from the input.
+        pub mod option {
+            struct Ser<'a, $($gen),*>(&'a $ty)
+            where
+                $($bounds)*;
+
+            impl<$($gen),*> $crate::re::serde::Serialize for Ser<'_, $($gen),*>
+            where
+                $($bounds)*
+            {
+                fn serialize<S>(&self, s: S) -> ::core::result::Result<S::Ok, S::Error>
+                where
+                    S: $crate::re::serde::Serializer,
+                {
+                    super::serialize(self.0, s)
+                }
+            }
+
+            struct De<$($gen),*>($ty)
+            where
+                $($bounds)*;
+
+            impl<'de, $($gen),*> $crate::re::serde::Deserialize<'de> for De<$($gen),*>
+            where
+                $($bounds)*
+            {
+                fn deserialize<D>(d: D) -> ::core::result::Result<Self, D::Error>
+                where
+                    D: $crate::re::serde::Deserializer<'de>,
+                {
+                    super::deserialize(d).map(De)
+                }
+            }
+
+            /// Serializes an `Option` in the format of the parent module.
+            pub fn serialize<$($gen,)* S>(
+                d: &::core::option::Option<$ty>,
+                s: S,
+            ) -> ::core::result::Result<S::Ok, S::Error>
+            where
+                S: $crate::re::serde::Serializer,
+                $($bounds)*
+            {
+                match d {
+                    ::core::option::Option::Some(v) => s.serialize_some(&Ser(v)),
+                    ::core::option::Option::None => s.serialize_none(),
+                }
+            }
+
+            /// Deserializes an `Option` in the format of the parent module.
+            pub fn deserialize<'a, $($gen,)* D>(
+                d: D,
+            ) -> ::core::result::Result<::core::option::Option<$ty>, D::Error>
+            where
+                D: $crate::re::serde::Deserializer<'a>,
+                $($bounds)*
+            {
+                let got: ::core::option::Option<De<$($gen),*>> =
+                    $crate::re::serde::Deserialize::deserialize(d)?;
+                ::core::result::Result::Ok(got.map(|De(v)| v))
+            }
+        }
+    };
+}
@@ -0,0 +1,154 @@
+//! Convenience module to allow serialization via `humantime_serde` for
+//! `Option<Vec>`
+//!
+//! # Example
+//!
+//! ```
+//! use serde::{Serialize, Deserialize};
+//! use core::time::Duration;
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Foo {
+//!     #[serde(default)]
+//!     #[serde(with = "humantime_serde::option_vec")]
+//!     retries: Option<Vec<Duration>>,
+//! }
+//! ```
+
+use alloc::vec::Vec;
+use core::fmt;
+
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+use super::format::HumantimeFormat;
+use super::Serde;
+
+struct Seq<'a, T>(&'a [T]);
+
+impl<T> Serialize for Seq<'_, T>
+where
+    T: HumantimeFormat,
+{
+    fn serialize<S>(&self, s: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        super::vec::serialize(self.0, s)
+    }
+}
+
+/// Serializes an `Option<Vec<Duration>>` or `Option<Vec<chrono::DateTime>>`
+///
+/// This function can be used with `serde_derive`'s `with` and
+/// `serialize_with` annotations.
+pub fn serialize<T, S>(d: &Option<Vec<T>>, s: S) -> Result<S::Ok, S::Error>
+where
+    T: HumantimeFormat,
+    S: Serializer,
+{
+    match d {
+        Some(v) => s.serialize_some(&Seq(v)),
+        None => s.serialize_none(),
+    }
+}
+
+/// Deserializes an `Option<Vec<Duration>>` or
+/// `Option<Vec<chrono::DateTime>>`
+///
+/// This function can be used with `serde_derive`'s `with` and
+/// `deserialize_with` annotations.
+pub fn deserialize<'a, T, D>(d: D) -> Result<Option<Vec<T>>, D::Error>
+where
+    T: HumantimeFormat,
+    D: Deserializer<'a>,
+{
+    let got: Option<Vec<Serde<T>>> = Deserialize::deserialize(d)?;
+    Ok(got.map(|v| v.into_iter().map(Serde::into_inner).collect()))
+}
+
+/// Deserializes an `Option<Vec<Duration>>` or
+/// `Option<Vec<chrono::DateTime>>` into `place`, reusing the allocation of
+/// the `Vec` it holds, if any.
+///
+/// On error, `place` holds the elements read before it.
+pub fn deserialize_in_place<'a, T, D>(d: D, place: &mut Option<Vec<T>>) -> Result<(), D::Error>
+where
+    T: HumantimeFormat,
+    D: Deserializer<'a>,
+{
+    struct V<'p, T>(&'p mut Option<Vec<T>>);
+
+    impl<'de, T> de::Visitor<'de> for V<'_, T>
+    where
+        T: HumantimeFormat,
+    {
+        type Value = ();
+
+        fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+            fmt.write_str("a sequence or null")
+        }
+
+        fn visit_none<E>(self) -> Result<(), E>
+        where
+            E: de::Error,
+        {
+            *self.0 = None;
+            Ok(())
+        }
+
+        fn visit_unit<E>(self) -> Result<(), E>
+        where
+            E: de::Error,
+        {
+            self.visit_none()
+        }
+
+        fn visit_some<D>(self, d: D) -> Result<(), D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            super::vec::deserialize_in_place(d, self.0.get_or_insert_with(Vec::new))
+        }
+    }
+
+    d.deserialize_option(V(place))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloc::vec;
+    use core::time::Duration;
+
+    #[derive(Serialize, Deserialize)]
+    struct Foo {
+        #[serde(with = "super", default)]
+        retries: Option<Vec<Duration>>,
+    }
+
+    #[test]
+    fn with() {
+        let foo = serde_json::from_str::<Foo>(r#"{"retries": ["1s", "90s"]}"#).unwrap();
+        assert_eq!(foo.retries, Some(vec![Duration::from_secs(1), Duration::from_secs(90)]));
+        let reverse = serde_json::to_string(&foo).unwrap();
+        assert_eq!(reverse, r#"{"retries":["1s","1m 30s"]}"#);
+
+        let foo = serde_json::from_str::<Foo>("{}").unwrap();
+        assert_eq!(foo.retries, None);
+        assert_eq!(serde_json::to_string(&foo).unwrap(), r#"{"retries":null}"#);
+    }
+
+    #[test]
+    fn in_place() {
+        let mut place = Some(Vec::with_capacity(4));
+        let ptr = place.as_ref().unwrap().as_ptr();
+        let mut de = serde_json::Deserializer::from_str(r#"["1s"]"#);
+        deserialize_in_place::<Duration, _>(&mut de, &mut place).unwrap();
+        assert_eq!(place.as_deref(), Some(&[Duration::from_secs(1)][..]));
+        assert_eq!(place.as_ref().unwrap().as_ptr(), ptr);
+
+        let mut de = serde_json::Deserializer::from_str("null");
+        deserialize_in_place::<Duration, _>(&mut de, &mut place).unwrap();
+        assert_eq!(place, None);
+    }
+}
@@ -0,0 +1,219 @@
+use core::convert::TryFrom;
+use core::fmt;
+use core::str::FromStr;
+use core::time::Duration;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use super::format::{HumantimeFormat, Ref};
+use super::grammar::DURATION_UNITS;
+use super::Serde;
+
+/// An amount of one unit, written `"6 weeks"` or `"6w"`, that keeps the
+/// unit it was written in.
+///
+/// Where a `Duration` read from `"6 weeks"` is written back as `"42days"`,
+/// a `Period` is written back as `"6 weeks"`, so values a user picked in a
+/// form come back to them the way they entered them. The units are those
+/// of humantime, including months and years of fixed length.
+///
+/// # Example
+///
+/// ```
+/// use core::time::Duration;
+/// use humantime_serde::{Period, Unit};
+///
+/// let retention: Period = "6w".parse().unwrap();
+/// assert_eq!(retention, Period::new(6, Unit::Weeks));
+/// assert_eq!(retention.to_duration(), Some(Duration::from_secs(42 * 86_400)));
+/// assert_eq!(retention.to_string(), "6 weeks");
+/// ```
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Period {
+    /// The number of units.
+    pub amount: u64,
+    /// The unit.
+    pub unit: Unit,
+}
+
+/// The unit of a [`Period`].
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq, Ord, PartialOrd)]
+pub enum Unit {
+    /// Nanoseconds.
+    Nanoseconds,
+    /// Microseconds.
+    Microseconds,
+    /// Milliseconds.
+    Milliseconds,
+    /// Seconds.
+    Seconds,
+    /// Minutes.
+    Minutes,
+    /// Hours.
+    Hours,
+    /// Days of 24 hours.
+    Days,
+    /// Weeks of 7 days.
+    Weeks,
+    /// Months of 30.44 days, as in humantime.
+    Months,
+    /// Years of 365.25 days, as in humantime.
+    Years,
+}
+
+/// The units in the order of `DURATION_UNITS`.
+const UNITS: [Unit; 10] = [
+    Unit::Nanoseconds,
+    Unit::Microseconds,
+    Unit::Milliseconds,
+    Unit::Seconds,
+    Unit::Minutes,
+    Unit::Hours,
+    Unit::Days,
+    Unit::Weeks,
+    Unit::Months,
+    Unit::Years,
+];
+
+impl Unit {
+    /// The length of one unit.
+    pub fn length(self) -> Duration {
+        DURATION_UNITS[self as usize].length
+    }
+
+    /// The plural name of the unit, e.g. `"weeks"`.
+    pub fn name(self) -> &'static str {
+        DURATION_UNITS[self as usize].name
+    }
+
+    /// The unit spelled `s`, in any of humantime's spellings.
+    pub fn from_suffix(s: &str) -> Option<Unit> {
+        DURATION_UNITS
+            .iter()
+            .position(|unit| unit.suffixes.contains(&s))
+            .map(|i| UNITS[i])
+    }
+}
+
+impl Period {
+    /// Creates a period of `amount` units.
+    pub const fn new(amount: u64, unit: Unit) -> Period {
+        Period { amount, unit }
+    }
+
+    /// The length of the period, or `None` if it overflows a `Duration`.
+    pub fn to_duration(&self) -> Option<Duration> {
+        let nanos = self.unit.length().as_nanos().checked_mul(self.amount.into())?;
+        let secs = u64::try_from(nanos / 1_000_000_000).ok()?;
+        Some(Duration::new(secs, (nanos % 1_000_000_000) as u32))
+    }
+}
+
+/// The error returned when parsing a [`Period`] fails.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct InvalidPeriod;
+
+impl fmt::Display for InvalidPeriod {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("invalid period, expected e.g. 6 weeks")
+    }
+}
+
+impl core::error::Error for InvalidPeriod {}
+
+impl HumantimeFormat for Period {
+    const EXPECTING: &'static str = "a period";
+
+    type Err = InvalidPeriod;
+
+    fn parse(s: &str) -> Result<Period, InvalidPeriod> {
+        let s = s.trim();
+        let end = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+        let amount = s[..end].parse().map_err(|_| InvalidPeriod)?;
+        let unit = Unit::from_suffix(s[end..].trim_start()).ok_or(InvalidPeriod)?;
+        Ok(Period::new(amount, unit))
+    }
+
+    fn format(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = self.unit.name();
+        match self.amount {
+            1 => write!(f, "1 {}", &name[..name.len() - 1]),
+            n => write!(f, "{} {}", n, name),
+        }
+    }
+}
+
+impl fmt::Display for Period {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.format(f)
+    }
+}
+
+impl FromStr for Period {
+    type Err = InvalidPeriod;
+
+    fn from_str(s: &str) -> Result<Period, InvalidPeriod> {
+        Period::parse(s)
+    }
+}
+
+impl Serialize for Period {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        Ref(self).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Period {
+    fn deserialize<D>(d: D) -> Result<Period, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Serde::deserialize(d).map(Serde::into_inner)
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod test {
+    use super::*;
+    use alloc::string::ToString;
+
+    #[test]
+    fn parse() {
+        assert_eq!("6 weeks".parse(), Ok(Period::new(6, Unit::Weeks)));
+        assert_eq!(" 90s ".parse(), Ok(Period::new(90, Unit::Seconds)));
+        assert_eq!("1M".parse(), Ok(Period::new(1, Unit::Months)));
+        assert_eq!("1m".parse(), Ok(Period::new(1, Unit::Minutes)));
+        assert_eq!("0 usec".parse(), Ok(Period::new(0, Unit::Microseconds)));
+        assert_eq!("6".parse::<Period>(), Err(InvalidPeriod));
+        assert_eq!("weeks".parse::<Period>(), Err(InvalidPeriod));
+        assert_eq!("1h 30m".parse::<Period>(), Err(InvalidPeriod));
+        assert_eq!("-1 day".parse::<Period>(), Err(InvalidPeriod));
+    }
+
+    #[test]
+    fn units() {
+        for (i, &unit) in UNITS.iter().enumerate() {
+            assert_eq!(unit as usize, i);
+            assert_eq!(Period::new(1, unit).to_duration(), Some(unit.length()));
+        }
+        assert_eq!(Period::new(1, Unit::Years).to_string(), "1 year");
+        assert_eq!(Period::new(2, Unit::Years).to_duration(), Some(Duration::from_secs(63_115_200)));
+        assert_eq!(Period::new(u64::MAX, Unit::Nanoseconds).to_duration(), Some(Duration::from_nanos(u64::MAX)));
+        assert_eq!(Period::new(u64::MAX, Unit::Years).to_duration(), None);
+    }
+
+    #[test]
+    fn serde() {
+        for s in &["\"6 weeks\"", "\"1 month\"", "\"0 nanoseconds\""] {
+            let period: Period = serde_json::from_str(s).unwrap();
+            assert_eq!(serde_json::to_string(&period).unwrap(), *s);
+        }
+        let period: Period = serde_json::from_str("\"2d\"").unwrap();
+        assert_eq!(serde_json::to_string(&period).unwrap(), "\"2 days\"");
+        let err = serde_json::from_str::<Period>("\"soon\"").unwrap_err();
+        assert!(err.to_string().contains("expected a period"));
+    }
+}
@@ -0,0 +1,5 @@
+//! Support code for the declarative macros. Not public API.
+
+pub use crate::literal::parse_duration;
+#[cfg(feature = "alloc")]
+pub use alloc::vec::Vec;
@@ -0,0 +1,227 @@
+//! A `with` module for durations in Prometheus' syntax, as in its
+//! configuration files and query language.
+//!
+//! Durations are whole numbers with the units `y`, `w`, `d`, `h`, `m`, `s`
+//! and `ms`, from largest to smallest, each at most once and without
+//! spaces: `1y2w3d4h5m6s7ms`. A year is 365 days and there are no months.
+//! A lone `0` is accepted too.
+//!
+//! Durations are written as Prometheus writes them, with the largest units
+//! possible: `90s` becomes `1m30s` and `168h` becomes `1w`. Prometheus
+//! counts milliseconds in a signed 64-bit count of nanoseconds, so
+//! durations with a fraction of a millisecond, or beyond 292 years, fail
+//! to serialize.
+//!
+//! # Example
+//!
+//! ```
+//! use core::time::Duration;
+//! use serde::{Serialize, Deserialize};
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct ScrapeConfig {
+//!     #[serde(with = "humantime_serde::prometheus")]
+//!     scrape_interval: Duration,
+//! }
+//!
+//! let json = r#"{"scrape_interval": "90s"}"#;
+//! let config: ScrapeConfig = serde_json::from_str(json).unwrap();
+//! assert_eq!(config.scrape_interval, Duration::from_secs(90));
+//! let json = serde_json::to_string(&config).unwrap();
+//! assert_eq!(json, r#"{"scrape_interval":"1m30s"}"#);
+//! ```
+
+use core::fmt;
+use core::time::Duration;
+
+use serde::{de, ser, Deserializer, Serializer};
+
+use crate::Error;
+
+/// The longest duration Prometheus represents, in milliseconds.
+const MAX_MILLIS: u128 = i64::MAX as u128 / 1_000_000;
+
+/// The units from largest to smallest, with their length in milliseconds.
+const UNITS: [(&str, u128); 7] = [
+    ("y", 365 * 86_400_000),
+    ("w", 7 * 86_400_000),
+    ("d", 86_400_000),
+    ("h", 3_600_000),
+    ("m", 60_000),
+    ("s", 1_000),
+    ("ms", 1),
+];
+
+/// Parses a duration in Prometheus' syntax.
+pub fn parse(s: &str) -> Result<Duration, Error> {
+    if s.is_empty() {
+        return Err(Error::Empty);
+    }
+    if s == "0" {
+        return Ok(Duration::ZERO);
+    }
+    let mut rest = s;
+    // The index of the smallest unit so far; later units must be smaller.
+    let mut next_unit = 0;
+    let mut total: u128 = 0;
+    while !rest.is_empty() {
+        let pos = s.len() - rest.len();
+        let len = rest.find(|c: char| !c.is_ascii_digit()).unwrap_or(rest.len());
+        if len == 0 {
+            return Err(Error::NumberExpected(pos));
+        }
+        let (number, after) = rest.split_at(len);
+        let unit_len = after.find(|c: char| c.is_ascii_digit()).unwrap_or(after.len());
+        let start = pos + len;
+        let unit = UNITS
+            .iter()
+            .position(|&(name, _)| name == &after[..unit_len])
+            .ok_or(Error::UnknownUnit { start, end: start + unit_len, suggestion: None })?;
+        if unit < next_unit {
+            return Err(Error::Invalid("units from largest to smallest, each at most once"));
+        }
+        next_unit = unit + 1;
+        let mut value: u128 = 0;
+        for b in number.bytes() {
+            value = value * 10 + u128::from(b - b'0');
+            if value > MAX_MILLIS {
+                return Err(Error::NumberOverflow);
+            }
+        }
+        total = value
+            .checked_mul(UNITS[unit].1)
+            .map(|v| total + v)
+            .filter(|&v| v <= MAX_MILLIS)
+            .ok_or(Error::NumberOverflow)?;
+        rest = &after[unit_len..];
+    }
+    Ok(Duration::from_millis(total as u64))
+}
+
+/// Formats a duration exactly as Prometheus does, leaving out any fraction
+/// of a millisecond.
+pub fn format(d: Duration) -> impl fmt::Display {
+    PrometheusDuration(d)
+}
+
+struct PrometheusDuration(Duration);
+
+impl fmt::Display for PrometheusDuration {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut millis = self.0.as_millis();
+        if millis == 0 {
+            return f.write_str("0s");
+        }
+        for &(name, length) in &UNITS {
+            if millis >= length {
+                write!(f, "{}{}", millis / length, name)?;
+                millis %= length;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Serializes a `Duration` in Prometheus' syntax.
+pub fn serialize<S>(d: &Duration, s: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    if d.subsec_nanos() % 1_000_000 != 0 {
+        return Err(ser::Error::custom(format_args!(
+            "{:?} is not a whole number of milliseconds",
+            d
+        )));
+    }
+    if d.as_millis() > MAX_MILLIS {
+        return Err(ser::Error::custom(format_args!(
+            "{:?} is longer than a Prometheus duration holds",
+            d
+        )));
+    }
+    s.collect_str(&format(*d))
+}
+
+/// Deserializes a `Duration` from Prometheus' syntax.
+pub fn deserialize<'a, D>(d: D) -> Result<Duration, D::Error>
+where
+    D: Deserializer<'a>,
+{
+    struct V;
+
+    impl de::Visitor<'_> for V {
+        type Value = Duration;
+
+        fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+            fmt.write_str("a Prometheus duration")
+        }
+
+        fn visit_str<E>(self, v: &str) -> Result<Duration, E>
+        where
+            E: de::Error,
+        {
+            crate::check_len(v, crate::DEFAULT_MAX_LEN, "a Prometheus duration")?;
+            parse(v).map_err(|err| {
+                E::invalid_value(de::Unexpected::Str(v), &crate::Because(&self, &err))
+            })
+        }
+    }
+
+    d.deserialize_str(V)
+}
+
+crate::__option_module!([] ::core::time::Duration, []);
+
+#[cfg(all(test, feature = "alloc"))]
+mod test {
+    use super::*;
+    use alloc::string::ToString;
+
+    #[test]
+    fn parsing() {
+        let ms = Duration::from_millis;
+        let day = 86_400_000;
+        assert_eq!(parse("1y2w3d4h5m6s7ms"), Ok(ms(382 * day + 14_706_007)));
+        assert_eq!(parse("90s"), Ok(ms(90_000)));
+        assert_eq!(parse("0"), Ok(Duration::ZERO));
+        assert_eq!(parse("0s"), Ok(Duration::ZERO));
+        assert_eq!(parse("292y"), Ok(ms(292 * 365 * day)));
+        assert_eq!(parse("293y"), Err(Error::NumberOverflow));
+        assert_eq!(parse(""), Err(Error::Empty));
+        assert_eq!(parse("s"), Err(Error::NumberExpected(0)));
+        assert_eq!(parse("1h1"), Err(Error::UnknownUnit { start: 3, end: 3, suggestion: None }));
+        assert_eq!(parse("1M"), Err(Error::UnknownUnit { start: 1, end: 2, suggestion: None }));
+        assert_eq!(parse("1h 5m"), Err(Error::UnknownUnit { start: 1, end: 3, suggestion: None }));
+        assert!(parse("5m1h").is_err());
+        assert!(parse("1s1s").is_err());
+        assert!(parse("1.5h").is_err());
+        assert!(parse("-1s").is_err());
+    }
+
+    #[test]
+    fn formatting() {
+        let format = |millis| format(Duration::from_millis(millis)).to_string();
+        assert_eq!(format(0), "0s");
+        assert_eq!(format(7), "7ms");
+        assert_eq!(format(90_000), "1m30s");
+        assert_eq!(format(604_800_000), "1w");
+        assert_eq!(format(33_019_506_007), "1y2w3d4h5m6s7ms");
+        assert_eq!(super::format(Duration::from_nanos(999_999)).to_string(), "0s");
+    }
+
+    #[test]
+    fn serde() {
+        #[derive(serde::Serialize, serde::Deserialize)]
+        struct Rule {
+            #[serde(with = "super")]
+            interval: Duration,
+        }
+
+        let rule: Rule = serde_json::from_str(r#"{"interval": "1d12h"}"#).unwrap();
+        assert_eq!(serde_json::to_string(&rule).unwrap(), r#"{"interval":"1d12h"}"#);
+        let err = serde_json::from_str::<Rule>(r#"{"interval": "1 day"}"#).unwrap_err();
+        assert!(err.to_string().contains("expected a Prometheus duration"));
+        let rule = Rule { interval: Duration::from_micros(1_500) };
+        assert!(serde_json::to_string(&rule).is_err());
+    }
+}
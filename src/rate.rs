@@ -0,0 +1,187 @@
+use core::fmt;
+use core::str::FromStr;
+use core::time::Duration;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use super::format::{HumantimeFormat, Ref, SliceWriter};
+use super::Serde;
+
+/// A number of events per period, written `"100/min"`, `"5 per second"` or
+/// `"1000/15m"`.
+///
+/// A period without a number means one of that unit. Rates are written back
+/// as `count/unit` when the period is exactly one unit, and as
+/// `count/duration` otherwise.
+///
+/// # Example
+///
+/// ```
+/// use core::time::Duration;
+/// use humantime_serde::Rate;
+///
+/// let rate: Rate = "5 per second".parse().unwrap();
+/// assert_eq!(rate, Rate::new(5, Duration::from_secs(1)));
+/// assert_eq!(rate.interval(), Some(Duration::from_millis(200)));
+/// assert_eq!(rate.to_string(), "5/s");
+/// ```
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Rate {
+    /// The number of events.
+    pub count: u64,
+    /// The period the events are spread over.
+    pub per: Duration,
+}
+
+/// Units written back without a number, in the syntax accepted by humantime.
+const UNITS: &[(Duration, &str)] = &[
+    (Duration::from_nanos(1), "ns"),
+    (Duration::from_micros(1), "us"),
+    (Duration::from_millis(1), "ms"),
+    (Duration::from_secs(1), "s"),
+    (Duration::from_secs(60), "min"),
+    (Duration::from_secs(3_600), "h"),
+    (Duration::from_secs(86_400), "day"),
+    (Duration::from_secs(604_800), "week"),
+];
+
+impl Rate {
+    /// Creates a rate of `count` events per `per`.
+    pub const fn new(count: u64, per: Duration) -> Rate {
+        Rate { count, per }
+    }
+
+    /// The time between two events when they are evenly spread, or `None`
+    /// if the count is zero.
+    pub fn interval(&self) -> Option<Duration> {
+        if self.count == 0 {
+            return None;
+        }
+        let nanos = self.per.as_nanos() / u128::from(self.count);
+        Some(Duration::new(
+            (nanos / 1_000_000_000) as u64,
+            (nanos % 1_000_000_000) as u32,
+        ))
+    }
+
+    /// The number of events per second.
+    pub fn per_second(&self) -> f64 {
+        self.count as f64 / self.per.as_secs_f64()
+    }
+}
+
+/// The error returned when parsing a [`Rate`] fails.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct InvalidRate;
+
+impl fmt::Display for InvalidRate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("invalid rate, expected e.g. 100/min")
+    }
+}
+
+impl core::error::Error for InvalidRate {}
+
+fn parse_period(s: &str) -> Result<Duration, InvalidRate> {
+    let per = if s.starts_with(|c: char| c.is_ascii_digit()) {
+        Duration::parse(s)
+    } else {
+        // A bare unit is one of that unit; humantime wants the number.
+        let mut buf = [0; 32];
+        let mut w = SliceWriter::new(&mut buf);
+        fmt::Write::write_fmt(&mut w, format_args!("1{}", s)).map_err(|_| InvalidRate)?;
+        Duration::parse(w.into_str().map_err(|_| InvalidRate)?)
+    };
+    match per {
+        Ok(per) if per != Duration::ZERO => Ok(per),
+        _ => Err(InvalidRate),
+    }
+}
+
+impl HumantimeFormat for Rate {
+    const EXPECTING: &'static str = "a rate";
+
+    type Err = InvalidRate;
+
+    fn parse(s: &str) -> Result<Rate, InvalidRate> {
+        let (count, per) = s
+            .split_once('/')
+            .or_else(|| s.split_once(" per "))
+            .ok_or(InvalidRate)?;
+        let count = count.trim().parse().map_err(|_| InvalidRate)?;
+        Ok(Rate::new(count, parse_period(per.trim())?))
+    }
+
+    fn format(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match UNITS.iter().find(|(d, _)| *d == self.per) {
+            Some((_, unit)) => write!(f, "{}/{}", self.count, unit),
+            None => write!(f, "{}/{}", self.count, Ref(&self.per)),
+        }
+    }
+}
+
+impl fmt::Display for Rate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.format(f)
+    }
+}
+
+impl FromStr for Rate {
+    type Err = InvalidRate;
+
+    fn from_str(s: &str) -> Result<Rate, InvalidRate> {
+        Rate::parse(s)
+    }
+}
+
+impl Serialize for Rate {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        Ref(self).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Rate {
+    fn deserialize<D>(d: D) -> Result<Rate, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Serde::deserialize(d).map(Serde::into_inner)
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod test {
+    use super::*;
+    use alloc::string::ToString;
+
+    #[test]
+    fn parse() {
+        assert_eq!("100/min".parse(), Ok(Rate::new(100, Duration::from_secs(60))));
+        assert_eq!("100 / 5m".parse(), Ok(Rate::new(100, Duration::from_secs(300))));
+        assert_eq!("3 per hour".parse(), Ok(Rate::new(3, Duration::from_secs(3600))));
+        assert_eq!("100/0s".parse::<Rate>(), Err(InvalidRate));
+        assert_eq!("100".parse::<Rate>(), Err(InvalidRate));
+        assert_eq!("x/s".parse::<Rate>(), Err(InvalidRate));
+    }
+
+    #[test]
+    fn serde() {
+        for s in &["\"100/min\"", "\"1000/15m\"", "\"0/day\""] {
+            let rate: Rate = serde_json::from_str(s).unwrap();
+            assert_eq!(serde_json::to_string(&rate).unwrap(), *s);
+        }
+        let err = serde_json::from_str::<Rate>("\"fast\"").unwrap_err();
+        assert!(err.to_string().contains("expected a rate"));
+    }
+
+    #[test]
+    fn conversions() {
+        let rate = Rate::new(3, Duration::from_secs(1));
+        assert_eq!(rate.interval(), Some(Duration::new(0, 333_333_333)));
+        assert_eq!(Rate::new(120, Duration::from_secs(60)).per_second(), 2.0);
+        assert_eq!(Rate::new(0, Duration::from_secs(1)).interval(), None);
+    }
+}
@@ -0,0 +1,161 @@
+use alloc::string::{String, ToString};
+use core::fmt;
+use core::ops::Deref;
+use core::str::FromStr;
+use core::time::Duration;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use super::format::{HumantimeFormat, Ref};
+use super::Serde;
+
+/// A `Duration` that remembers the string it was parsed from and
+/// serializes it back verbatim.
+///
+/// Tools rewriting configuration files can round-trip `"90s"` as written
+/// instead of normalizing it to `"1m 30s"`.
+///
+/// # Example
+///
+/// ```
+/// use core::time::Duration;
+/// use humantime_serde::RawDuration;
+///
+/// let d: RawDuration = serde_json::from_str("\"90s\"").unwrap();
+/// assert_eq!(*d, Duration::from_secs(90));
+/// assert_eq!(serde_json::to_string(&d).unwrap(), "\"90s\"");
+/// assert_eq!(RawDuration::from(Duration::from_secs(90)).as_str(), "1m 30s");
+/// ```
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct RawDuration {
+    duration: Duration,
+    source: String,
+}
+
+impl RawDuration {
+    /// The parsed duration.
+    pub fn duration(&self) -> Duration {
+        self.duration
+    }
+
+    /// The string the duration was parsed from.
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+
+    /// Consumes the `RawDuration`, returning the inner `Duration`.
+    pub fn into_inner(self) -> Duration {
+        self.duration
+    }
+}
+
+impl HumantimeFormat for RawDuration {
+    const EXPECTING: &'static str = "a duration";
+
+    type Err = humantime::DurationError;
+
+    fn parse(s: &str) -> Result<RawDuration, humantime::DurationError> {
+        Ok(RawDuration {
+            duration: Duration::parse(s)?,
+            source: s.to_string(),
+        })
+    }
+
+    fn format(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.source)
+    }
+
+    fn is_overflow(err: &humantime::DurationError) -> bool {
+        Duration::is_overflow(err)
+    }
+
+    fn suggestion(s: &str, err: &humantime::DurationError) -> Option<&'static str> {
+        Duration::suggestion(s, err)
+    }
+}
+
+impl fmt::Display for RawDuration {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.format(f)
+    }
+}
+
+impl FromStr for RawDuration {
+    type Err = humantime::DurationError;
+
+    fn from_str(s: &str) -> Result<RawDuration, humantime::DurationError> {
+        RawDuration::parse(s)
+    }
+}
+
+impl Deref for RawDuration {
+    type Target = Duration;
+
+    fn deref(&self) -> &Duration {
+        &self.duration
+    }
+}
+
+impl From<Duration> for RawDuration {
+    fn from(duration: Duration) -> RawDuration {
+        RawDuration {
+            duration,
+            source: Ref(&duration).to_string(),
+        }
+    }
+}
+
+impl From<RawDuration> for Duration {
+    fn from(d: RawDuration) -> Duration {
+        d.duration
+    }
+}
+
+impl Serialize for RawDuration {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        Ref(self).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for RawDuration {
+    fn deserialize<D>(d: D) -> Result<RawDuration, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Serde::deserialize(d).map(Serde::into_inner)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn verbatim() {
+        #[derive(Serialize, Deserialize)]
+        struct Foo {
+            timeout: RawDuration,
+            #[serde(with = "crate::option", default)]
+            idle: Option<RawDuration>,
+        }
+
+        let json = r#"{"timeout":"90 seconds","idle":"1h 0m"}"#;
+        let foo: Foo = serde_json::from_str(json).unwrap();
+        assert_eq!(foo.timeout.duration(), Duration::from_secs(90));
+        assert_eq!(foo.idle.as_ref().unwrap().as_str(), "1h 0m");
+        assert_eq!(serde_json::to_string(&foo).unwrap(), json);
+
+        assert!(serde_json::from_str::<RawDuration>("\"90 parsecs\"").is_err());
+    }
+
+    #[test]
+    fn errors() {
+        let err = serde_json::from_str::<RawDuration>("\"2 secnds\"").unwrap_err();
+        assert!(err.to_string().contains("did you mean \"seconds\"?"));
+        let err = serde_json::from_str::<RawDuration>("\"99999999999999999999y\"").unwrap_err();
+        assert!(err.to_string().contains("is too large for a duration"));
+    }
+}
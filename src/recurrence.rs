@@ -0,0 +1,396 @@
+use core::convert::TryFrom;
+use core::fmt;
+use core::str::FromStr;
+use core::time::Duration;
+
+use chrono::{DateTime, Datelike, NaiveDateTime, TimeDelta, TimeZone, Weekday};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use super::format::{HumantimeFormat, Ref};
+use super::window::write_time;
+use super::{Every, Serde, TimeOfDay, TimeWindow, Unit, WeeklySchedule};
+
+const DAY_NAMES: [&str; 7] = ["monday", "tuesday", "wednesday", "thursday", "friday", "saturday", "sunday"];
+const ALL_DAYS: u8 = 0b111_1111;
+const WEEKDAYS: u8 = 0b001_1111;
+const WEEKENDS: u8 = 0b110_0000;
+
+/// A schedule written in simple English, such as `"every monday at 9am"`
+/// or `"every 15 minutes between 9am and 5pm"`.
+///
+/// Two forms are understood:
+///
+/// * `every <days> [at <time>]` runs once on each of the days, at midnight
+///   if no time is given.
+/// * `every <period> [between <time> and <time>] [on <days>]` runs every
+///   period, counting from the start of the window, or from midnight, on
+///   each of the days. The end of the window is excluded.
+///
+/// Days are `day`, `weekday`, `weekend` or day names such as `monday` or
+/// `mon`, separated by commas or `and`, in the singular or the plural.
+/// Times are anything [`TimeOfDay`] reads, and periods any duration, or a
+/// unit such as `hour` on its own. Words are read in any case. A schedule
+/// is written back in the same form with times as `HH:MM`.
+///
+/// # Example
+///
+/// ```
+/// use chrono::{TimeZone, Utc};
+/// use humantime_serde::Recurrence;
+///
+/// let standup: Recurrence = "every weekday at 9:30am".parse().unwrap();
+/// assert_eq!(standup.to_string(), "every weekday at 09:30");
+///
+/// // 2024-01-05 is a Friday.
+/// let t = Utc.with_ymd_and_hms(2024, 1, 5, 10, 0, 0).unwrap();
+/// assert_eq!(standup.next_after(&t), Some(Utc.with_ymd_and_hms(2024, 1, 8, 9, 30, 0).unwrap()));
+///
+/// let poll: Recurrence = "every 15 minutes between 9am and 5pm".parse().unwrap();
+/// assert_eq!(poll.next_after(&t), Some(Utc.with_ymd_and_hms(2024, 1, 5, 10, 15, 0).unwrap()));
+/// assert_eq!(poll.to_string(), "every 15m between 09:00 and 17:00");
+/// ```
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Recurrence {
+    days: u8,
+    rule: Rule,
+}
+
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+enum Rule {
+    At(TimeOfDay),
+    Every(Every, Option<TimeWindow>),
+}
+
+impl Recurrence {
+    /// Returns whether the schedule runs on `day`.
+    pub fn runs_on(&self, day: Weekday) -> bool {
+        self.days & (1 << day.num_days_from_monday()) != 0
+    }
+
+    /// The time of day of a schedule running once a day.
+    pub fn time(&self) -> Option<TimeOfDay> {
+        match self.rule {
+            Rule::At(time) => Some(time),
+            Rule::Every(..) => None,
+        }
+    }
+
+    /// The period of a schedule running every period.
+    pub fn every(&self) -> Option<Every> {
+        match self.rule {
+            Rule::At(_) => None,
+            Rule::Every(every, _) => Some(every),
+        }
+    }
+
+    /// The hours a schedule running every period runs in each day, or
+    /// `None` if it runs all day or once a day.
+    pub fn window(&self) -> Option<TimeWindow> {
+        match self.rule {
+            Rule::At(_) => None,
+            Rule::Every(_, window) => window,
+        }
+    }
+
+    /// The hours a schedule running every period runs in over the week, or
+    /// `None` if it runs once a day.
+    pub fn schedule(&self) -> Option<WeeklySchedule> {
+        let window = match self.rule {
+            Rule::At(_) => return None,
+            Rule::Every(_, window) => window.unwrap_or_else(|| TimeWindow::new(Default::default(), Default::default())),
+        };
+        let mut schedule = WeeklySchedule::new();
+        let mut day = Weekday::Mon;
+        for _ in 0..7 {
+            if self.runs_on(day) {
+                schedule.push(day, window);
+            }
+            day = day.succ();
+        }
+        Some(schedule)
+    }
+
+    /// The first time the schedule runs strictly after `t`, in `t`'s time
+    /// zone.
+    ///
+    /// Times skipped by a change of offset are left out, and times
+    /// occurring twice are taken at the earlier instant.
+    pub fn next_after<Tz>(&self, t: &DateTime<Tz>) -> Option<DateTime<Tz>>
+    where
+        Tz: TimeZone,
+    {
+        let local = t.naive_local();
+        // The day before, for windows crossing midnight, to a week after.
+        (-1..=7)
+            .filter_map(|days| local.date().checked_add_signed(TimeDelta::days(days)))
+            .filter(|date| self.runs_on(date.weekday()))
+            .filter_map(|date| match self.rule {
+                Rule::At(time) => Some(date.and_time(time.into_inner())).filter(|&at| at > local),
+                Rule::Every(every, window) => {
+                    let (start, len) = match window {
+                        Some(w) => {
+                            let len = w.end() - w.start();
+                            let len = if len <= TimeDelta::zero() { len + TimeDelta::days(1) } else { len };
+                            (date.and_time(w.start()), len)
+                        }
+                        None => (date.and_time(Default::default()), TimeDelta::days(1)),
+                    };
+                    next_in_window(start, len, every.period(), local)
+                }
+            })
+            .filter_map(|at| t.timezone().from_local_datetime(&at).earliest())
+            .filter(|at| at > t)
+            .min()
+    }
+}
+
+/// The first time after `t` falling a whole number of periods after
+/// `start` and before `start + len`.
+fn next_in_window(start: NaiveDateTime, len: TimeDelta, period: Duration, t: NaiveDateTime) -> Option<NaiveDateTime> {
+    if t < start {
+        return Some(start);
+    }
+    let elapsed = (t - start).num_nanoseconds()? as i128;
+    let period = period.as_nanos() as i128;
+    let offset = (elapsed / period + 1) * period;
+    let offset = TimeDelta::nanoseconds(i64::try_from(offset).ok()?);
+    Some(start + offset).filter(|_| offset < len)
+}
+
+/// The error returned when parsing a [`Recurrence`] fails.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct InvalidRecurrence;
+
+impl fmt::Display for InvalidRecurrence {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("invalid schedule, expected e.g. every monday at 9am")
+    }
+}
+
+impl core::error::Error for InvalidRecurrence {}
+
+/// Splits `s` around the first occurrence of `word` surrounded by spaces,
+/// in any case.
+fn split_word<'s>(s: &'s str, word: &str) -> Option<(&'s str, &'s str)> {
+    let bytes = s.as_bytes();
+    (1..s.len().saturating_sub(word.len())).find_map(|i| {
+        let end = i + word.len();
+        let found = bytes[i - 1] == b' '
+            && bytes[end] == b' '
+            && bytes[i..end].eq_ignore_ascii_case(word.as_bytes());
+        found.then(|| (s[..i].trim_end(), s[end..].trim_start()))
+    })
+}
+
+/// Strips `prefix` from `s`, in any case.
+fn strip_word<'s>(s: &'s str, prefix: &str) -> Option<&'s str> {
+    let head = s.get(..prefix.len())?;
+    head.eq_ignore_ascii_case(prefix).then(|| &s[prefix.len()..])
+}
+
+fn parse_day(s: &str) -> Option<u8> {
+    let one = |s: &str| -> Option<u8> {
+        if s.eq_ignore_ascii_case("day") {
+            Some(ALL_DAYS)
+        } else if s.eq_ignore_ascii_case("weekday") {
+            Some(WEEKDAYS)
+        } else if s.eq_ignore_ascii_case("weekend") {
+            Some(WEEKENDS)
+        } else {
+            let day = s.parse::<Weekday>().ok()?;
+            Some(1 << day.num_days_from_monday())
+        }
+    };
+    one(s).or_else(|| one(s.strip_suffix('s')?))
+}
+
+fn parse_days(s: &str) -> Result<u8, InvalidRecurrence> {
+    let mut days = 0;
+    for part in s.split(',') {
+        let mut part = part.trim();
+        part = strip_word(part, "and ").map_or(part, str::trim_start);
+        let mut rest = Some(part);
+        while let Some(part) = rest {
+            let (day, next) = split_word(part, "and").map_or((part, None), |(day, next)| (day, Some(next)));
+            days |= parse_day(day).ok_or(InvalidRecurrence)?;
+            rest = next;
+        }
+    }
+    Ok(days)
+}
+
+fn parse_time(s: &str) -> Result<TimeOfDay, InvalidRecurrence> {
+    TimeOfDay::parse(s).map_err(|_| InvalidRecurrence)
+}
+
+fn parse_period(s: &str) -> Result<Every, InvalidRecurrence> {
+    let period = match Unit::from_suffix(s) {
+        Some(unit) => unit.length(),
+        None => Duration::parse(s).map_err(|_| InvalidRecurrence)?,
+    };
+    Every::new(period).ok_or(InvalidRecurrence)
+}
+
+fn write_days(days: u8, plural: bool, f: &mut fmt::Formatter) -> fmt::Result {
+    let s = if plural { "s" } else { "" };
+    match days {
+        ALL_DAYS => return write!(f, "day{}", s),
+        WEEKDAYS => return write!(f, "weekday{}", s),
+        WEEKENDS => return write!(f, "weekend{}", s),
+        _ => {}
+    }
+    let count = days.count_ones();
+    let names = DAY_NAMES.iter().enumerate().filter(|&(i, _)| days & (1 << i) != 0);
+    for (n, (_, name)) in names.enumerate() {
+        match n as u32 {
+            0 => {}
+            n if n + 1 == count => f.write_str(" and ")?,
+            _ => f.write_str(", ")?,
+        }
+        write!(f, "{}{}", name, s)?;
+    }
+    Ok(())
+}
+
+impl HumantimeFormat for Recurrence {
+    const EXPECTING: &'static str = "a schedule such as \"every monday at 9am\"";
+
+    type Err = InvalidRecurrence;
+
+    fn parse(s: &str) -> Result<Recurrence, InvalidRecurrence> {
+        let s = strip_word(s.trim(), "every ").ok_or(InvalidRecurrence)?.trim_start();
+        let (days, time) = split_word(s, "at").map_or((s, None), |(days, time)| (days, Some(time)));
+        if let Ok(days) = parse_days(days) {
+            let time = time.map_or(Ok(TimeOfDay::default()), parse_time)?;
+            return Ok(Recurrence { days, rule: Rule::At(time) });
+        }
+        let (s, days) = match split_word(s, "on") {
+            Some((s, days)) => (s, parse_days(days)?),
+            None => (s, ALL_DAYS),
+        };
+        let (period, window) = match split_word(s, "between") {
+            Some((period, window)) => {
+                let (start, end) = split_word(window, "and").ok_or(InvalidRecurrence)?;
+                let window = TimeWindow::new(parse_time(start)?.into_inner(), parse_time(end)?.into_inner());
+                (period, Some(window))
+            }
+            None => (s, None),
+        };
+        Ok(Recurrence { days, rule: Rule::Every(parse_period(period)?, window) })
+    }
+
+    fn format(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.rule {
+            Rule::At(time) => {
+                f.write_str("every ")?;
+                write_days(self.days, false, f)?;
+                write!(f, " at {}", time)
+            }
+            Rule::Every(every, window) => {
+                every.format(f)?;
+                if let Some(w) = window {
+                    f.write_str(" between ")?;
+                    write_time(w.start(), f)?;
+                    f.write_str(" and ")?;
+                    write_time(w.end(), f)?;
+                }
+                if self.days != ALL_DAYS {
+                    f.write_str(" on ")?;
+                    write_days(self.days, true, f)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl fmt::Display for Recurrence {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.format(f)
+    }
+}
+
+impl FromStr for Recurrence {
+    type Err = InvalidRecurrence;
+
+    fn from_str(s: &str) -> Result<Recurrence, InvalidRecurrence> {
+        Recurrence::parse(s)
+    }
+}
+
+impl Serialize for Recurrence {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        Ref(self).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Recurrence {
+    fn deserialize<D>(d: D) -> Result<Recurrence, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Serde::deserialize(d).map(Serde::into_inner)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloc::string::ToString;
+    use chrono::Utc;
+
+    fn canonical(s: &str) -> alloc::string::String {
+        s.parse::<Recurrence>().unwrap().to_string()
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!(canonical("every day at noon"), "every day at 12:00");
+        assert_eq!(canonical("Every Monday At 9am"), "every monday at 09:00");
+        assert_eq!(canonical("every mon, wed and fri at 6:30 pm"), "every monday, wednesday and friday at 18:30");
+        assert_eq!(canonical("every tuesdays and thursdays"), "every tuesday and thursday at 00:00");
+        assert_eq!(canonical("every saturday and sunday at 10:00"), "every weekend at 10:00");
+        assert_eq!(canonical("every hour"), "every 1h");
+        assert_eq!(canonical("every 15 minutes between 9am and 5pm"), "every 15m between 09:00 and 17:00");
+        assert_eq!(canonical("every 2h on weekdays"), "every 2h on weekdays");
+        assert_eq!(canonical("every 30m between 10pm and 2am on friday, saturday"), "every 30m between 22:00 and 02:00 on fridays and saturdays");
+        for s in &["monday at 9am", "every", "every someday", "every monday at 25:00", "every 0s", "every 5m between 9am", "every 5m on payday"] {
+            assert_eq!(s.parse::<Recurrence>(), Err(InvalidRecurrence), "{}", s);
+        }
+    }
+
+    #[test]
+    fn next_after() {
+        // 2024-01-01 is a Monday.
+        let at = |d, h, m| Utc.with_ymd_and_hms(2024, 1, d, h, m, 0).unwrap();
+        let next = |s: &str, t: DateTime<Utc>| s.parse::<Recurrence>().unwrap().next_after(&t);
+        assert_eq!(next("every monday at 9am", at(1, 9, 0)), Some(at(8, 9, 0)));
+        assert_eq!(next("every monday at 9am", at(1, 8, 59)), Some(at(1, 9, 0)));
+        assert_eq!(next("every day", at(1, 12, 0)), Some(at(2, 0, 0)));
+        assert_eq!(next("every 7m", at(1, 23, 58)), Some(at(2, 0, 0)));
+        assert_eq!(next("every 15m between 9am and 5pm", at(1, 16, 45)), Some(at(2, 9, 0)));
+        assert_eq!(next("every 15m between 9am and 5pm on weekdays", at(5, 17, 0)), Some(at(8, 9, 0)));
+        assert_eq!(next("every 1h between 10pm and 2am on monday", at(2, 0, 30)), Some(at(2, 1, 0)));
+        assert_eq!(next("every 1h between 10pm and 2am on monday", at(2, 1, 30)), Some(at(8, 22, 0)));
+    }
+
+    #[test]
+    fn schedule() {
+        let poll: Recurrence = "every 5m between 9am and 5pm on weekdays".parse().unwrap();
+        let schedule = poll.schedule().unwrap();
+        assert_eq!(schedule.windows(Weekday::Fri), ["09:00-17:00".parse::<TimeWindow>().unwrap()]);
+        assert!(schedule.windows(Weekday::Sat).is_empty());
+        assert_eq!("every day at noon".parse::<Recurrence>().unwrap().schedule(), None);
+    }
+
+    #[test]
+    fn serde() {
+        let r: Recurrence = serde_json::from_str("\"every weekend at noon\"").unwrap();
+        assert_eq!(serde_json::to_string(&r).unwrap(), "\"every weekend at 12:00\"");
+        let err = serde_json::from_str::<Recurrence>("\"sometimes\"").unwrap_err();
+        assert!(err.to_string().contains("expected a schedule such as \"every monday at 9am\""));
+    }
+}
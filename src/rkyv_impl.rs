@@ -0,0 +1,144 @@
+//! `rkyv` support, enabled with the `rkyv` feature.
+//!
+//! `HumanDuration` and `HumanTimestamp` are archived exactly as the
+//! `Duration` and `DateTime<Utc>` they wrap, so an archive written from the
+//! plain types can be read back as the newtypes and the other way around.
+//! `Serde<T>` is archived as a `Serde` holding the archived `T`.
+//!
+//! `Archive::resolve` is an unsafe method, so these impls are the only place
+//! in the crate allowed unsafe code. They only forward to the inner type.
+
+use core::time::Duration;
+
+use rkyv::time::ArchivedDuration;
+use rkyv::{Archive, Deserialize, Fallible, Serialize};
+#[cfg(feature = "chrono")]
+use chrono::{DateTime, Utc};
+
+use super::{HumanDuration, Serde};
+#[cfg(feature = "chrono")]
+use super::HumanTimestamp;
+
+impl<T: Archive> Archive for Serde<T> {
+    type Archived = Serde<T::Archived>;
+    type Resolver = T::Resolver;
+
+    #[allow(unsafe_code)]
+    unsafe fn resolve(&self, pos: usize, resolver: T::Resolver, out: *mut Self::Archived) {
+        let (offset, out) = rkyv::out_field!(out.0);
+        self.0.resolve(pos + offset, resolver, out);
+    }
+}
+
+impl<T, S> Serialize<S> for Serde<T>
+where
+    T: Serialize<S>,
+    S: Fallible + ?Sized,
+{
+    fn serialize(&self, serializer: &mut S) -> Result<T::Resolver, S::Error> {
+        self.0.serialize(serializer)
+    }
+}
+
+impl<T, D> Deserialize<Serde<T>, D> for Serde<T::Archived>
+where
+    T: Archive,
+    T::Archived: Deserialize<T, D>,
+    D: Fallible + ?Sized,
+{
+    fn deserialize(&self, deserializer: &mut D) -> Result<Serde<T>, D::Error> {
+        Deserialize::<T, D>::deserialize(&self.0, deserializer).map(Serde)
+    }
+}
+
+impl Archive for HumanDuration {
+    type Archived = ArchivedDuration;
+    type Resolver = <Duration as Archive>::Resolver;
+
+    #[allow(unsafe_code)]
+    unsafe fn resolve(&self, pos: usize, resolver: Self::Resolver, out: *mut ArchivedDuration) {
+        Duration::resolve(self, pos, resolver, out);
+    }
+}
+
+impl<S: Fallible + ?Sized> Serialize<S> for HumanDuration {
+    fn serialize(&self, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+        Duration::serialize(self, serializer)
+    }
+}
+
+impl<D: Fallible + ?Sized> Deserialize<HumanDuration, D> for ArchivedDuration {
+    fn deserialize(&self, deserializer: &mut D) -> Result<HumanDuration, D::Error> {
+        Deserialize::<Duration, D>::deserialize(self, deserializer).map(HumanDuration::from)
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl Archive for HumanTimestamp {
+    type Archived = rkyv::Archived<DateTime<Utc>>;
+    type Resolver = rkyv::Resolver<DateTime<Utc>>;
+
+    #[allow(unsafe_code)]
+    unsafe fn resolve(&self, pos: usize, resolver: Self::Resolver, out: *mut Self::Archived) {
+        DateTime::<Utc>::resolve(self, pos, resolver, out);
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl<S: Fallible + ?Sized> Serialize<S> for HumanTimestamp {
+    fn serialize(&self, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+        DateTime::<Utc>::serialize(self, serializer)
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl<D: Fallible + ?Sized> Deserialize<HumanTimestamp, D> for rkyv::Archived<DateTime<Utc>> {
+    fn deserialize(&self, deserializer: &mut D) -> Result<HumanTimestamp, D::Error> {
+        Deserialize::<DateTime<Utc>, D>::deserialize(self, deserializer).map(HumanTimestamp::from)
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod test {
+    use super::*;
+
+    fn round_trip<T>(value: &T) -> T
+    where
+        T: Serialize<rkyv::ser::serializers::AllocSerializer<256>>,
+        T::Archived: Deserialize<T, rkyv::Infallible>,
+    {
+        let bytes = rkyv::to_bytes::<_, 256>(value).unwrap();
+        // SAFETY: the bytes were just written by `to_bytes` for a `T`.
+        #[allow(unsafe_code)]
+        let archived = unsafe { rkyv::archived_root::<T>(&bytes) };
+        archived.deserialize(&mut rkyv::Infallible).unwrap()
+    }
+
+    #[test]
+    fn duration() {
+        let dur = Serde::from(Duration::new(90, 500));
+        assert_eq!(round_trip(&dur), dur);
+    }
+
+    #[test]
+    fn human_duration() {
+        let dur = HumanDuration::from(Duration::new(90, 500));
+        assert_eq!(round_trip(&dur), dur);
+        let bytes = rkyv::to_bytes::<_, 256>(&dur).unwrap();
+        assert_eq!(&bytes[..], &rkyv::to_bytes::<_, 256>(&*dur).unwrap()[..]);
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn time() {
+        let time = Serde::from(DateTime::UNIX_EPOCH + Duration::new(1526063310, 0));
+        assert_eq!(round_trip(&time), time);
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn human_timestamp() {
+        let time = HumanTimestamp::from(DateTime::UNIX_EPOCH + Duration::new(1526063310, 0));
+        assert_eq!(round_trip(&time), time);
+    }
+}
@@ -0,0 +1,93 @@
+//! A `with` module for `Duration` fields that saturates instead of failing
+//! when the input is too large.
+//!
+//! # Example
+//!
+//! ```
+//! use core::time::Duration;
+//! use serde::{Serialize, Deserialize};
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Foo {
+//!     #[serde(with = "humantime_serde::saturating")]
+//!     ttl: Duration,
+//! }
+//!
+//! let foo: Foo = serde_json::from_str(r#"{"ttl": "1000000000000000000000 years"}"#).unwrap();
+//! assert_eq!(foo.ttl, Duration::MAX);
+//! ```
+
+use core::fmt;
+use core::time::Duration;
+
+use serde::{de, Deserializer, Serializer};
+
+use super::format::HumantimeFormat;
+
+/// Serializes a `Duration`.
+pub fn serialize<S>(d: &Duration, s: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    crate::serialize(d, s)
+}
+
+/// Deserializes a `Duration`, returning `Duration::MAX` if it is too large.
+pub fn deserialize<'a, D>(d: D) -> Result<Duration, D::Error>
+where
+    D: Deserializer<'a>,
+{
+    struct V;
+
+    impl de::Visitor<'_> for V {
+        type Value = Duration;
+
+        fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+            fmt.write_str(Duration::EXPECTING)
+        }
+
+        fn visit_str<E>(self, v: &str) -> Result<Duration, E>
+        where
+            E: de::Error,
+        {
+            crate::check_len(v, crate::DEFAULT_MAX_LEN, Duration::EXPECTING)?;
+            match Duration::parse(v) {
+                Ok(d) => Ok(d),
+                Err(err) if Duration::is_overflow(&err) => Ok(Duration::MAX),
+                Err(err) => {
+                    let err = crate::DidYouMean(&err, Duration::suggestion(v, &err));
+                    Err(E::invalid_value(de::Unexpected::Str(v), &crate::Because(&self, &err)))
+                }
+            }
+        }
+    }
+
+    d.deserialize_str(V)
+}
+
+crate::__option_module!([] ::core::time::Duration, []);
+
+#[cfg(test)]
+mod test {
+    use serde::{Deserialize, Serialize};
+    use core::time::Duration;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Foo {
+        #[serde(with = "super")]
+        ttl: Duration,
+        #[serde(with = "super::option", default)]
+        idle: Option<Duration>,
+    }
+
+    #[test]
+    fn saturate() {
+        let json = r#"{"ttl": "99999999999999999999s", "idle": "1000000000000000000000 years"}"#;
+        let foo = serde_json::from_str::<Foo>(json).unwrap();
+        assert_eq!(foo, Foo { ttl: Duration::MAX, idle: Some(Duration::MAX) });
+
+        let foo = serde_json::from_str::<Foo>(r#"{"ttl": "1m"}"#).unwrap();
+        assert_eq!(foo, Foo { ttl: Duration::from_secs(60), idle: None });
+        assert!(serde_json::from_str::<Foo>(r#"{"ttl": "1 fortnight"}"#).is_err());
+    }
+}
@@ -0,0 +1,274 @@
+use alloc::vec::Vec;
+use core::fmt;
+use core::str::FromStr;
+
+use chrono::{DateTime, Datelike, TimeDelta, TimeZone, Timelike, Weekday};
+use serde::de::{self, MapAccess, Visitor};
+use serde::ser::SerializeMap;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use super::TimeWindow;
+
+const DAY_NANOS: i64 = 86_400 * 1_000_000_000;
+const WEEK_NANOS: i64 = 7 * DAY_NANOS;
+
+const DAY_NAMES: [&str; 7] = ["mon", "tue", "wed", "thu", "fri", "sat", "sun"];
+
+/// Daily windows for each day of the week.
+///
+/// Deserializes from a map of weekdays to lists of [`TimeWindow`]s, such as
+/// `{"mon": ["09:00-17:00"], "sat": []}`. Days are written as `mon` or
+/// `monday`, in any case, and missing days have no windows. A window
+/// crossing midnight belongs to the day it starts on, so `"22:00-02:00"`
+/// under `fri` covers the first two hours of Saturday.
+///
+/// # Example
+///
+/// ```
+/// use chrono::{TimeZone, Utc};
+/// use humantime_serde::WeeklySchedule;
+///
+/// let json = r#"{"mon": ["09:00-17:00"], "fri": ["22:00-02:00"], "sat": []}"#;
+/// let schedule: WeeklySchedule = serde_json::from_str(json).unwrap();
+///
+/// // 2024-01-06 is a Saturday.
+/// let t = Utc.with_ymd_and_hms(2024, 1, 6, 1, 0, 0).unwrap();
+/// assert!(schedule.is_active_at(&t));
+/// assert_eq!(
+///     schedule.next_transition(&t),
+///     Some(Utc.with_ymd_and_hms(2024, 1, 6, 2, 0, 0).unwrap()),
+/// );
+/// ```
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct WeeklySchedule {
+    days: [Vec<TimeWindow>; 7],
+}
+
+impl WeeklySchedule {
+    /// Creates a schedule without any window.
+    pub fn new() -> WeeklySchedule {
+        WeeklySchedule::default()
+    }
+
+    /// The windows starting on `day`.
+    pub fn windows(&self, day: Weekday) -> &[TimeWindow] {
+        &self.days[day.num_days_from_monday() as usize]
+    }
+
+    /// Adds a window starting on `day`.
+    pub fn push(&mut self, day: Weekday, window: TimeWindow) {
+        self.days[day.num_days_from_monday() as usize].push(window);
+    }
+
+    /// Returns whether `t` is within one of the windows, in `t`'s time zone.
+    pub fn is_active_at<Tz>(&self, t: &DateTime<Tz>) -> bool
+    where
+        Tz: TimeZone,
+    {
+        self.is_active(week_offset(t))
+    }
+
+    /// Returns the next point after `t` at which the schedule becomes active
+    /// or inactive, or `None` if it never changes.
+    pub fn next_transition<Tz>(&self, t: &DateTime<Tz>) -> Option<DateTime<Tz>>
+    where
+        Tz: TimeZone,
+    {
+        let now = week_offset(t);
+        let delta = self
+            .spans()
+            .flat_map(|(start, end)| [start, end % WEEK_NANOS])
+            .filter(|&b| self.is_active(b) != self.is_active((b - 1).rem_euclid(WEEK_NANOS)))
+            .map(|b| match (b - now).rem_euclid(WEEK_NANOS) {
+                0 => WEEK_NANOS,
+                delta => delta,
+            })
+            .min()?;
+        let delta = TimeDelta::nanoseconds(delta);
+        // Step in local time so the windows follow the wall clock across
+        // offset changes.
+        let naive = t.naive_local() + delta;
+        Some(
+            t.timezone()
+                .from_local_datetime(&naive)
+                .earliest()
+                .unwrap_or_else(|| t.clone() + delta),
+        )
+    }
+
+    /// The windows as spans of nanoseconds since Monday midnight. The end
+    /// may be past the end of the week.
+    fn spans(&self) -> impl Iterator<Item = (i64, i64)> + '_ {
+        self.days.iter().enumerate().flat_map(|(day, windows)| {
+            windows.iter().map(move |w| {
+                let start = day as i64 * DAY_NANOS + time_nanos(&w.start());
+                let mut len = time_nanos(&w.end()) - time_nanos(&w.start());
+                if len <= 0 {
+                    len += DAY_NANOS;
+                }
+                (start, start + len)
+            })
+        })
+    }
+
+    fn is_active(&self, offset: i64) -> bool {
+        self.spans().any(|(start, end)| {
+            (start <= offset && offset < end) || (start <= offset + WEEK_NANOS && offset + WEEK_NANOS < end)
+        })
+    }
+}
+
+fn time_nanos<T: Timelike>(t: &T) -> i64 {
+    t.num_seconds_from_midnight() as i64 * 1_000_000_000 + t.nanosecond().min(999_999_999) as i64
+}
+
+fn week_offset<Tz>(t: &DateTime<Tz>) -> i64
+where
+    Tz: TimeZone,
+{
+    let local = t.naive_local();
+    local.weekday().num_days_from_monday() as i64 * DAY_NANOS + time_nanos(&local)
+}
+
+impl Serialize for WeeklySchedule {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let days = self.days.iter().filter(|w| !w.is_empty()).count();
+        let mut map = serializer.serialize_map(Some(days))?;
+        for (name, windows) in DAY_NAMES.iter().zip(&self.days) {
+            if !windows.is_empty() {
+                map.serialize_entry(name, windows)?;
+            }
+        }
+        map.end()
+    }
+}
+
+struct Day(Weekday);
+
+impl<'de> Deserialize<'de> for Day {
+    fn deserialize<D>(d: D) -> Result<Day, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct V;
+
+        impl Visitor<'_> for V {
+            type Value = Day;
+
+            fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+                fmt.write_str("a day of the week")
+            }
+
+            fn visit_str<E>(self, v: &str) -> Result<Day, E>
+            where
+                E: de::Error,
+            {
+                Weekday::from_str(v)
+                    .map(Day)
+                    .map_err(|_| E::invalid_value(de::Unexpected::Str(v), &self))
+            }
+        }
+
+        d.deserialize_str(V)
+    }
+}
+
+impl<'de> Deserialize<'de> for WeeklySchedule {
+    fn deserialize<D>(d: D) -> Result<WeeklySchedule, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct V;
+
+        impl<'de> Visitor<'de> for V {
+            type Value = WeeklySchedule;
+
+            fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+                fmt.write_str("a map of days of the week to time windows")
+            }
+
+            fn visit_map<A>(self, mut map: A) -> Result<WeeklySchedule, A::Error>
+            where
+                A: MapAccess<'de>,
+            {
+                let mut schedule = WeeklySchedule::new();
+                let mut seen = [false; 7];
+                while let Some(Day(day)) = map.next_key()? {
+                    let i = day.num_days_from_monday() as usize;
+                    if seen[i] {
+                        return Err(de::Error::custom(format_args!(
+                            "duplicate day `{}`",
+                            DAY_NAMES[i]
+                        )));
+                    }
+                    seen[i] = true;
+                    schedule.days[i] = map.next_value()?;
+                }
+                Ok(schedule)
+            }
+        }
+
+        d.deserialize_map(V)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloc::string::ToString;
+    use chrono::{FixedOffset, Utc};
+
+    fn schedule() -> WeeklySchedule {
+        let json = r#"{"Monday": ["09:00-12:00", "13:00-17:00"], "sun": ["22:00-01:00"], "sat": []}"#;
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn serde() {
+        let schedule = schedule();
+        assert_eq!(schedule.windows(Weekday::Mon).len(), 2);
+        assert!(schedule.windows(Weekday::Sat).is_empty());
+        assert_eq!(
+            serde_json::to_string(&schedule).unwrap(),
+            r#"{"mon":["09:00-12:00","13:00-17:00"],"sun":["22:00-01:00"]}"#
+        );
+
+        let err = serde_json::from_str::<WeeklySchedule>(r#"{"mon": [], "mon": []}"#).unwrap_err();
+        assert!(err.to_string().starts_with("duplicate day `mon`"));
+        let err = serde_json::from_str::<WeeklySchedule>(r#"{"someday": []}"#).unwrap_err();
+        assert!(err.to_string().contains("expected a day of the week"));
+    }
+
+    #[test]
+    fn active() {
+        let schedule = schedule();
+        // 2024-01-01 is a Monday.
+        let at = |d, h, m| Utc.with_ymd_and_hms(2024, 1, d, h, m, 0).unwrap();
+        assert!(schedule.is_active_at(&at(1, 0, 30)));
+        assert!(schedule.is_active_at(&at(1, 9, 0)));
+        assert!(!schedule.is_active_at(&at(1, 12, 30)));
+        assert!(!schedule.is_active_at(&at(2, 10, 0)));
+
+        let paris = FixedOffset::east_opt(3600).unwrap();
+        assert!(schedule.is_active_at(&at(1, 8, 0).with_timezone(&paris)));
+    }
+
+    #[test]
+    fn transitions() {
+        let schedule = schedule();
+        let at = |d, h, m| Utc.with_ymd_and_hms(2024, 1, d, h, m, 0).unwrap();
+        assert_eq!(schedule.next_transition(&at(1, 0, 30)), Some(at(1, 1, 0)));
+        assert_eq!(schedule.next_transition(&at(1, 9, 0)), Some(at(1, 12, 0)));
+        assert_eq!(schedule.next_transition(&at(1, 12, 0)), Some(at(1, 13, 0)));
+        assert_eq!(schedule.next_transition(&at(2, 0, 0)), Some(at(7, 22, 0)));
+        assert_eq!(WeeklySchedule::new().next_transition(&at(1, 0, 0)), None);
+
+        let mut always = WeeklySchedule::new();
+        always.push(Weekday::Mon, "00:00-00:00".parse().unwrap());
+        always.push(Weekday::Tue, "00:00-00:00".parse().unwrap());
+        assert_eq!(always.next_transition(&at(1, 5, 0)), Some(at(3, 0, 0)));
+    }
+}
@@ -0,0 +1,88 @@
+use alloc::string::String;
+use core::cell::RefCell;
+use core::fmt::{self, Write};
+
+use serde::{ser, Serializer};
+
+std::thread_local! {
+    static SCRATCH: RefCell<Option<String>> = RefCell::new(None);
+}
+
+/// Runs `f` with a buffer, reused by every value this crate serializes on
+/// the current thread, in place.
+///
+/// Values are normally written with `Serializer::collect_str`, which
+/// serializers without a specialized implementation turn into a `String`
+/// per value. Within `f` they are formatted into the buffer instead and
+/// passed to `Serializer::serialize_str`, so serializing many timestamps
+/// allocates once. Nested calls share the outer buffer.
+///
+/// # Example
+///
+/// ```
+/// use core::time::Duration;
+/// use humantime_serde::{with_scratch_buffer, Serde};
+///
+/// let values = vec![Serde::from(Duration::from_secs(90)); 1000];
+/// let json = with_scratch_buffer(|| serde_json::to_string(&values)).unwrap();
+/// assert!(json.starts_with(r#"["1m 30s","1m 30s","#));
+/// ```
+pub fn with_scratch_buffer<R>(f: impl FnOnce() -> R) -> R {
+    struct Reset;
+
+    impl Drop for Reset {
+        fn drop(&mut self) {
+            SCRATCH.with(|cell| *cell.borrow_mut() = None);
+        }
+    }
+
+    let outer = SCRATCH.with(|cell| {
+        let mut slot = cell.borrow_mut();
+        let outer = slot.is_some();
+        if !outer {
+            *slot = Some(String::new());
+        }
+        outer
+    });
+    let _reset = if outer { None } else { Some(Reset) };
+    f()
+}
+
+/// Serializes `v` through the scratch buffer if one is in scope.
+pub(crate) fn serialize<S>(v: &dyn fmt::Display, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    SCRATCH.with(|cell| match cell.try_borrow_mut() {
+        Ok(mut slot) => match slot.as_mut() {
+            Some(buf) => {
+                buf.clear();
+                write!(buf, "{}", v).map_err(ser::Error::custom)?;
+                serializer.serialize_str(buf)
+            }
+            None => serializer.collect_str(v),
+        },
+        // A value serialized while formatting another one.
+        Err(_) => serializer.collect_str(v),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Serde;
+    use alloc::vec;
+    use core::time::Duration;
+
+    #[test]
+    fn scratch() {
+        let values = vec![Serde::from(Duration::from_secs(90)), Serde::from(Duration::from_millis(5))];
+        let plain = serde_json::to_string(&values).unwrap();
+        let scratch = with_scratch_buffer(|| {
+            with_scratch_buffer(|| serde_json::to_string(&values).unwrap())
+        });
+        assert_eq!(plain, scratch);
+        assert_eq!(plain, r#"["1m 30s","5ms"]"#);
+        SCRATCH.with(|cell| assert!(cell.borrow().is_none()));
+    }
+}
@@ -0,0 +1,271 @@
+//! `DeserializeSeed` implementations whose format is chosen at runtime.
+//!
+//! The `with` modules fix the format at compile time. The seeds here carry
+//! their options as values instead, so an application can pick them from
+//! its own configuration and pass them to `next_value_seed`,
+//! `next_element_seed` or `DeserializeSeed::deserialize` directly.
+//!
+//! # Example
+//!
+//! ```
+//! use core::time::Duration;
+//! use serde::de::DeserializeSeed;
+//! use humantime_serde::custom::Options;
+//! use humantime_serde::seed::{DurationOptions, DurationSeed, TimestampSeed};
+//!
+//! let lenient = true; // e.g. read from the application's settings
+//!
+//! let seed = DurationSeed::with_options(DurationOptions::DEFAULT.lenient(lenient));
+//! let mut de = serde_json::Deserializer::from_str(r#""1 hour, 30 minutes""#);
+//! assert_eq!(seed.deserialize(&mut de).unwrap(), Duration::from_secs(5400));
+//!
+//! let seed = TimestampSeed::<chrono::Utc>::with_options(Options::DEFAULT.lenient(lenient));
+//! let mut de = serde_json::Deserializer::from_str(r#""2018-05-11 18:28:30""#);
+//! assert_eq!(seed.deserialize(&mut de).unwrap().timestamp(), 1526063310);
+//! ```
+
+use core::fmt;
+#[cfg(feature = "chrono")]
+use core::marker::PhantomData;
+use core::time::Duration;
+
+#[cfg(feature = "chrono")]
+use chrono::{DateTime, FixedOffset, TimeZone};
+use serde::de::{self, DeserializeSeed, Deserializer};
+
+#[cfg(feature = "chrono")]
+use super::custom::Options;
+
+/// The options of a [`DurationSeed`].
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub struct DurationOptions {
+    /// Whether the separators of [`parse_duration_lenient`] are accepted.
+    ///
+    /// [`parse_duration_lenient`]: crate::parse_duration_lenient
+    pub lenient: bool,
+    /// The longest input accepted, in bytes.
+    pub max_len: usize,
+}
+
+impl DurationOptions {
+    /// The options matching the default format of this crate.
+    pub const DEFAULT: DurationOptions = DurationOptions {
+        lenient: false,
+        max_len: crate::DEFAULT_MAX_LEN,
+    };
+
+    /// Sets the leniency.
+    pub const fn lenient(self, lenient: bool) -> DurationOptions {
+        DurationOptions { lenient, ..self }
+    }
+
+    /// Sets the longest input accepted.
+    pub const fn max_len(self, max_len: usize) -> DurationOptions {
+        DurationOptions { max_len, ..self }
+    }
+}
+
+impl Default for DurationOptions {
+    fn default() -> DurationOptions {
+        DurationOptions::DEFAULT
+    }
+}
+
+/// Deserializes a `Duration` according to runtime [`DurationOptions`].
+#[derive(Copy, Clone, Debug, Default)]
+pub struct DurationSeed {
+    options: DurationOptions,
+}
+
+impl DurationSeed {
+    /// A seed using the default format of this crate.
+    pub fn new() -> DurationSeed {
+        DurationSeed::default()
+    }
+
+    /// A seed using `options`.
+    pub fn with_options(options: DurationOptions) -> DurationSeed {
+        DurationSeed { options }
+    }
+}
+
+impl<'de> DeserializeSeed<'de> for DurationSeed {
+    type Value = Duration;
+
+    fn deserialize<D>(self, d: D) -> Result<Duration, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct V(DurationOptions);
+
+        impl de::Visitor<'_> for V {
+            type Value = Duration;
+
+            fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+                fmt.write_str("a duration")
+            }
+
+            fn visit_str<E>(self, v: &str) -> Result<Duration, E>
+            where
+                E: de::Error,
+            {
+                crate::check_len(v, self.0.max_len, "a duration")?;
+                let parsed = if self.0.lenient {
+                    crate::parse_duration_lenient(v)
+                } else {
+                    crate::parse::<Duration>(v)
+                };
+                parsed.map_err(|err| {
+                    E::invalid_value(de::Unexpected::Str(v), &crate::Because(&self, &err))
+                })
+            }
+        }
+
+        d.deserialize_str(V(self.options))
+    }
+}
+
+/// Deserializes a `DateTime<Tz>` according to runtime [`custom::Options`].
+///
+/// [`custom::Options`]: crate::custom::Options
+#[cfg(feature = "chrono")]
+pub struct TimestampSeed<Tz> {
+    options: Options,
+    tz: PhantomData<fn() -> Tz>,
+}
+
+#[cfg(feature = "chrono")]
+impl<Tz> TimestampSeed<Tz> {
+    /// A seed using the default format of this crate.
+    pub fn new() -> TimestampSeed<Tz> {
+        TimestampSeed::with_options(Options::DEFAULT)
+    }
+
+    /// A seed using `options`.
+    pub fn with_options(options: Options) -> TimestampSeed<Tz> {
+        TimestampSeed {
+            options,
+            tz: PhantomData,
+        }
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl<Tz> Default for TimestampSeed<Tz> {
+    fn default() -> TimestampSeed<Tz> {
+        TimestampSeed::new()
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl<Tz> Clone for TimestampSeed<Tz> {
+    fn clone(&self) -> TimestampSeed<Tz> {
+        TimestampSeed::with_options(self.options)
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl<Tz> Copy for TimestampSeed<Tz> {}
+
+#[cfg(feature = "chrono")]
+impl<Tz> fmt::Debug for TimestampSeed<Tz> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("TimestampSeed").field("options", &self.options).finish()
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl<'de, Tz> DeserializeSeed<'de> for TimestampSeed<Tz>
+where
+    Tz: TimeZone,
+    DateTime<Tz>: From<DateTime<FixedOffset>>,
+{
+    type Value = DateTime<Tz>;
+
+    fn deserialize<D>(self, d: D) -> Result<DateTime<Tz>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        crate::custom::deserialize(&self.options, d)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloc::string::ToString;
+    use alloc::vec::Vec;
+    #[cfg(feature = "chrono")]
+    use chrono::Utc;
+    use serde::de::{SeqAccess, Visitor};
+
+    /// A list of durations whose format comes from the enclosing document.
+    #[derive(Debug, PartialEq)]
+    struct Timeouts(Vec<Duration>);
+
+    struct TimeoutsSeed(DurationSeed);
+
+    impl<'de> DeserializeSeed<'de> for TimeoutsSeed {
+        type Value = Timeouts;
+
+        fn deserialize<D>(self, d: D) -> Result<Timeouts, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            impl<'de> Visitor<'de> for TimeoutsSeed {
+                type Value = Timeouts;
+
+                fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+                    fmt.write_str("a list of durations")
+                }
+
+                fn visit_seq<A>(self, mut seq: A) -> Result<Timeouts, A::Error>
+                where
+                    A: SeqAccess<'de>,
+                {
+                    let mut v = Vec::new();
+                    while let Some(d) = seq.next_element_seed(self.0)? {
+                        v.push(d);
+                    }
+                    Ok(Timeouts(v))
+                }
+            }
+
+            d.deserialize_seq(self)
+        }
+    }
+
+    #[test]
+    fn runtime_options() {
+        let json = r#"["1m and 30s", "2h"]"#;
+        let lenient = TimeoutsSeed(DurationSeed::with_options(DurationOptions::DEFAULT.lenient(true)));
+        let timeouts = lenient.deserialize(&mut serde_json::Deserializer::from_str(json)).unwrap();
+        assert_eq!(timeouts, Timeouts(Vec::from([Duration::from_secs(90), Duration::from_secs(7200)])));
+
+        let strict = TimeoutsSeed(DurationSeed::new());
+        let err = strict.deserialize(&mut serde_json::Deserializer::from_str(json)).unwrap_err();
+        assert!(err.to_string().contains("expected a duration"));
+
+        let short = DurationSeed::with_options(DurationOptions::DEFAULT.max_len(4));
+        assert!(short.deserialize(serde_json::Value::from("1m 30s")).is_err());
+        assert!(short.deserialize(serde_json::Value::from("90s")).is_ok());
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn timestamp() {
+        let value = serde_json::Value::from("2018-05-11T20:28:30+02:00");
+        let t = TimestampSeed::<FixedOffset>::new().deserialize(value.clone()).unwrap();
+        assert_eq!(t.offset().local_minus_utc(), 7200);
+        let t = TimestampSeed::<Utc>::new().deserialize(value).unwrap();
+        assert_eq!(t, "2018-05-11T18:28:30Z".parse::<DateTime<Utc>>().unwrap());
+
+        let seed = TimestampSeed::<Utc>::new();
+        assert!(seed.deserialize(serde_json::Value::from("2018-05-11 18:28:30")).is_err());
+
+        let seed = TimestampSeed::<Utc>::with_options(Options::DEFAULT.lenient(true));
+        assert!(seed.deserialize(serde_json::Value::from("2018-05-11 18:28:30")).is_ok());
+        assert!(seed.deserialize(serde_json::Value::from("now")).is_err());
+        assert!(seed.deserialize(serde_json::Value::from("2018-05-11 18:28:30 + 1h")).is_err());
+    }
+}
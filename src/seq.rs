@@ -0,0 +1,120 @@
+//! A `with` module for any collection of durations or timestamps, written
+//! as an array of humantime strings.
+//!
+//! It works for every collection that can be iterated over by reference
+//! and built from an iterator, such as `VecDeque`, `LinkedList` and
+//! `BinaryHeap`, as well as collections from other crates. Elements are
+//! written in the order the collection iterates in, and the collection is
+//! built directly from the input, without an intermediate `Vec`, so the
+//! module works without the `alloc` feature too.
+//!
+//! # Example
+//!
+//! ```
+//! use std::collections::{BinaryHeap, VecDeque};
+//! use core::time::Duration;
+//! use serde::{Serialize, Deserialize};
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Backlog {
+//!     #[serde(with = "humantime_serde::seq")]
+//!     pending: VecDeque<Duration>,
+//!     #[serde(with = "humantime_serde::seq")]
+//!     longest: BinaryHeap<Duration>,
+//! }
+//!
+//! let json = r#"{"pending": ["1s", "1m"], "longest": ["2h"]}"#;
+//! let backlog: Backlog = serde_json::from_str(json).unwrap();
+//! assert_eq!(backlog.pending.front(), Some(&Duration::from_secs(1)));
+//! let json = serde_json::to_string(&backlog).unwrap();
+//! assert_eq!(json, r#"{"pending":["1s","1m"],"longest":["2h"]}"#);
+//! ```
+
+use core::fmt;
+use core::iter::FromIterator;
+use core::marker::PhantomData;
+
+use serde::de::{SeqAccess, Visitor};
+use serde::{Deserializer, Serializer};
+
+use super::format::{HumantimeFormat, Ref};
+use super::Serde;
+
+/// Serializes a collection of `Duration` or `DateTime<Tz>` as an array.
+pub fn serialize<'s, C, T, S>(d: &'s C, s: S) -> Result<S::Ok, S::Error>
+where
+    &'s C: IntoIterator<Item = &'s T>,
+    T: HumantimeFormat + 's,
+    S: Serializer,
+{
+    s.collect_seq(d.into_iter().map(Ref))
+}
+
+/// Deserializes a collection of `Duration` or `DateTime<Tz>` from an array.
+pub fn deserialize<'a, C, T, D>(d: D) -> Result<C, D::Error>
+where
+    C: IntoIterator<Item = T> + FromIterator<T>,
+    T: HumantimeFormat,
+    D: Deserializer<'a>,
+{
+    struct V<C, T>(PhantomData<(C, T)>);
+
+    impl<'de, C, T> Visitor<'de> for V<C, T>
+    where
+        C: FromIterator<T>,
+        T: HumantimeFormat,
+    {
+        type Value = C;
+
+        fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+            write!(fmt, "a sequence of {}", T::EXPECTING)
+        }
+
+        fn visit_seq<A>(self, mut seq: A) -> Result<C, A::Error>
+        where
+            A: SeqAccess<'de>,
+        {
+            core::iter::from_fn(|| seq.next_element::<Serde<T>>().transpose())
+                .map(|v| v.map(Serde::into_inner))
+                .collect()
+        }
+    }
+
+    d.deserialize_seq(V(PhantomData))
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod test {
+    use super::*;
+    use alloc::collections::{BTreeSet, LinkedList, VecDeque};
+    use alloc::string::ToString;
+    use core::time::Duration;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Debug, Serialize, Deserialize)]
+    struct Foo {
+        #[serde(with = "super")]
+        queue: VecDeque<Duration>,
+        #[serde(with = "super")]
+        list: LinkedList<Duration>,
+        #[serde(with = "super")]
+        set: BTreeSet<Duration>,
+    }
+
+    #[test]
+    fn collections() {
+        let json = r#"{"queue": ["1s", "2s"], "list": ["1m"], "set": ["2h", "1h", "60m"]}"#;
+        let foo: Foo = serde_json::from_str(json).unwrap();
+        assert_eq!(foo.queue, [Duration::from_secs(1), Duration::from_secs(2)]);
+        assert_eq!(foo.set.len(), 2);
+        let json = serde_json::to_string(&foo).unwrap();
+        assert_eq!(json, r#"{"queue":["1s","2s"],"list":["1m"],"set":["1h","2h"]}"#);
+
+        let json = r#"{"queue": ["1s", "soon"], "list": [], "set": []}"#;
+        let err = serde_json::from_str::<Foo>(json).unwrap_err();
+        assert!(err.to_string().contains("expected a duration"));
+        let json = r#"{"queue": "1s", "list": [], "set": []}"#;
+        let err = serde_json::from_str::<Foo>(json).unwrap_err();
+        assert!(err.to_string().contains("expected a sequence of a duration"));
+    }
+}
@@ -0,0 +1,202 @@
+//! `with` modules for `BTreeSet` and `HashSet` fields of durations or
+//! timestamps, written as arrays of humantime strings.
+//!
+//! The parent module merges duplicate elements, as inserting into a set
+//! does. The [`unique`] submodule rejects them instead, since a duplicate
+//! in a hand-written list is usually a mistake. `HashSet` needs the `std`
+//! feature.
+//!
+//! # Example
+//!
+//! ```
+//! use std::collections::BTreeSet;
+//! use core::time::Duration;
+//! use serde::{Serialize, Deserialize};
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Alerts {
+//!     #[serde(with = "humantime_serde::set")]
+//!     remind_before: BTreeSet<Duration>,
+//!     #[serde(with = "humantime_serde::set::unique")]
+//!     escalate_after: BTreeSet<Duration>,
+//! }
+//!
+//! let json = r#"{"remind_before": ["1h", "5m", "60m"], "escalate_after": ["15m"]}"#;
+//! let alerts: Alerts = serde_json::from_str(json).unwrap();
+//! let json = serde_json::to_string(&alerts).unwrap();
+//! assert_eq!(json, r#"{"remind_before":["5m","1h"],"escalate_after":["15m"]}"#);
+//!
+//! let json = r#"{"remind_before": [], "escalate_after": ["15m", "900s"]}"#;
+//! let err = serde_json::from_str::<Alerts>(json).unwrap_err();
+//! assert!(err.to_string().starts_with("duplicate element 15m"));
+//! ```
+
+use alloc::collections::BTreeSet;
+use core::fmt;
+use core::marker::PhantomData;
+
+use serde::de::{self, SeqAccess, Visitor};
+use serde::{Deserializer, Serializer};
+
+use super::format::{HumantimeFormat, Ref};
+use super::Serde;
+
+/// A set of durations or timestamps.
+pub trait Set: Default {
+    /// The element type.
+    type Item: HumantimeFormat;
+
+    /// Whether `v` is in the set.
+    fn contains(&self, v: &Self::Item) -> bool;
+
+    /// Adds `v`, unless it is in the set already.
+    fn insert(&mut self, v: Self::Item);
+}
+
+impl<T> Set for BTreeSet<T>
+where
+    T: HumantimeFormat + Ord,
+{
+    type Item = T;
+
+    fn contains(&self, v: &T) -> bool {
+        BTreeSet::contains(self, v)
+    }
+
+    fn insert(&mut self, v: T) {
+        BTreeSet::insert(self, v);
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T, H> Set for std::collections::HashSet<T, H>
+where
+    T: HumantimeFormat + Eq + core::hash::Hash,
+    H: core::hash::BuildHasher + Default,
+{
+    type Item = T;
+
+    fn contains(&self, v: &T) -> bool {
+        std::collections::HashSet::contains(self, v)
+    }
+
+    fn insert(&mut self, v: T) {
+        std::collections::HashSet::insert(self, v);
+    }
+}
+
+/// Serializes a set, or any collection, of `Duration` or `DateTime<Tz>` as
+/// an array, in the order the collection iterates in.
+pub fn serialize<'s, C, T, S>(d: &'s C, s: S) -> Result<S::Ok, S::Error>
+where
+    &'s C: IntoIterator<Item = &'s T>,
+    T: HumantimeFormat + 's,
+    S: Serializer,
+{
+    s.collect_seq(d.into_iter().map(Ref))
+}
+
+/// Deserializes a `BTreeSet` or `HashSet` of `Duration` or `DateTime<Tz>`,
+/// merging duplicates.
+pub fn deserialize<'a, C, D>(d: D) -> Result<C, D::Error>
+where
+    C: Set,
+    D: Deserializer<'a>,
+{
+    d.deserialize_seq(V { unique: false, set: PhantomData })
+}
+
+struct V<C> {
+    unique: bool,
+    set: PhantomData<C>,
+}
+
+impl<'de, C> Visitor<'de> for V<C>
+where
+    C: Set,
+{
+    type Value = C;
+
+    fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "a set of {}", C::Item::EXPECTING)
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<C, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut set = C::default();
+        while let Some(v) = seq.next_element::<Serde<C::Item>>()? {
+            let v = v.into_inner();
+            if self.unique && set.contains(&v) {
+                return Err(de::Error::custom(format_args!("duplicate element {}", Ref(&v))));
+            }
+            set.insert(v);
+        }
+        Ok(set)
+    }
+}
+
+/// Reads and writes sets like the parent module, but rejects duplicate
+/// elements.
+pub mod unique {
+    use super::*;
+
+    pub use super::serialize;
+
+    /// Deserializes a `BTreeSet` or `HashSet` of `Duration` or
+    /// `DateTime<Tz>`, rejecting duplicates.
+    pub fn deserialize<'a, C, D>(d: D) -> Result<C, D::Error>
+    where
+        C: Set,
+        D: Deserializer<'a>,
+    {
+        d.deserialize_seq(V { unique: true, set: PhantomData })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloc::string::ToString;
+    use core::time::Duration;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Debug, Serialize, Deserialize)]
+    struct Foo {
+        #[serde(with = "super")]
+        merged: BTreeSet<Duration>,
+        #[serde(with = "super::unique")]
+        unique: BTreeSet<Duration>,
+    }
+
+    #[test]
+    fn btree() {
+        let json = r#"{"merged": ["1m", "1s", "60s"], "unique": ["2s", "1s"]}"#;
+        let foo: Foo = serde_json::from_str(json).unwrap();
+        assert_eq!(foo.merged.len(), 2);
+        let json = serde_json::to_string(&foo).unwrap();
+        assert_eq!(json, r#"{"merged":["1s","1m"],"unique":["1s","2s"]}"#);
+
+        let json = r#"{"merged": [], "unique": ["1s", "1000ms"]}"#;
+        let err = serde_json::from_str::<Foo>(json).unwrap_err();
+        assert!(err.to_string().starts_with("duplicate element 1s"));
+        let json = r#"{"merged": "1s", "unique": []}"#;
+        let err = serde_json::from_str::<Foo>(json).unwrap_err();
+        assert!(err.to_string().contains("expected a set of a duration"));
+    }
+
+    #[test]
+    #[cfg(all(feature = "std", feature = "chrono"))]
+    fn hash() {
+        use chrono::{DateTime, Utc};
+        use std::collections::HashSet;
+
+        let d = serde_json::json!(["2018-05-11T18:28:30Z", "2018-05-11T20:28:30+02:00"]);
+        let set: HashSet<DateTime<Utc>> = deserialize(d.clone()).unwrap();
+        assert_eq!(set.len(), 1);
+        let json = serialize(&set, serde_json::value::Serializer).unwrap();
+        assert_eq!(json, serde_json::json!(["2018-05-11T18:28:30Z"]));
+        assert!(unique::deserialize::<HashSet<DateTime<Utc>>, _>(d).is_err());
+    }
+}
@@ -0,0 +1,94 @@
+//! A `with` module for `Vec` fields of durations or timestamps that must be
+//! strictly increasing.
+//!
+//! Schedule lists, retry ladders and the like are written in order; an
+//! entry out of order usually means a typo in the configuration, which this
+//! module reports instead of accepting.
+//!
+//! # Example
+//!
+//! ```
+//! use core::time::Duration;
+//! use serde::{Serialize, Deserialize};
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Retry {
+//!     #[serde(with = "humantime_serde::sorted")]
+//!     delays: Vec<Duration>,
+//! }
+//!
+//! let retry: Retry = serde_json::from_str(r#"{"delays": ["1s", "10s", "1m"]}"#).unwrap();
+//! assert_eq!(retry.delays.len(), 3);
+//!
+//! let err = serde_json::from_str::<Retry>(r#"{"delays": ["1s", "10m", "1m"]}"#).unwrap_err();
+//! assert!(err.to_string().starts_with("element 2, 1m, is not after 10m"));
+//! ```
+
+use alloc::vec::Vec;
+
+use serde::{de, Deserializer, Serializer};
+
+use super::format::{HumantimeFormat, Ref};
+
+/// Serializes a slice of `Duration` or `DateTime<Tz>`.
+pub fn serialize<T, S>(d: &[T], s: S) -> Result<S::Ok, S::Error>
+where
+    T: HumantimeFormat,
+    S: Serializer,
+{
+    crate::vec::serialize(d, s)
+}
+
+/// Deserializes a `Vec<Duration>` or `Vec<DateTime<Tz>>`, rejecting it
+/// unless each element is after the one before it.
+pub fn deserialize<'a, T, D>(d: D) -> Result<Vec<T>, D::Error>
+where
+    T: HumantimeFormat + PartialOrd,
+    D: Deserializer<'a>,
+{
+    let v = crate::vec::deserialize::<T, D>(d)?;
+    match v.windows(2).position(|w| w[0] >= w[1]) {
+        None => Ok(v),
+        Some(i) => Err(de::Error::custom(format_args!(
+            "element {}, {}, is not after {}",
+            i + 1,
+            Ref(&v[i + 1]),
+            Ref(&v[i]),
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloc::string::ToString;
+    use core::time::Duration;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Debug, Serialize, Deserialize)]
+    struct Foo {
+        #[serde(with = "super")]
+        delays: Vec<Duration>,
+    }
+
+    #[test]
+    fn sorted() {
+        let foo: Foo = serde_json::from_str(r#"{"delays": ["1s", "2s"]}"#).unwrap();
+        assert_eq!(serde_json::to_string(&foo).unwrap(), r#"{"delays":["1s","2s"]}"#);
+        assert!(serde_json::from_str::<Foo>(r#"{"delays": []}"#).is_ok());
+
+        let err = serde_json::from_str::<Foo>(r#"{"delays": ["1s", "1s"]}"#).unwrap_err();
+        assert!(err.to_string().starts_with("element 1, 1s, is not after 1s"));
+    }
+
+    #[test]
+    #[cfg(feature = "chrono")]
+    fn timestamps() {
+        use chrono::{DateTime, Utc};
+
+        let d = serde_json::json!(["2018-05-11T18:28:30Z", "2018-05-11T20:28:30+01:00"]);
+        assert!(deserialize::<DateTime<Utc>, _>(d).is_ok());
+        let d = serde_json::json!(["2018-05-11T18:28:30Z", "2018-05-11T20:28:30+02:00"]);
+        assert!(deserialize::<DateTime<Utc>, _>(d).is_err());
+    }
+}
@@ -0,0 +1,233 @@
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::marker::PhantomData;
+use core::ops::{Deref, Range};
+
+use serde::de::{self, IntoDeserializer, MapAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use super::format::{HumantimeFormat, Ref};
+use super::Serde;
+
+// The protocol of the `serde_spanned` crate, which `toml` implements.
+const NAME: &str = "$__serde_spanned_private_Spanned";
+const START: &str = "$__serde_spanned_private_start";
+const END: &str = "$__serde_spanned_private_end";
+const VALUE: &str = "$__serde_spanned_private_value";
+const FIELDS: &[&str] = &[START, END, VALUE];
+
+/// A value along with the byte range it was parsed from, when the
+/// deserializer reports it.
+///
+/// Span aware deserializers such as `toml`'s record where the value was
+/// written, so configuration loaders can point errors at it. Deserializers
+/// without that support but which hand structs to `deserialize_any`
+/// produce a value without a span. Comparisons, hashing and serialization
+/// only look at the value.
+///
+/// # Example
+///
+/// ```
+/// use core::time::Duration;
+/// use serde::Deserialize;
+/// use serde::de::IntoDeserializer;
+/// use humantime_serde::Spanned;
+///
+/// let d: serde::de::value::StrDeserializer<serde::de::value::Error> = "1m".into_deserializer();
+/// let timeout = Spanned::<Duration>::deserialize(d).unwrap();
+/// assert_eq!(*timeout, Duration::from_secs(60));
+/// assert_eq!(timeout.span(), None);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Spanned<T> {
+    span: Option<Range<usize>>,
+    value: T,
+}
+
+impl<T> Spanned<T> {
+    /// Wraps a value without a span.
+    pub fn new(value: T) -> Spanned<T> {
+        Spanned { span: None, value }
+    }
+
+    /// The byte range of the value in the input, if known.
+    pub fn span(&self) -> Option<Range<usize>> {
+        self.span.clone()
+    }
+
+    /// The 1-based line and column where the value starts in `source`, the
+    /// input it was deserialized from.
+    pub fn line_col(&self, source: &str) -> Option<(usize, usize)> {
+        let start = self.span.as_ref()?.start;
+        let before = source.get(..start)?;
+        let line = before.matches('\n').count() + 1;
+        let col = before.rsplit('\n').next().map_or(0, |l| l.chars().count()) + 1;
+        Some((line, col))
+    }
+
+    /// Consumes the `Spanned`, returning the inner value.
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T> Deref for Spanned<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T: PartialEq> PartialEq for Spanned<T> {
+    fn eq(&self, other: &Spanned<T>) -> bool {
+        self.value == other.value
+    }
+}
+
+impl<T: Eq> Eq for Spanned<T> {}
+
+impl<T: Hash> Hash for Spanned<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.value.hash(state);
+    }
+}
+
+impl<T> Serialize for Spanned<T>
+where
+    T: HumantimeFormat,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        Ref(&self.value).serialize(serializer)
+    }
+}
+
+impl<'de, T> Deserialize<'de> for Spanned<T>
+where
+    T: HumantimeFormat,
+{
+    fn deserialize<D>(d: D) -> Result<Spanned<T>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct V<T>(PhantomData<T>);
+
+        impl<'de, T> Visitor<'de> for V<T>
+        where
+            T: HumantimeFormat,
+        {
+            type Value = Spanned<T>;
+
+            fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+                fmt.write_str(T::EXPECTING)
+            }
+
+            fn visit_str<E>(self, v: &str) -> Result<Spanned<T>, E>
+            where
+                E: de::Error,
+            {
+                let value = Serde::<T>::deserialize(v.into_deserializer())?;
+                Ok(Spanned::new(value.into_inner()))
+            }
+
+            fn visit_map<A>(self, mut map: A) -> Result<Spanned<T>, A::Error>
+            where
+                A: MapAccess<'de>,
+            {
+                let (mut start, mut end, mut value) = (None, None, None);
+                while let Some(key) = map.next_key::<Field>()? {
+                    match key {
+                        Field::Start => start = Some(map.next_value::<usize>()?),
+                        Field::End => end = Some(map.next_value::<usize>()?),
+                        Field::Value => value = Some(map.next_value::<Serde<T>>()?.into_inner()),
+                    }
+                }
+                let value = value.ok_or_else(|| de::Error::missing_field(VALUE))?;
+                let span = match (start, end) {
+                    (Some(start), Some(end)) => Some(start..end),
+                    _ => None,
+                };
+                Ok(Spanned { span, value })
+            }
+        }
+
+        d.deserialize_struct(NAME, FIELDS, V(PhantomData))
+    }
+}
+
+/// A key of the `serde_spanned` map, read without allocating.
+enum Field {
+    Start,
+    End,
+    Value,
+}
+
+impl<'de> Deserialize<'de> for Field {
+    fn deserialize<D>(d: D) -> Result<Field, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct V;
+
+        impl Visitor<'_> for V {
+            type Value = Field;
+
+            fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+                fmt.write_str("a span field")
+            }
+
+            fn visit_str<E>(self, v: &str) -> Result<Field, E>
+            where
+                E: de::Error,
+            {
+                match v {
+                    START => Ok(Field::Start),
+                    END => Ok(Field::End),
+                    VALUE => Ok(Field::Value),
+                    _ => Err(E::unknown_field(v, FIELDS)),
+                }
+            }
+        }
+
+        d.deserialize_identifier(V)
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod test {
+    use super::*;
+    use alloc::string::ToString;
+    #[cfg(feature = "chrono")]
+    use chrono::{DateTime, Utc};
+    use core::time::Duration;
+
+    #[test]
+    fn spanned() {
+        // What a span aware deserializer hands to the visitor.
+        let source = "[job]\ntimeout = \"1m\"\n";
+        let value = serde_json::json!({ START: 16, END: 20, VALUE: "1m" });
+        let timeout = Spanned::<Duration>::deserialize(value).unwrap();
+        assert_eq!(*timeout, Duration::from_secs(60));
+        assert_eq!(timeout.span(), Some(16..20));
+        assert_eq!(&source[timeout.span().unwrap()], "\"1m\"");
+        assert_eq!(timeout.line_col(source), Some((2, 11)));
+        assert_eq!(serde_json::to_string(&timeout).unwrap(), "\"1m\"");
+    }
+
+    #[test]
+    fn errors() {
+        let value = serde_json::json!({ START: 0, END: 4, VALUE: "1 fortnight" });
+        assert!(Spanned::<Duration>::deserialize(value).is_err());
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn timestamp_errors() {
+        let d: de::value::StrDeserializer<de::value::Error> = "yesterday".into_deserializer();
+        let err = Spanned::<DateTime<Utc>>::deserialize(d).unwrap_err();
+        assert!(err.to_string().contains("expected a timestamp"));
+    }
+}
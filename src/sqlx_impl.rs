@@ -0,0 +1,152 @@
+//! `sqlx` support for Postgres, enabled with the `sqlx` feature.
+//!
+//! Durations map to `interval`. Since a month has no fixed length, intervals
+//! with a month component are rejected on decode, as are negative ones.
+//! The day and time parts may have opposite signs, as in `1 day -1 hour`;
+//! only their total has to be positive or zero.
+//! Timestamps map to `timestamptz`.
+
+use core::convert::TryFrom;
+use core::time::Duration;
+
+#[cfg(feature = "chrono")]
+use chrono::{DateTime, Utc};
+use sqlx::encode::IsNull;
+use sqlx::error::BoxDynError;
+use sqlx::postgres::types::PgInterval;
+use sqlx::postgres::{PgArgumentBuffer, PgTypeInfo, PgValueRef, Postgres};
+use sqlx::{Decode, Encode, Type};
+
+use super::{HumanDuration, Serde};
+#[cfg(feature = "chrono")]
+use super::HumanTimestamp;
+
+const MICROS_PER_DAY: i128 = 86_400_000_000;
+
+impl Type<Postgres> for Serde<Duration> {
+    fn type_info() -> PgTypeInfo {
+        <PgInterval as Type<Postgres>>::type_info()
+    }
+}
+
+impl Encode<'_, Postgres> for Serde<Duration> {
+    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> Result<IsNull, BoxDynError> {
+        let interval = PgInterval::try_from(self.0)?;
+        <PgInterval as Encode<Postgres>>::encode_by_ref(&interval, buf)
+    }
+}
+
+impl<'r> Decode<'r, Postgres> for Serde<Duration> {
+    fn decode(value: PgValueRef<'r>) -> Result<Self, BoxDynError> {
+        let interval = <PgInterval as Decode<Postgres>>::decode(value)?;
+        from_interval(interval).map(Serde)
+    }
+}
+
+/// The duration of `interval`, if it has no month component and its days
+/// and microseconds add up to a duration that is not negative.
+fn from_interval(interval: PgInterval) -> Result<Duration, BoxDynError> {
+    if interval.months != 0 {
+        return Err("interval with a month component has no fixed duration".into());
+    }
+    let micros = i128::from(interval.days) * MICROS_PER_DAY + i128::from(interval.microseconds);
+    if micros < 0 {
+        return Err("negative interval".into());
+    }
+    u64::try_from(micros)
+        .map(Duration::from_micros)
+        .map_err(|_| "interval overflows a duration".into())
+}
+
+impl Type<Postgres> for HumanDuration {
+    fn type_info() -> PgTypeInfo {
+        <Serde<Duration> as Type<Postgres>>::type_info()
+    }
+}
+
+impl Encode<'_, Postgres> for HumanDuration {
+    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> Result<IsNull, BoxDynError> {
+        Serde::from(**self).encode_by_ref(buf)
+    }
+}
+
+impl<'r> Decode<'r, Postgres> for HumanDuration {
+    fn decode(value: PgValueRef<'r>) -> Result<Self, BoxDynError> {
+        <Serde<Duration> as Decode<Postgres>>::decode(value)
+            .map(|d| HumanDuration::from(d.into_inner()))
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl Type<Postgres> for Serde<DateTime<Utc>> {
+    fn type_info() -> PgTypeInfo {
+        <DateTime<Utc> as Type<Postgres>>::type_info()
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl Encode<'_, Postgres> for Serde<DateTime<Utc>> {
+    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> Result<IsNull, BoxDynError> {
+        <DateTime<Utc> as Encode<Postgres>>::encode_by_ref(&self.0, buf)
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl<'r> Decode<'r, Postgres> for Serde<DateTime<Utc>> {
+    fn decode(value: PgValueRef<'r>) -> Result<Self, BoxDynError> {
+        <DateTime<Utc> as Decode<Postgres>>::decode(value).map(Serde)
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl Type<Postgres> for HumanTimestamp {
+    fn type_info() -> PgTypeInfo {
+        <DateTime<Utc> as Type<Postgres>>::type_info()
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl Encode<'_, Postgres> for HumanTimestamp {
+    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> Result<IsNull, BoxDynError> {
+        <DateTime<Utc> as Encode<Postgres>>::encode_by_ref(self, buf)
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl<'r> Decode<'r, Postgres> for HumanTimestamp {
+    fn decode(value: PgValueRef<'r>) -> Result<Self, BoxDynError> {
+        <DateTime<Utc> as Decode<Postgres>>::decode(value).map(HumanTimestamp::from)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloc::string::ToString;
+
+    fn interval(months: i32, days: i32, microseconds: i64) -> PgInterval {
+        PgInterval { months, days, microseconds }
+    }
+
+    #[test]
+    fn intervals() {
+        let d = from_interval(interval(0, 1, 90_000_000)).unwrap();
+        assert_eq!(d, Duration::from_secs(86_490));
+        let d = Duration::from_millis(90_500);
+        assert_eq!(from_interval(PgInterval::try_from(d).unwrap()).unwrap(), d);
+
+        let err = from_interval(interval(1, 0, 0)).unwrap_err();
+        assert_eq!(err.to_string(), "interval with a month component has no fixed duration");
+        let d = from_interval(interval(0, 1, -3_600_000_000)).unwrap();
+        assert_eq!(d, Duration::from_secs(82_800));
+        let d = from_interval(interval(0, -1, 90_000_000_000)).unwrap();
+        assert_eq!(d, Duration::from_secs(3_600));
+
+        let err = from_interval(interval(0, -1, 0)).unwrap_err();
+        assert_eq!(err.to_string(), "negative interval");
+        let err = from_interval(interval(0, 1, -86_400_000_001)).unwrap_err();
+        assert_eq!(err.to_string(), "negative interval");
+        let err = from_interval(interval(0, i32::MAX, i64::MAX)).unwrap_err();
+        assert_eq!(err.to_string(), "interval overflows a duration");
+    }
+}
@@ -0,0 +1,354 @@
+//! Timestamp formats given by a chrono strftime pattern, generated with
+//! [`strftime!`].
+//!
+//! Legacy systems often exchange timestamps in a fixed layout such as
+//! `20180511182830`. The generated module writes timestamps with the pattern
+//! and reads them back. A pattern with an offset (`%z` and the like) keeps
+//! it; otherwise timestamps are written in UTC and read as UTC, and a
+//! pattern without a time of day reads midnight.
+//!
+//! More patterns may follow the first. They are tried in order on
+//! deserialization, the first match winning, while serialization always uses
+//! the first, as tolerant readers of several legacy layouts do.
+//!
+//! The patterns are checked at compile time: an unknown `%` specifier fails
+//! the build rather than every call.
+//!
+//! # Example
+//!
+//! ```
+//! use chrono::{DateTime, Utc};
+//! use serde::{Serialize, Deserialize};
+//!
+//! humantime_serde::strftime!(pub mod compact_ts, "%Y%m%d%H%M%S");
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Record {
+//!     #[serde(with = "compact_ts")]
+//!     at: DateTime<Utc>,
+//! }
+//!
+//! let record: Record = serde_json::from_str(r#"{"at": "20180511182830"}"#).unwrap();
+//! assert_eq!(record.at.timestamp(), 1526063310);
+//! assert_eq!(serde_json::to_string(&record).unwrap(), r#"{"at":"20180511182830"}"#);
+//!
+//! humantime_serde::strftime!(pub mod tolerant, "%Y-%m-%d %H:%M:%S", "%d/%m/%Y %H:%M");
+//!
+//! #[derive(Deserialize)]
+//! struct Row {
+//!     #[serde(with = "tolerant")]
+//!     at: DateTime<Utc>,
+//! }
+//!
+//! let row: Row = serde_json::from_str(r#"{"at": "11/05/2018 18:28"}"#).unwrap();
+//! assert_eq!(row.at.timestamp(), 1526063280);
+//! ```
+//!
+//! ```compile_fail
+//! humantime_serde::strftime!(pub mod broken, "%Y%Q");
+//! ```
+//!
+//! [`strftime!`]: crate::strftime
+
+use core::fmt;
+
+use chrono::format::ParseErrorKind;
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
+use serde::{de, Deserializer, Serializer};
+
+/// Returns whether `pattern` only uses specifiers chrono knows.
+pub const fn is_valid_pattern(pattern: &str) -> bool {
+    let b = pattern.as_bytes();
+    let mut i = 0;
+    while i < b.len() {
+        if b[i] != b'%' {
+            i += 1;
+            continue;
+        }
+        i += 1;
+        if i < b.len() && matches!(b[i], b'-' | b'0' | b'_') {
+            i += 1;
+        }
+        if i == b.len() {
+            return false;
+        }
+        // Fractional seconds: %.f, %.3f, %3f and so on.
+        if b[i] == b'.' || matches!(b[i], b'3' | b'6' | b'9') {
+            if b[i] == b'.' {
+                i += 1;
+            }
+            if i < b.len() && matches!(b[i], b'3' | b'6' | b'9') {
+                i += 1;
+            }
+            if i == b.len() || b[i] != b'f' {
+                return false;
+            }
+        }
+        // Offsets: %:z, %::z, %:::z and %#z.
+        let mut colons = 0;
+        while i < b.len() && b[i] == b':' && colons < 3 {
+            colons += 1;
+            i += 1;
+        }
+        if colons == 0 && i < b.len() && b[i] == b'#' {
+            i += 1;
+            colons = 1;
+        }
+        if i == b.len() || (colons > 0 && b[i] != b'z') {
+            return false;
+        }
+        let known = matches!(
+            b[i],
+            b'Y' | b'C' | b'y' | b'q' | b'm' | b'b' | b'B' | b'h' | b'd' | b'e' | b'a' | b'A'
+                | b'w' | b'u' | b'U' | b'W' | b'G' | b'g' | b'V' | b'j' | b'D' | b'x' | b'F'
+                | b'v' | b'H' | b'k' | b'I' | b'l' | b'P' | b'p' | b'M' | b'S' | b'f' | b'R'
+                | b'T' | b'X' | b'r' | b'Z' | b'z' | b'c' | b'+' | b's' | b't' | b'n' | b'%'
+        );
+        if !known {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+/// Parses `s` with `pattern`, as described in the [module](self) docs.
+pub fn parse(pattern: &str, s: &str) -> Result<DateTime<FixedOffset>, crate::Error> {
+    let err = match DateTime::parse_from_str(s, pattern) {
+        Ok(t) => return Ok(t),
+        Err(err) => err,
+    };
+    if err.kind() != ParseErrorKind::NotEnough {
+        return Err(err.into());
+    }
+    let naive = match NaiveDateTime::parse_from_str(s, pattern) {
+        Ok(t) => t,
+        Err(err) if err.kind() == ParseErrorKind::NotEnough => {
+            NaiveDate::parse_from_str(s, pattern)?.and_time(NaiveTime::default())
+        }
+        Err(err) => return Err(err.into()),
+    };
+    Ok(naive.and_utc().fixed_offset())
+}
+
+/// Serializes a `DateTime<Tz>` with `pattern`.
+///
+/// The timestamp keeps its offset if the pattern writes one, and is
+/// converted to UTC otherwise.
+pub fn serialize<Tz, S>(pattern: &str, t: &DateTime<Tz>, s: S) -> Result<S::Ok, S::Error>
+where
+    Tz: TimeZone,
+    Tz::Offset: fmt::Display,
+    S: Serializer,
+{
+    if has_offset(pattern) {
+        s.collect_str(&t.format(pattern))
+    } else {
+        s.collect_str(&t.with_timezone(&Utc).format(pattern))
+    }
+}
+
+fn has_offset(pattern: &str) -> bool {
+    pattern
+        .split('%')
+        .skip(1)
+        .any(|spec| spec.trim_start_matches([':', '#']).starts_with('z'))
+}
+
+/// Parses `s` with the first of `patterns` that matches.
+///
+/// If none does, the error is the one for the first pattern.
+pub fn parse_any(patterns: &[&str], s: &str) -> Result<DateTime<FixedOffset>, crate::Error> {
+    let mut first = None;
+    for pattern in patterns {
+        match parse(pattern, s) {
+            Ok(t) => return Ok(t),
+            Err(err) => {
+                first.get_or_insert(err);
+            }
+        }
+    }
+    Err(first.unwrap_or(crate::Error::Empty))
+}
+
+/// Deserializes a `DateTime<Tz>` with `pattern`.
+pub fn deserialize<'a, Tz, D>(pattern: &str, d: D) -> Result<DateTime<Tz>, D::Error>
+where
+    Tz: TimeZone,
+    DateTime<Tz>: From<DateTime<FixedOffset>>,
+    D: Deserializer<'a>,
+{
+    deserialize_any(core::slice::from_ref(&pattern), d)
+}
+
+/// Deserializes a `DateTime<Tz>` with the first of `patterns` that matches.
+pub fn deserialize_any<'a, Tz, D>(patterns: &[&str], d: D) -> Result<DateTime<Tz>, D::Error>
+where
+    Tz: TimeZone,
+    DateTime<Tz>: From<DateTime<FixedOffset>>,
+    D: Deserializer<'a>,
+{
+    struct V<'p>(&'p [&'p str]);
+
+    impl de::Visitor<'_> for V<'_> {
+        type Value = DateTime<FixedOffset>;
+
+        fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+            fmt.write_str("a timestamp formatted as ")?;
+            for (i, pattern) in self.0.iter().enumerate() {
+                if i > 0 {
+                    fmt.write_str(" or ")?;
+                }
+                write!(fmt, "{:?}", pattern)?;
+            }
+            Ok(())
+        }
+
+        fn visit_str<E>(self, v: &str) -> Result<DateTime<FixedOffset>, E>
+        where
+            E: de::Error,
+        {
+            crate::check_len(v, crate::DEFAULT_MAX_LEN, "a timestamp")?;
+            parse_any(self.0, v).map_err(|err| {
+                E::invalid_value(de::Unexpected::Str(v), &crate::Because(&self, &err))
+            })
+        }
+    }
+
+    d.deserialize_str(V(patterns)).map(DateTime::from)
+}
+
+/// Generates a module for `#[serde(with = "...")]` formatting timestamps
+/// with a chrono strftime pattern, and reading them with it or any of the
+/// patterns after it.
+///
+/// The generated module has an `option` submodule for `Option` fields.
+///
+/// See the [`strftime`](mod@crate::strftime) module for details.
+#[macro_export]
+macro_rules! strftime {
+    ($(#[$attr:meta])* $vis:vis mod $name:ident, $pattern:literal $(, $more:literal)* $(,)?) => {
+        $(#[$attr])*
+        $vis mod $name {
+            const PATTERN: &str = $pattern;
+            const PATTERNS: &[&str] = &[$pattern $(, $more)*];
+
+            const _: () = {
+                let mut i = 0;
+                while i < PATTERNS.len() {
+                    ::core::assert!(
+                        $crate::strftime::is_valid_pattern(PATTERNS[i]),
+                        "invalid strftime pattern",
+                    );
+                    i += 1;
+                }
+            };
+
+            /// Serializes a `DateTime<Tz>` with the pattern.
+            pub fn serialize<Tz, S>(
+                t: &$crate::re::chrono::DateTime<Tz>,
+                s: S,
+            ) -> ::core::result::Result<S::Ok, S::Error>
+            where
+                Tz: $crate::re::chrono::TimeZone,
+                Tz::Offset: ::core::fmt::Display,
+                S: $crate::re::serde::Serializer,
+            {
+                $crate::strftime::serialize(PATTERN, t, s)
+            }
+
+            /// Deserializes a `DateTime<Tz>` with the first pattern that
+            /// matches.
+            pub fn deserialize<'a, Tz, D>(
+                d: D,
+            ) -> ::core::result::Result<$crate::re::chrono::DateTime<Tz>, D::Error>
+            where
+                Tz: $crate::re::chrono::TimeZone,
+                $crate::re::chrono::DateTime<Tz>: ::core::convert::From<
+                    $crate::re::chrono::DateTime<$crate::re::chrono::FixedOffset>,
+                >,
+                D: $crate::re::serde::Deserializer<'a>,
+            {
+                $crate::strftime::deserialize_any(PATTERNS, d)
+            }
+
+            $crate::__option_module!([Tz] $crate::re::chrono::DateTime<Tz>, [
+                Tz: $crate::re::chrono::TimeZone,
+                Tz::Offset: ::core::fmt::Display,
+                $crate::re::chrono::DateTime<Tz>: ::core::convert::From<
+                    $crate::re::chrono::DateTime<$crate::re::chrono::FixedOffset>,
+                >,
+            ]);
+        }
+    };
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde::{Deserialize, Serialize};
+
+    crate::strftime!(mod compact, "%Y%m%d%H%M%S");
+    crate::strftime!(mod with_offset, "%d/%m/%Y %H:%M %z");
+    crate::strftime!(mod date, "%Y-%m-%d");
+    crate::strftime!(mod tolerant, "%Y-%m-%dT%H:%M:%S%:z", "%Y%m%d%H%M%S", "%d/%m/%Y",);
+
+    #[derive(Debug, Serialize, Deserialize)]
+    struct Foo {
+        #[serde(with = "compact")]
+        compact: DateTime<Utc>,
+        #[serde(with = "with_offset")]
+        offset: DateTime<FixedOffset>,
+        #[serde(with = "date::option")]
+        date: Option<DateTime<Utc>>,
+    }
+
+    #[test]
+    fn strftime() {
+        let json = r#"{"compact": "20180511182830", "offset": "11/05/2018 20:28 +0200", "date": "2018-05-11"}"#;
+        let foo: Foo = serde_json::from_str(json).unwrap();
+        assert_eq!(foo.compact.timestamp(), 1526063310);
+        assert_eq!(foo.offset.timestamp(), 1526063280);
+        assert_eq!(foo.date.unwrap().timestamp(), 1525996800);
+        assert_eq!(
+            serde_json::to_string(&foo).unwrap(),
+            r#"{"compact":"20180511182830","offset":"11/05/2018 20:28 +0200","date":"2018-05-11"}"#
+        );
+        assert!(serde_json::from_str::<Foo>(r#"{"compact": "2018", "offset": "", "date": null}"#).is_err());
+    }
+
+    #[test]
+    fn tolerant() {
+        #[derive(Debug, Serialize, Deserialize)]
+        struct Foo {
+            #[serde(with = "tolerant")]
+            at: DateTime<Utc>,
+        }
+
+        for (json, ts) in [
+            (r#"{"at": "2018-05-11T20:28:30+02:00"}"#, 1526063310),
+            (r#"{"at": "20180511182830"}"#, 1526063310),
+            (r#"{"at": "11/05/2018"}"#, 1525996800),
+        ] {
+            let foo: Foo = serde_json::from_str(json).unwrap();
+            assert_eq!(foo.at.timestamp(), ts);
+        }
+        let foo: Foo = serde_json::from_str(r#"{"at": "20180511182830"}"#).unwrap();
+        assert_eq!(serde_json::to_string(&foo).unwrap(), r#"{"at":"2018-05-11T18:28:30+00:00"}"#);
+
+        let err = serde_json::from_str::<Foo>(r#"{"at": "2018"}"#).unwrap_err();
+        assert!(err.to_string().contains(
+            r#"expected a timestamp formatted as "%Y-%m-%dT%H:%M:%S%:z" or "%Y%m%d%H%M%S" or "%d/%m/%Y""#
+        ));
+    }
+
+    #[test]
+    fn patterns() {
+        for p in ["", "%Y", "%Y-%m-%dT%H:%M:%S%.3f%:z", "%-d %_m %0H", "%s", "%%", "%3f", "%#z", "%:::z"] {
+            assert!(is_valid_pattern(p), "{:?}", p);
+        }
+        for p in ["%", "%Q", "%.x", "%3", "%:y", "%#", "%-"] {
+            assert!(!is_valid_pattern(p), "{:?}", p);
+        }
+    }
+}
@@ -0,0 +1,88 @@
+//! A `with` module for `DateTime<Utc>` fields rejecting timestamps with a
+//! non-zero offset.
+//!
+//! By default a timestamp such as `2018-05-11T20:28:30+02:00` is converted
+//! to UTC. Protocols mandating normalized UTC may want such input flagged
+//! instead; this module only accepts `Z` or a zero offset.
+//!
+//! # Example
+//!
+//! ```
+//! use chrono::{DateTime, Utc};
+//! use serde::{Serialize, Deserialize};
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Event {
+//!     #[serde(with = "humantime_serde::strict_utc")]
+//!     at: DateTime<Utc>,
+//! }
+//!
+//! let event: Event = serde_json::from_str(r#"{"at": "2018-05-11T18:28:30Z"}"#).unwrap();
+//! assert_eq!(event.at.timestamp(), 1526063310);
+//!
+//! let json = r#"{"at": "2018-05-11T20:28:30+02:00"}"#;
+//! let err = serde_json::from_str::<Event>(json).unwrap_err();
+//! assert!(err.to_string().starts_with("2018-05-11T20:28:30+02:00 has offset +02:00"));
+//! ```
+
+use chrono::{DateTime, FixedOffset, Utc};
+use serde::{de, Deserialize, Deserializer, Serializer};
+
+use super::format::Ref;
+use super::Serde;
+
+/// Serializes a `DateTime<Utc>`.
+pub fn serialize<S>(d: &DateTime<Utc>, s: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    crate::serialize(d, s)
+}
+
+/// Deserializes a `DateTime<Utc>`, rejecting it unless its offset is zero.
+pub fn deserialize<'a, D>(d: D) -> Result<DateTime<Utc>, D::Error>
+where
+    D: Deserializer<'a>,
+{
+    let v = Serde::<DateTime<FixedOffset>>::deserialize(d)?.into_inner();
+    if v.offset().local_minus_utc() == 0 {
+        Ok(v.to_utc())
+    } else {
+        Err(de::Error::custom(format_args!(
+            "{} has offset {}, expected a UTC timestamp",
+            Ref(&v),
+            v.offset(),
+        )))
+    }
+}
+
+crate::__option_module!([] ::chrono::DateTime<::chrono::Utc>, []);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde::Serialize;
+
+    #[derive(Serialize, Deserialize)]
+    struct Foo {
+        #[serde(with = "super")]
+        at: DateTime<Utc>,
+        #[serde(with = "super::option")]
+        maybe: Option<DateTime<Utc>>,
+    }
+
+    #[test]
+    fn strict_utc() {
+        let json = r#"{"at": "2018-05-11T18:28:30Z", "maybe": "2018-05-11T18:28:30+00:00"}"#;
+        let foo: Foo = serde_json::from_str(json).unwrap();
+        assert_eq!(foo.at.timestamp(), 1526063310);
+        assert_eq!(foo.maybe, Some(foo.at));
+        assert_eq!(
+            serde_json::to_string(&foo).unwrap(),
+            r#"{"at":"2018-05-11T18:28:30Z","maybe":"2018-05-11T18:28:30Z"}"#
+        );
+
+        let json = r#"{"at": "2018-05-11T18:28:30Z", "maybe": "2018-05-11T18:28:30-01:00"}"#;
+        assert!(serde_json::from_str::<Foo>(json).is_err());
+    }
+}
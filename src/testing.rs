@@ -0,0 +1,188 @@
+//! Helpers asserting the `serde_test` token sequences produced for the
+//! types supported by `humantime_serde`.
+//!
+//! Requires the `testing` feature.
+//!
+//! # Example
+//!
+//! ```
+//! use core::time::Duration;
+//! use humantime_serde::testing;
+//!
+//! testing::assert_tokens(Duration::from_secs(90), "1m 30s");
+//! testing::assert_de_tokens("90 seconds", Duration::from_secs(90));
+//! testing::assert_option_tokens(Some(Duration::from_secs(15)), Some("15s"));
+//! testing::assert_de_error::<Duration>(
+//!     "15 parsecs",
+//!     r#"invalid value: string "15 parsecs", expected a duration: unknown time unit "parsecs", supported units: ns, us, ms, sec, min, hours, days, weeks, months, years (and few variations)"#,
+//! );
+//! ```
+//!
+//! Whole structs can be checked with [`assert_roundtrip`], which runs the
+//! value through both a human-readable (JSON) and a binary (bincode) format:
+//!
+//! ```
+//! use core::time::Duration;
+//! use serde::{Serialize, Deserialize};
+//! use humantime_serde::testing;
+//!
+//! #[derive(Debug, PartialEq, Serialize, Deserialize)]
+//! struct Foo {
+//!     #[serde(with = "humantime_serde")]
+//!     timeout: Duration,
+//! }
+//!
+//! testing::assert_roundtrip(Foo { timeout: Duration::from_millis(1500) });
+//! testing::assert_parses("1m 30s", Duration::from_secs(90));
+//! ```
+
+use core::fmt;
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_test::Configure;
+
+use super::Serde;
+
+pub use serde_test::Token;
+
+/// Asserts that `value` serializes to the string `s` and that `s`
+/// deserializes back to `value`.
+pub fn assert_tokens<T>(value: T, s: &'static str)
+where
+    Serde<T>: Serialize + for<'de> Deserialize<'de> + PartialEq + fmt::Debug,
+{
+    serde_test::assert_tokens(&Serde::from(value), &[Token::Str(s)]);
+}
+
+/// Asserts that `value` serializes to the string `s`.
+pub fn assert_ser_tokens<T>(value: T, s: &'static str)
+where
+    Serde<T>: Serialize,
+{
+    serde_test::assert_ser_tokens(&Serde::from(value), &[Token::Str(s)]);
+}
+
+/// Asserts that the string `s` deserializes to `expected`.
+pub fn assert_de_tokens<T>(s: &'static str, expected: T)
+where
+    Serde<T>: for<'de> Deserialize<'de> + PartialEq + fmt::Debug,
+{
+    serde_test::assert_de_tokens(&Serde::from(expected), &[Token::Str(s)]);
+}
+
+/// Asserts that deserializing the string `s` fails with the message `error`.
+pub fn assert_de_error<T>(s: &'static str, error: &str)
+where
+    Serde<T>: for<'de> Deserialize<'de>,
+{
+    serde_test::assert_de_tokens_error::<Serde<T>>(&[Token::Str(s)], error);
+}
+
+/// Asserts that an optional `value` round-trips through the string `s`,
+/// with `None` on either side standing for a serialized `None`.
+pub fn assert_option_tokens<T>(value: Option<T>, s: Option<&'static str>)
+where
+    Serde<Option<T>>: Serialize + for<'de> Deserialize<'de> + PartialEq + fmt::Debug,
+{
+    match s {
+        Some(s) => serde_test::assert_tokens(
+            &Serde::from(value),
+            &[Token::Some, Token::Str(s)],
+        ),
+        None => serde_test::assert_tokens(&Serde::from(value), &[Token::None]),
+    }
+}
+
+/// Asserts that `value` survives a round trip through both JSON and bincode.
+///
+/// Panics if either format rejects the value or if what comes back differs
+/// from `value`, e.g. because a timestamp lost its sub-second precision.
+pub fn assert_roundtrip<T>(value: T)
+where
+    T: Serialize + DeserializeOwned + PartialEq + fmt::Debug,
+{
+    let json = serde_json::to_string(&value).expect("failed to serialize to JSON");
+    let readable: T =
+        serde_json::from_str(&json).expect("failed to deserialize from JSON");
+    assert_eq!(readable, value, "JSON round trip through {}", json);
+
+    let bytes = bincode::serialize(&value).expect("failed to serialize to bincode");
+    let compact: T =
+        bincode::deserialize(&bytes).expect("failed to deserialize from bincode");
+    assert_eq!(compact, value, "bincode round trip");
+}
+
+/// Asserts that the string `s` deserializes to `expected` through both
+/// human-readable and compact deserializers.
+pub fn assert_parses<T>(s: &'static str, expected: T)
+where
+    T: Clone,
+    Serde<T>: for<'de> Deserialize<'de> + PartialEq + fmt::Debug,
+{
+    let expected = Serde::from(expected);
+    serde_test::assert_de_tokens(&expected.clone().readable(), &[Token::Str(s)]);
+    serde_test::assert_de_tokens(&expected.compact(), &[Token::Str(s)]);
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use core::time::Duration;
+    use chrono::{DateTime, Utc};
+
+    #[test]
+    fn duration() {
+        assert_tokens(Duration::from_secs(90), "1m 30s");
+        assert_ser_tokens(Duration::from_millis(1500), "1s 500ms");
+        assert_de_tokens("90 seconds", Duration::from_secs(90));
+        assert_de_error::<Duration>(
+            "15 parsecs",
+            r#"invalid value: string "15 parsecs", expected a duration: unknown time unit "parsecs", supported units: ns, us, ms, sec, min, hours, days, weeks, months, years (and few variations)"#,
+        );
+    }
+
+    #[test]
+    fn time() {
+        let time = DateTime::<Utc>::UNIX_EPOCH + Duration::new(1526063310, 0);
+        assert_tokens(time, "2018-05-11T18:28:30Z");
+        assert_de_tokens("2018-05-11T20:28:30+02:00", time);
+    }
+
+    #[test]
+    fn roundtrip() {
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct Foo {
+            #[serde(with = "crate")]
+            timeout: Duration,
+            #[serde(with = "crate", default)]
+            time: Option<DateTime<Utc>>,
+        }
+
+        assert_roundtrip(Foo {
+            timeout: Duration::from_millis(1500),
+            time: Some(DateTime::UNIX_EPOCH + Duration::new(1526063310, 0)),
+        });
+        assert_parses("1m 30s", Duration::from_secs(90));
+    }
+
+    #[test]
+    #[should_panic(expected = "JSON round trip")]
+    fn roundtrip_precision_loss() {
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct Foo {
+            #[serde(with = "crate")]
+            time: DateTime<Utc>,
+        }
+
+        assert_roundtrip(Foo {
+            time: DateTime::UNIX_EPOCH + Duration::new(1526063310, 500),
+        });
+    }
+
+    #[test]
+    fn option() {
+        assert_option_tokens(Some(Duration::from_secs(15)), Some("15s"));
+        assert_option_tokens::<Duration>(None, None);
+    }
+}
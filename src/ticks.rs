@@ -0,0 +1,215 @@
+//! Durations written as a whole number of ticks at a fixed tick rate.
+//!
+//! Simulations advancing in fixed steps configure their timers in ticks,
+//! while people would rather write `"2s"`. The functions here, generic over
+//! the tick rate `HZ`, read either a number of ticks or a humantime string,
+//! and write the number of ticks nearest to the duration. [`ticks!`]
+//! generates a module for `#[serde(with = "...")]` at a given rate.
+//!
+//! Ticks at rates not dividing a second evenly, such as 60Hz, are rounded
+//! to the nearest nanosecond, which still converts back to the same number
+//! of ticks.
+//!
+//! # Example
+//!
+//! ```
+//! use core::time::Duration;
+//! use serde::{Serialize, Deserialize};
+//!
+//! humantime_serde::ticks!(mod server_ticks: 20);
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Mob {
+//!     #[serde(with = "server_ticks")]
+//!     despawn_after: Duration,
+//!     #[serde(
+//!         serialize_with = "humantime_serde::ticks::serialize::<60, _>",
+//!         deserialize_with = "humantime_serde::ticks::deserialize::<60, _>"
+//!     )]
+//!     attack_cooldown: Duration,
+//! }
+//!
+//! let json = r#"{"despawn_after": "5m", "attack_cooldown": 30}"#;
+//! let mob: Mob = serde_json::from_str(json).unwrap();
+//! assert_eq!(mob.despawn_after, Duration::from_secs(300));
+//! assert_eq!(mob.attack_cooldown, Duration::from_millis(500));
+//!
+//! let json = serde_json::to_string(&mob).unwrap();
+//! assert_eq!(json, r#"{"despawn_after":6000,"attack_cooldown":30}"#);
+//! ```
+//!
+//! [`ticks!`]: macro@crate::ticks
+
+use core::convert::TryFrom;
+use core::fmt;
+use core::time::Duration;
+
+use serde::{de, ser, Deserializer, Serializer};
+
+use super::format::{HumantimeFormat, Ref};
+
+const NANOS_PER_SEC: u128 = 1_000_000_000;
+
+/// The duration of `ticks` ticks at `HZ` ticks a second, to the nearest
+/// nanosecond.
+///
+/// # Panics
+///
+/// Panics if `HZ` is zero.
+pub fn from_ticks<const HZ: u32>(ticks: u64) -> Duration {
+    let hz = u128::from(HZ);
+    let nanos = (u128::from(ticks) * NANOS_PER_SEC + hz / 2) / hz;
+    Duration::new((nanos / NANOS_PER_SEC) as u64, (nanos % NANOS_PER_SEC) as u32)
+}
+
+/// The number of ticks at `HZ` ticks a second nearest to `d`, or `None` if
+/// it does not fit in a `u64`.
+pub fn to_ticks<const HZ: u32>(d: Duration) -> Option<u64> {
+    let ticks = (d.as_nanos() * u128::from(HZ) + NANOS_PER_SEC / 2) / NANOS_PER_SEC;
+    u64::try_from(ticks).ok()
+}
+
+/// Serializes a `Duration` as the nearest number of ticks.
+pub fn serialize<const HZ: u32, S>(d: &Duration, s: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    match to_ticks::<HZ>(*d) {
+        Some(ticks) => s.serialize_u64(ticks),
+        None => Err(ser::Error::custom(format_args!(
+            "{} is too many ticks at {}Hz",
+            Ref(d),
+            HZ,
+        ))),
+    }
+}
+
+/// Deserializes a `Duration` from a number of ticks or a humantime string.
+pub fn deserialize<'a, const HZ: u32, D>(d: D) -> Result<Duration, D::Error>
+where
+    D: Deserializer<'a>,
+{
+    struct V<const HZ: u32>;
+
+    impl<const HZ: u32> de::Visitor<'_> for V<HZ> {
+        type Value = Duration;
+
+        fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+            write!(fmt, "a duration or a number of ticks at {}Hz", HZ)
+        }
+
+        fn visit_str<E>(self, v: &str) -> Result<Duration, E>
+        where
+            E: de::Error,
+        {
+            crate::check_len(v, crate::DEFAULT_MAX_LEN, Duration::EXPECTING)?;
+            Duration::parse(v).map_err(|err| {
+                let err = crate::DidYouMean(&err, Duration::suggestion(v, &err));
+                E::invalid_value(de::Unexpected::Str(v), &crate::Because(&self, &err))
+            })
+        }
+
+        fn visit_i64<E>(self, v: i64) -> Result<Duration, E>
+        where
+            E: de::Error,
+        {
+            u64::try_from(v)
+                .map(from_ticks::<HZ>)
+                .map_err(|_| E::invalid_value(de::Unexpected::Signed(v), &self))
+        }
+
+        fn visit_u64<E>(self, v: u64) -> Result<Duration, E>
+        where
+            E: de::Error,
+        {
+            Ok(from_ticks::<HZ>(v))
+        }
+    }
+
+    d.deserialize_any(V::<HZ>)
+}
+
+/// Generates a module for `#[serde(with = "...")]` writing durations as a
+/// number of ticks at a tick rate in hertz.
+///
+/// The generated module has an `option` submodule for `Option` fields.
+///
+/// See the [`ticks`](mod@crate::ticks) module for details.
+#[macro_export]
+macro_rules! ticks {
+    ($(#[$attr:meta])* $vis:vis mod $name:ident : $hz:expr) => {
+        $(#[$attr])*
+        $vis mod $name {
+            /// Serializes a `Duration` as the nearest number of ticks.
+            pub fn serialize<S>(
+                d: &::core::time::Duration,
+                s: S,
+            ) -> ::core::result::Result<S::Ok, S::Error>
+            where
+                S: $crate::re::serde::Serializer,
+            {
+                $crate::ticks::serialize::<{ $hz }, S>(d, s)
+            }
+
+            /// Deserializes a `Duration` from a number of ticks or a
+            /// humantime string.
+            pub fn deserialize<'a, D>(
+                d: D,
+            ) -> ::core::result::Result<::core::time::Duration, D::Error>
+            where
+                D: $crate::re::serde::Deserializer<'a>,
+            {
+                $crate::ticks::deserialize::<{ $hz }, D>(d)
+            }
+
+            $crate::__option_module!([] ::core::time::Duration, []);
+        }
+    };
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod test {
+    use super::*;
+    use alloc::string::ToString;
+    use serde::{Deserialize, Serialize};
+
+    crate::ticks!(mod sixty: 60);
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Timer {
+        #[serde(with = "sixty")]
+        period: Duration,
+        #[serde(with = "sixty::option", default)]
+        delay: Option<Duration>,
+    }
+
+    #[test]
+    fn conversions() {
+        assert_eq!(from_ticks::<60>(1), Duration::from_nanos(16_666_667));
+        assert_eq!(from_ticks::<60>(90), Duration::from_millis(1500));
+        assert_eq!(to_ticks::<60>(from_ticks::<60>(1)), Some(1));
+        assert_eq!(to_ticks::<60>(Duration::from_millis(10)), Some(1));
+        assert_eq!(to_ticks::<60>(Duration::from_millis(5)), Some(0));
+        assert_eq!(to_ticks::<1>(Duration::MAX), None);
+        for ticks in [0, 1, 59, 61, 1_000_003, u64::MAX / 60] {
+            assert_eq!(to_ticks::<60>(from_ticks::<60>(ticks)), Some(ticks));
+        }
+    }
+
+    #[test]
+    fn serde() {
+        let timer: Timer = serde_json::from_str(r#"{"period": "2s", "delay": 3}"#).unwrap();
+        assert_eq!(timer.period, Duration::from_secs(2));
+        assert_eq!(timer.delay, Some(Duration::from_millis(50)));
+        assert_eq!(serde_json::to_string(&timer).unwrap(), r#"{"period":120,"delay":3}"#);
+
+        let err = serde_json::from_str::<Timer>(r#"{"period": -1}"#).unwrap_err();
+        assert!(err.to_string().contains("expected a duration or a number of ticks at 60Hz"));
+        let err = serde_json::from_str::<Timer>(r#"{"period": "2 parsecs"}"#).unwrap_err();
+        assert!(err.to_string().contains("a duration or a number of ticks"));
+
+        let timer = Timer { period: Duration::MAX, delay: None };
+        let err = serde_json::to_string(&timer).unwrap_err();
+        assert!(err.to_string().ends_with("is too many ticks at 60Hz"));
+    }
+}
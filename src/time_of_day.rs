@@ -0,0 +1,259 @@
+use core::fmt;
+use core::ops::Deref;
+use core::str::FromStr;
+
+use chrono::{DateTime, NaiveDate, NaiveTime, TimeZone};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use super::format::{HumantimeFormat, Ref};
+use super::window::write_time;
+use super::{Ambiguity, Error, Serde};
+
+/// A `NaiveTime` parsed leniently from `"9am"`, `"9:30 pm"`, `"09:00"` or
+/// `"21:30:15"`, or from one of the keywords `noon`, `midnight` and
+/// `end of day`.
+///
+/// It is written back as `HH:MM`, or `HH:MM:SS` when the seconds are not
+/// zero. `12am` is midnight and `12pm` is noon. `end of day` is the last
+/// nanosecond before midnight, 23:59:59.999999999, and is written back as
+/// `end of day` since `HH:MM:SS` would lose its fraction.
+///
+/// # Example
+///
+/// ```
+/// use chrono::{NaiveDate, Utc};
+/// use humantime_serde::TimeOfDay;
+///
+/// let report_at: TimeOfDay = "9:30pm".parse().unwrap();
+/// assert_eq!(report_at.to_string(), "21:30");
+///
+/// let date = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+/// let t = report_at.on(date, &Utc).unwrap();
+/// assert_eq!(t.to_rfc3339(), "2024-01-01T21:30:00+00:00");
+/// ```
+#[derive(Copy, Clone, Debug, Default, Eq, Hash, PartialEq, Ord, PartialOrd)]
+pub struct TimeOfDay(NaiveTime);
+
+impl TimeOfDay {
+    /// Wraps a `NaiveTime`.
+    pub const fn new(t: NaiveTime) -> TimeOfDay {
+        TimeOfDay(t)
+    }
+
+    /// Consumes the `TimeOfDay`, returning the inner `NaiveTime`.
+    pub fn into_inner(self) -> NaiveTime {
+        self.0
+    }
+
+    /// The point in time at this time of day on `date` in `tz`.
+    ///
+    /// Returns the earlier instant if the time occurs twice on that day,
+    /// and `None` if it is skipped by a change of offset.
+    pub fn on<Tz>(&self, date: NaiveDate, tz: &Tz) -> Option<DateTime<Tz>>
+    where
+        Tz: TimeZone,
+    {
+        tz.from_local_datetime(&date.and_time(self.0)).earliest()
+    }
+
+    /// The point in time at this time of day on `date` in `tz`, picking
+    /// between two instants according to `ambiguity`.
+    pub fn resolve_on<Tz>(
+        &self,
+        date: NaiveDate,
+        tz: &Tz,
+        ambiguity: Ambiguity,
+    ) -> Result<DateTime<Tz>, Error>
+    where
+        Tz: TimeZone,
+    {
+        ambiguity.resolve(tz, &date.and_time(self.0))
+    }
+}
+
+/// The error returned when parsing a [`TimeOfDay`] fails.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct InvalidTimeOfDay;
+
+impl fmt::Display for InvalidTimeOfDay {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("invalid time of day, expected e.g. 09:00 or 9am")
+    }
+}
+
+impl core::error::Error for InvalidTimeOfDay {}
+
+/// The time named by a keyword, if `s` is one.
+pub(crate) fn keyword(s: &str) -> Option<NaiveTime> {
+    if s.eq_ignore_ascii_case("noon") {
+        NaiveTime::from_hms_opt(12, 0, 0)
+    } else if s.eq_ignore_ascii_case("midnight") {
+        NaiveTime::from_hms_opt(0, 0, 0)
+    } else if s.eq_ignore_ascii_case("end of day") {
+        end_of_day()
+    } else {
+        None
+    }
+}
+
+fn end_of_day() -> Option<NaiveTime> {
+    NaiveTime::from_hms_nano_opt(23, 59, 59, 999_999_999)
+}
+
+impl HumantimeFormat for TimeOfDay {
+    const EXPECTING: &'static str = "a time of day";
+
+    type Err = InvalidTimeOfDay;
+
+    fn parse(s: &str) -> Result<TimeOfDay, InvalidTimeOfDay> {
+        let s = s.trim();
+        if let Some(t) = keyword(s) {
+            return Ok(TimeOfDay(t));
+        }
+        let strip = |suffix: &str| {
+            s.len()
+                .checked_sub(suffix.len())
+                .filter(|&i| s.is_char_boundary(i) && s[i..].eq_ignore_ascii_case(suffix))
+                .map(|i| s[..i].trim_end())
+        };
+        let (clock, pm) = match (strip("am"), strip("pm")) {
+            (Some(clock), _) => (clock, Some(false)),
+            (_, Some(clock)) => (clock, Some(true)),
+            _ => (s, None),
+        };
+        let mut fields = clock.split(':');
+        let mut field = |required: bool| match fields.next() {
+            Some(f) if !f.is_empty() && f.len() <= 2 && f.bytes().all(|b| b.is_ascii_digit()) => {
+                f.parse::<u32>().map_err(|_| InvalidTimeOfDay)
+            }
+            None if !required => Ok(0),
+            _ => Err(InvalidTimeOfDay),
+        };
+        let mut hour = field(true)?;
+        let minute = field(pm.is_none())?;
+        let second = field(false)?;
+        if fields.next().is_some() {
+            return Err(InvalidTimeOfDay);
+        }
+        if let Some(pm) = pm {
+            if hour == 0 || hour > 12 {
+                return Err(InvalidTimeOfDay);
+            }
+            hour = hour % 12 + if pm { 12 } else { 0 };
+        }
+        NaiveTime::from_hms_opt(hour, minute, second)
+            .map(TimeOfDay)
+            .ok_or(InvalidTimeOfDay)
+    }
+
+    fn format(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if Some(self.0) == end_of_day() {
+            return f.write_str("end of day");
+        }
+        write_time(self.0, f)
+    }
+}
+
+impl fmt::Display for TimeOfDay {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.format(f)
+    }
+}
+
+impl FromStr for TimeOfDay {
+    type Err = InvalidTimeOfDay;
+
+    fn from_str(s: &str) -> Result<TimeOfDay, InvalidTimeOfDay> {
+        TimeOfDay::parse(s)
+    }
+}
+
+impl Deref for TimeOfDay {
+    type Target = NaiveTime;
+
+    fn deref(&self) -> &NaiveTime {
+        &self.0
+    }
+}
+
+impl From<NaiveTime> for TimeOfDay {
+    fn from(t: NaiveTime) -> TimeOfDay {
+        TimeOfDay(t)
+    }
+}
+
+impl From<TimeOfDay> for NaiveTime {
+    fn from(t: TimeOfDay) -> NaiveTime {
+        t.0
+    }
+}
+
+impl Serialize for TimeOfDay {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        Ref(self).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for TimeOfDay {
+    fn deserialize<D>(d: D) -> Result<TimeOfDay, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Serde::deserialize(d).map(Serde::into_inner)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloc::string::ToString;
+    use chrono::{FixedOffset, Timelike};
+
+    fn parse(s: &str) -> Result<(u32, u32, u32), InvalidTimeOfDay> {
+        s.parse::<TimeOfDay>().map(|t| (t.hour(), t.minute(), t.second()))
+    }
+
+    #[test]
+    fn lenient() {
+        assert_eq!(parse("9am"), Ok((9, 0, 0)));
+        assert_eq!(parse("9:05 PM"), Ok((21, 5, 0)));
+        assert_eq!(parse("12am"), Ok((0, 0, 0)));
+        assert_eq!(parse("12pm"), Ok((12, 0, 0)));
+        assert_eq!(parse("09:00"), Ok((9, 0, 0)));
+        assert_eq!(parse("21:30:15"), Ok((21, 30, 15)));
+        assert_eq!(parse("13pm"), Err(InvalidTimeOfDay));
+        assert_eq!(parse("9"), Err(InvalidTimeOfDay));
+        assert_eq!(parse("24:00"), Err(InvalidTimeOfDay));
+        assert_eq!(parse("9:00:00:00"), Err(InvalidTimeOfDay));
+    }
+
+    #[test]
+    fn keywords() {
+        assert_eq!(parse("noon"), Ok((12, 0, 0)));
+        assert_eq!(parse(" Midnight "), Ok((0, 0, 0)));
+        let t: TimeOfDay = "end of day".parse().unwrap();
+        assert_eq!(t.nanosecond(), 999_999_999);
+        assert_eq!(t.to_string(), "end of day");
+        assert_eq!("noon".parse::<TimeOfDay>().unwrap().to_string(), "12:00");
+        assert_eq!(parse("end  of day"), Err(InvalidTimeOfDay));
+    }
+
+    #[test]
+    fn serde() {
+        let t: TimeOfDay = serde_json::from_str("\"6:45:30 am\"").unwrap();
+        assert_eq!(serde_json::to_string(&t).unwrap(), "\"06:45:30\"");
+        let err = serde_json::from_str::<TimeOfDay>("\"noonish\"").unwrap_err();
+        assert!(err.to_string().contains("expected a time of day"));
+    }
+
+    #[test]
+    fn on() {
+        let t: TimeOfDay = "9am".parse().unwrap();
+        let date = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let tz = FixedOffset::east_opt(3600).unwrap();
+        assert_eq!(t.on(date, &tz).unwrap().to_rfc3339(), "2024-01-01T09:00:00+01:00");
+    }
+}
@@ -0,0 +1,187 @@
+use core::fmt;
+use core::str::FromStr;
+use core::time::Duration;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use super::format::{HumantimeFormat, Ref};
+use super::Serde;
+
+/// A timeout that can be switched off, written as a duration or as `"off"`
+/// or `"none"`.
+///
+/// `DEFAULT_MS` is the timeout [`Default`] returns, in milliseconds, so a
+/// `#[serde(default)]` field gets it when the setting is left out. A
+/// default of zero means off. Switched off timeouts are written as
+/// `"off"`.
+///
+/// # Example
+///
+/// ```
+/// use core::time::Duration;
+/// use serde::{Serialize, Deserialize};
+/// use humantime_serde::Timeout;
+///
+/// const DEFAULT_30S: u64 = 30_000;
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct Client {
+///     #[serde(default)]
+///     connect: Timeout<DEFAULT_30S>,
+///     #[serde(default)]
+///     read: Timeout,
+/// }
+///
+/// let client: Client = serde_json::from_str(r#"{"read": "5s"}"#).unwrap();
+/// assert_eq!(client.connect.get(), Some(Duration::from_secs(30)));
+/// assert_eq!(client.read.get(), Some(Duration::from_secs(5)));
+///
+/// let client: Client = serde_json::from_str(r#"{"connect": "off"}"#).unwrap();
+/// assert_eq!(client.connect.get(), None);
+/// assert_eq!(serde_json::to_string(&client).unwrap(), r#"{"connect":"off","read":"off"}"#);
+/// ```
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Timeout<const DEFAULT_MS: u64 = 0>(Option<Duration>);
+
+impl<const DEFAULT_MS: u64> Timeout<DEFAULT_MS> {
+    /// A timeout that is switched off.
+    pub const OFF: Timeout<DEFAULT_MS> = Timeout(None);
+
+    /// Creates a timeout, switched off if `d` is `None`.
+    pub const fn new(d: Option<Duration>) -> Timeout<DEFAULT_MS> {
+        Timeout(d)
+    }
+
+    /// The timeout, or `None` if it is switched off.
+    pub const fn get(&self) -> Option<Duration> {
+        self.0
+    }
+
+    /// Whether the timeout is switched off.
+    pub const fn is_off(&self) -> bool {
+        self.0.is_none()
+    }
+}
+
+impl<const DEFAULT_MS: u64> Default for Timeout<DEFAULT_MS> {
+    fn default() -> Timeout<DEFAULT_MS> {
+        match DEFAULT_MS {
+            0 => Timeout(None),
+            ms => Timeout(Some(Duration::from_millis(ms))),
+        }
+    }
+}
+
+impl<const DEFAULT_MS: u64> HumantimeFormat for Timeout<DEFAULT_MS> {
+    const EXPECTING: &'static str = "a duration or \"off\"";
+
+    type Err = humantime::DurationError;
+
+    fn parse(s: &str) -> Result<Timeout<DEFAULT_MS>, humantime::DurationError> {
+        let s = s.trim();
+        if s.eq_ignore_ascii_case("off") || s.eq_ignore_ascii_case("none") {
+            return Ok(Timeout(None));
+        }
+        Duration::parse(s).map(|d| Timeout(Some(d)))
+    }
+
+    fn format(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.0 {
+            Some(d) => d.format(f),
+            None => f.write_str("off"),
+        }
+    }
+
+    fn is_overflow(err: &humantime::DurationError) -> bool {
+        Duration::is_overflow(err)
+    }
+
+    fn suggestion(s: &str, err: &humantime::DurationError) -> Option<&'static str> {
+        Duration::suggestion(s, err)
+    }
+}
+
+impl<const DEFAULT_MS: u64> fmt::Display for Timeout<DEFAULT_MS> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.format(f)
+    }
+}
+
+impl<const DEFAULT_MS: u64> FromStr for Timeout<DEFAULT_MS> {
+    type Err = humantime::DurationError;
+
+    fn from_str(s: &str) -> Result<Timeout<DEFAULT_MS>, humantime::DurationError> {
+        Timeout::parse(s)
+    }
+}
+
+impl<const DEFAULT_MS: u64> From<Duration> for Timeout<DEFAULT_MS> {
+    fn from(d: Duration) -> Timeout<DEFAULT_MS> {
+        Timeout(Some(d))
+    }
+}
+
+impl<const DEFAULT_MS: u64> From<Option<Duration>> for Timeout<DEFAULT_MS> {
+    fn from(d: Option<Duration>) -> Timeout<DEFAULT_MS> {
+        Timeout(d)
+    }
+}
+
+impl<const DEFAULT_MS: u64> From<Timeout<DEFAULT_MS>> for Option<Duration> {
+    fn from(t: Timeout<DEFAULT_MS>) -> Option<Duration> {
+        t.0
+    }
+}
+
+impl<const DEFAULT_MS: u64> Serialize for Timeout<DEFAULT_MS> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        Ref(self).serialize(serializer)
+    }
+}
+
+impl<'de, const DEFAULT_MS: u64> Deserialize<'de> for Timeout<DEFAULT_MS> {
+    fn deserialize<D>(d: D) -> Result<Timeout<DEFAULT_MS>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Serde::deserialize(d).map(Serde::into_inner)
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod test {
+    use super::*;
+    use alloc::string::ToString;
+
+    #[test]
+    fn parse() {
+        assert_eq!("off".parse(), Ok(Timeout::<0>::OFF));
+        assert_eq!(" None ".parse(), Ok(Timeout::<0>::OFF));
+        assert_eq!("90s".parse::<Timeout>().unwrap().get(), Some(Duration::from_secs(90)));
+        assert_eq!("0s".parse::<Timeout>().unwrap().get(), Some(Duration::ZERO));
+        assert!("never".parse::<Timeout>().is_err());
+        assert_eq!(Timeout::<0>::from(Duration::from_secs(90)).to_string(), "1m 30s");
+        assert_eq!(Timeout::<0>::OFF.to_string(), "off");
+    }
+
+    #[test]
+    fn default() {
+        assert!(Timeout::<0>::default().is_off());
+        assert_eq!(Timeout::<1500>::default().get(), Some(Duration::from_millis(1500)));
+        assert_eq!(Option::from(Timeout::<1500>::default()), Some(Duration::from_millis(1500)));
+    }
+
+    #[test]
+    fn serde() {
+        let t: Timeout<30_000> = serde_json::from_str("\"none\"").unwrap();
+        assert_eq!(serde_json::to_string(&t).unwrap(), "\"off\"");
+        let t: Timeout = serde_json::from_str("\"2m\"").unwrap();
+        assert_eq!(serde_json::to_string(&t).unwrap(), "\"2m\"");
+        let err = serde_json::from_str::<Timeout>("\"2 secnds\"").unwrap_err();
+        assert!(err.to_string().contains("expected a duration or \"off\""));
+        assert!(err.to_string().contains("did you mean \"seconds\"?"));
+    }
+}
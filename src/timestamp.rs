@@ -0,0 +1,198 @@
+use core::cmp::Ordering;
+use core::fmt;
+use core::ops::{Deref, DerefMut};
+use core::str::FromStr;
+#[cfg(feature = "std")]
+use std::time::SystemTime;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use super::format::HumantimeFormat;
+use super::Serde;
+
+/// A `DateTime<Utc>` which serializes to and from an RFC 3339 string on its
+/// own, without any `#[serde(with)]` annotation.
+///
+/// # Example
+///
+/// ```
+/// use serde::{Serialize, Deserialize};
+/// use chrono::{DateTime, Utc};
+/// use humantime_serde::HumanTimestamp;
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct Foo {
+///     created: HumanTimestamp,
+///     #[serde(default)]
+///     deleted: Option<HumanTimestamp>,
+/// }
+///
+/// let created: HumanTimestamp = "2018-05-11T20:28:30+02:00".parse().unwrap();
+/// assert_eq!(created.to_string(), "2018-05-11T18:28:30Z");
+/// assert!(created > DateTime::<Utc>::UNIX_EPOCH);
+/// ```
+#[derive(Copy, Clone, Default, Eq, Hash, PartialEq, Ord, PartialOrd)]
+pub struct HumanTimestamp(DateTime<Utc>);
+
+impl HumanTimestamp {
+    /// Wraps a `DateTime<Utc>`.
+    pub const fn new(t: DateTime<Utc>) -> HumanTimestamp {
+        HumanTimestamp(t)
+    }
+
+    /// Consumes the `HumanTimestamp`, returning the inner `DateTime<Utc>`.
+    pub fn into_inner(self) -> DateTime<Utc> {
+        self.0
+    }
+}
+
+impl fmt::Debug for HumanTimestamp {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        self.0.fmt(formatter)
+    }
+}
+
+impl fmt::Display for HumanTimestamp {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        HumantimeFormat::format(&self.0, formatter)
+    }
+}
+
+impl FromStr for HumanTimestamp {
+    type Err = chrono::ParseError;
+
+    fn from_str(s: &str) -> Result<HumanTimestamp, chrono::ParseError> {
+        DateTime::<Utc>::parse(s).map(HumanTimestamp)
+    }
+}
+
+impl Deref for HumanTimestamp {
+    type Target = DateTime<Utc>;
+
+    fn deref(&self) -> &DateTime<Utc> {
+        &self.0
+    }
+}
+
+impl DerefMut for HumanTimestamp {
+    fn deref_mut(&mut self) -> &mut DateTime<Utc> {
+        &mut self.0
+    }
+}
+
+impl From<DateTime<Utc>> for HumanTimestamp {
+    fn from(t: DateTime<Utc>) -> HumanTimestamp {
+        HumanTimestamp(t)
+    }
+}
+
+impl From<HumanTimestamp> for DateTime<Utc> {
+    fn from(t: HumanTimestamp) -> DateTime<Utc> {
+        t.0
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<SystemTime> for HumanTimestamp {
+    fn from(t: SystemTime) -> HumanTimestamp {
+        HumanTimestamp(t.into())
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<HumanTimestamp> for SystemTime {
+    fn from(t: HumanTimestamp) -> SystemTime {
+        t.0.into()
+    }
+}
+
+impl PartialEq<DateTime<Utc>> for HumanTimestamp {
+    fn eq(&self, other: &DateTime<Utc>) -> bool {
+        self.0 == *other
+    }
+}
+
+impl PartialEq<HumanTimestamp> for DateTime<Utc> {
+    fn eq(&self, other: &HumanTimestamp) -> bool {
+        *self == other.0
+    }
+}
+
+impl PartialOrd<DateTime<Utc>> for HumanTimestamp {
+    fn partial_cmp(&self, other: &DateTime<Utc>) -> Option<Ordering> {
+        self.0.partial_cmp(other)
+    }
+}
+
+impl PartialOrd<HumanTimestamp> for DateTime<Utc> {
+    fn partial_cmp(&self, other: &HumanTimestamp) -> Option<Ordering> {
+        self.partial_cmp(&other.0)
+    }
+}
+
+impl Serialize for HumanTimestamp {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        Serde(&self.0).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for HumanTimestamp {
+    fn deserialize<D>(d: D) -> Result<HumanTimestamp, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Serde::<DateTime<Utc>>::deserialize(d).map(|t| HumanTimestamp(t.into_inner()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloc::string::ToString;
+    use core::time::Duration;
+
+    #[test]
+    fn serde() {
+        #[derive(Serialize, Deserialize)]
+        struct Foo {
+            time: HumanTimestamp,
+            #[serde(default)]
+            deleted: Option<HumanTimestamp>,
+        }
+
+        let json = r#"{"time": "2018-05-11T18:28:30Z"}"#;
+        let foo = serde_json::from_str::<Foo>(json).unwrap();
+        assert_eq!(foo.time, DateTime::UNIX_EPOCH + Duration::new(1526063310, 0));
+        assert_eq!(foo.deleted, None);
+        let reverse = serde_json::to_string(&foo).unwrap();
+        assert_eq!(reverse, r#"{"time":"2018-05-11T18:28:30Z","deleted":null}"#);
+    }
+
+    #[test]
+    fn parse_display() {
+        let t: HumanTimestamp = "2018-05-11T20:28:30+02:00".parse().unwrap();
+        assert_eq!(t.to_string(), "2018-05-11T18:28:30Z");
+        assert!("2018-05-11".parse::<HumanTimestamp>().is_err());
+    }
+
+    #[test]
+    fn compare() {
+        let t = HumanTimestamp::from(DateTime::UNIX_EPOCH + Duration::from_secs(1));
+        assert!(t > DateTime::<Utc>::UNIX_EPOCH);
+        assert!(DateTime::<Utc>::UNIX_EPOCH < t);
+        assert_eq!(HumanTimestamp::default(), DateTime::<Utc>::UNIX_EPOCH);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn system_time() {
+        let t = SystemTime::UNIX_EPOCH + Duration::from_secs(1526063310);
+        let human = HumanTimestamp::from(t);
+        assert_eq!(human.to_string(), "2018-05-11T18:28:30Z");
+        assert_eq!(SystemTime::from(human), t);
+    }
+}
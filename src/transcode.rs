@@ -0,0 +1,279 @@
+//! Rewrites durations and timestamps inside a `serde_json::Value`, for
+//! data that can't be described with annotated structs.
+//!
+//! A [`Transcoder`] holds rules pairing a path with a [`Conversion`]. Paths
+//! are object keys separated by dots, where `*` matches every key of an
+//! object or every element of an array. Paths that are absent from the
+//! value are skipped.
+//!
+//! Requires the `json` feature.
+//!
+//! # Example
+//!
+//! ```
+//! use serde_json::json;
+//! use humantime_serde::transcode::{Conversion, Transcoder};
+//!
+//! let mut config = json!({
+//!     "jobs": [
+//!         { "timeout": 90, "started": "2018-05-11T18:28:30Z" },
+//!         { "timeout": 5 },
+//!     ],
+//! });
+//! Transcoder::new()
+//!     .rule("jobs.*.timeout", Conversion::SecsToDuration)
+//!     .rule("jobs.*.started", Conversion::TimestampToUnix)
+//!     .apply(&mut config)
+//!     .unwrap();
+//! assert_eq!(config, json!({
+//!     "jobs": [
+//!         { "timeout": "1m 30s", "started": 1526063310 },
+//!         { "timeout": "5s" },
+//!     ],
+//! }));
+//! ```
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+use core::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+
+use super::format::HumantimeFormat;
+
+/// A rewrite applied to the values at a path.
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub enum Conversion {
+    /// A humantime string to a number of seconds, fractional if needed.
+    DurationToSecs,
+    /// A humantime string to a number of milliseconds, fractional if needed.
+    DurationToMillis,
+    /// A number of seconds to a humantime string.
+    SecsToDuration,
+    /// A number of milliseconds to a humantime string.
+    MillisToDuration,
+    /// A humantime string to its canonical form, e.g. `"90s"` to `"1m 30s"`.
+    NormalizeDuration,
+    /// An RFC 3339 string to seconds since the Unix epoch.
+    TimestampToUnix,
+    /// Seconds since the Unix epoch to an RFC 3339 string.
+    UnixToTimestamp,
+    /// An RFC 3339 string to its canonical UTC form.
+    NormalizeTimestamp,
+}
+
+/// The error returned when a value can't be converted.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TranscodeError {
+    path: String,
+    message: String,
+}
+
+impl TranscodeError {
+    /// The path of the offending value, with indices in place of `*`.
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+}
+
+impl fmt::Display for TranscodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {}", self.path, self.message)
+    }
+}
+
+impl core::error::Error for TranscodeError {}
+
+/// A set of conversions applied to a `serde_json::Value`.
+#[derive(Clone, Debug, Default)]
+pub struct Transcoder {
+    rules: Vec<(String, Conversion)>,
+}
+
+impl Transcoder {
+    /// Creates a transcoder without any rule.
+    pub fn new() -> Transcoder {
+        Transcoder::default()
+    }
+
+    /// Adds a rule converting the values at `path`.
+    pub fn rule(mut self, path: &str, conversion: Conversion) -> Transcoder {
+        self.rules.push((path.to_string(), conversion));
+        self
+    }
+
+    /// Applies the rules in order, stopping at the first value that can't be
+    /// converted.
+    ///
+    /// The rules are applied to a copy of `value`, which replaces it once
+    /// every rule succeeded, so `value` is left untouched on error.
+    pub fn apply(&self, value: &mut Value) -> Result<(), TranscodeError> {
+        let mut converted = value.clone();
+        for (path, conversion) in &self.rules {
+            let segments: Vec<&str> = path.split('.').collect();
+            walk(&mut converted, &segments, String::new(), *conversion)?;
+        }
+        *value = converted;
+        Ok(())
+    }
+}
+
+fn walk(
+    value: &mut Value,
+    segments: &[&str],
+    path: String,
+    conversion: Conversion,
+) -> Result<(), TranscodeError> {
+    let (segment, rest) = match segments.split_first() {
+        Some(split) => split,
+        None => {
+            return convert(value, conversion).map_err(|message| TranscodeError { path, message })
+        }
+    };
+    let join = |key: &str| {
+        if path.is_empty() {
+            key.to_string()
+        } else {
+            alloc::format!("{}.{}", path, key)
+        }
+    };
+    match value {
+        Value::Object(map) if *segment == "*" => {
+            for (key, v) in map.iter_mut() {
+                walk(v, rest, join(key), conversion)?;
+            }
+        }
+        Value::Object(map) => {
+            if let Some(v) = map.get_mut(*segment) {
+                walk(v, rest, join(segment), conversion)?;
+            }
+        }
+        Value::Array(items) if *segment == "*" => {
+            for (i, v) in items.iter_mut().enumerate() {
+                walk(v, rest, join(&i.to_string()), conversion)?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+fn convert(value: &mut Value, conversion: Conversion) -> Result<(), String> {
+    let converted = match conversion {
+        Conversion::DurationToSecs => number(parse_duration(value)?.as_secs_f64()),
+        Conversion::DurationToMillis => number(parse_duration(value)?.as_secs_f64() * 1e3),
+        Conversion::SecsToDuration => duration(as_f64(value)?)?,
+        Conversion::MillisToDuration => duration(as_f64(value)? / 1e3)?,
+        Conversion::NormalizeDuration => {
+            Value::String(crate::format(&parse_duration(value)?))
+        }
+        Conversion::TimestampToUnix => Value::from(parse_timestamp(value)?.timestamp()),
+        Conversion::UnixToTimestamp => {
+            let secs = value.as_i64().ok_or("expected a whole number of seconds")?;
+            let t = DateTime::from_timestamp(secs, 0).ok_or("timestamp out of range")?;
+            Value::String(crate::format(&t))
+        }
+        Conversion::NormalizeTimestamp => Value::String(crate::format(&parse_timestamp(value)?)),
+    };
+    *value = converted;
+    Ok(())
+}
+
+fn as_str(value: &Value) -> Result<&str, String> {
+    value.as_str().ok_or_else(|| "expected a string".to_string())
+}
+
+fn as_f64(value: &Value) -> Result<f64, String> {
+    value.as_f64().ok_or_else(|| "expected a number".to_string())
+}
+
+fn parse_duration(value: &Value) -> Result<Duration, String> {
+    Duration::parse(as_str(value)?).map_err(|e| e.to_string())
+}
+
+fn parse_timestamp(value: &Value) -> Result<DateTime<Utc>, String> {
+    DateTime::<Utc>::parse(as_str(value)?).map_err(|e| e.to_string())
+}
+
+/// A whole number when possible, so `90.0` is written `90`.
+fn number(n: f64) -> Value {
+    if n >= 0.0 && n < u64::MAX as f64 && (n as u64) as f64 == n {
+        Value::from(n as u64)
+    } else {
+        Value::from(n)
+    }
+}
+
+fn duration(secs: f64) -> Result<Value, String> {
+    let d = Duration::try_from_secs_f64(secs).map_err(|e| e.to_string())?;
+    Ok(Value::String(crate::format(&d)))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn conversions() {
+        let mut v = json!({
+            "a": "1m 30s",
+            "b": "1500ms",
+            "c": 1500,
+            "d": "90s",
+            "e": "2018-05-11T20:28:30+02:00",
+            "f": 1526063310,
+        });
+        Transcoder::new()
+            .rule("a", Conversion::DurationToSecs)
+            .rule("b", Conversion::DurationToSecs)
+            .rule("c", Conversion::MillisToDuration)
+            .rule("d", Conversion::NormalizeDuration)
+            .rule("e", Conversion::NormalizeTimestamp)
+            .rule("f", Conversion::UnixToTimestamp)
+            .rule("missing.*", Conversion::UnixToTimestamp)
+            .apply(&mut v)
+            .unwrap();
+        assert_eq!(
+            v,
+            json!({
+                "a": 90,
+                "b": 1.5,
+                "c": "1s 500ms",
+                "d": "1m 30s",
+                "e": "2018-05-11T18:28:30Z",
+                "f": "2018-05-11T18:28:30Z",
+            })
+        );
+    }
+
+    #[test]
+    fn wildcard_and_errors() {
+        let mut v = json!({ "jobs": { "a": { "every": "5m" }, "b": { "every": "soon" } } });
+        let err = Transcoder::new()
+            .rule("jobs.*.every", Conversion::DurationToMillis)
+            .apply(&mut v)
+            .unwrap_err();
+        assert_eq!(err.path(), "jobs.b.every");
+        assert!(err.to_string().starts_with("jobs.b.every: "));
+
+        let mut v = json!([{ "t": 1 }, { "t": "x" }]);
+        let err = Transcoder::new()
+            .rule("*.t", Conversion::SecsToDuration)
+            .apply(&mut v)
+            .unwrap_err();
+        assert_eq!(err.to_string(), "1.t: expected a number");
+        assert_eq!(v, json!([{ "t": 1 }, { "t": "x" }]));
+
+        let mut v = json!({ "a": 90, "b": "soon" });
+        let err = Transcoder::new()
+            .rule("a", Conversion::SecsToDuration)
+            .rule("b", Conversion::NormalizeDuration)
+            .apply(&mut v)
+            .unwrap_err();
+        assert_eq!(err.path(), "b");
+        assert_eq!(v, json!({ "a": 90, "b": "soon" }));
+    }
+}
@@ -0,0 +1,148 @@
+//! `valuable` support, enabled with the `valuable` feature.
+//!
+//! Each wrapper is exposed as a tuple struct whose single field is the string
+//! the serializer would emit.
+
+use alloc::string::ToString;
+use core::time::Duration;
+
+#[cfg(feature = "chrono")]
+use chrono::{DateTime, FixedOffset, Utc};
+use valuable::{Fields, StructDef, Structable, Valuable, Value, Visit};
+
+use super::format::Ref;
+use super::{HumanDuration, Serde};
+#[cfg(feature = "chrono")]
+use super::HumanTimestamp;
+
+fn visit_str(visit: &mut dyn Visit, s: &str) {
+    visit.visit_unnamed_fields(&[Value::String(s)]);
+}
+
+impl Valuable for Serde<Duration> {
+    fn as_value(&self) -> Value<'_> {
+        Value::Structable(self)
+    }
+
+    fn visit(&self, visit: &mut dyn Visit) {
+        visit_str(visit, &Ref(&self.0).to_string());
+    }
+}
+
+impl Structable for Serde<Duration> {
+    fn definition(&self) -> StructDef<'_> {
+        StructDef::new_static("Duration", Fields::Unnamed(1))
+    }
+}
+
+impl Valuable for HumanDuration {
+    fn as_value(&self) -> Value<'_> {
+        Value::Structable(self)
+    }
+
+    fn visit(&self, visit: &mut dyn Visit) {
+        visit_str(visit, &self.to_string());
+    }
+}
+
+impl Structable for HumanDuration {
+    fn definition(&self) -> StructDef<'_> {
+        StructDef::new_static("HumanDuration", Fields::Unnamed(1))
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl Valuable for Serde<DateTime<Utc>> {
+    fn as_value(&self) -> Value<'_> {
+        Value::Structable(self)
+    }
+
+    fn visit(&self, visit: &mut dyn Visit) {
+        visit_str(visit, &Ref(&self.0).to_string());
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl Structable for Serde<DateTime<Utc>> {
+    fn definition(&self) -> StructDef<'_> {
+        StructDef::new_static("DateTime", Fields::Unnamed(1))
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl Valuable for Serde<DateTime<FixedOffset>> {
+    fn as_value(&self) -> Value<'_> {
+        Value::Structable(self)
+    }
+
+    fn visit(&self, visit: &mut dyn Visit) {
+        visit_str(visit, &Ref(&self.0).to_string());
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl Structable for Serde<DateTime<FixedOffset>> {
+    fn definition(&self) -> StructDef<'_> {
+        StructDef::new_static("DateTime", Fields::Unnamed(1))
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl Valuable for HumanTimestamp {
+    fn as_value(&self) -> Value<'_> {
+        Value::Structable(self)
+    }
+
+    fn visit(&self, visit: &mut dyn Visit) {
+        visit_str(visit, &self.to_string());
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl Structable for HumanTimestamp {
+    fn definition(&self) -> StructDef<'_> {
+        StructDef::new_static("HumanTimestamp", Fields::Unnamed(1))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloc::string::String;
+
+    /// Keeps the string a value visits.
+    struct Capture(String);
+
+    impl Visit for Capture {
+        fn visit_value(&mut self, _: Value<'_>) {}
+
+        fn visit_unnamed_fields(&mut self, values: &[Value<'_>]) {
+            if let [Value::String(s)] = values {
+                self.0 = s.to_string();
+            }
+        }
+    }
+
+    fn visited(v: &dyn Valuable) -> String {
+        let mut capture = Capture(String::new());
+        v.visit(&mut capture);
+        capture.0
+    }
+
+    #[test]
+    fn duration() {
+        let d = Duration::from_millis(90_500);
+        assert_eq!(visited(&Serde::from(d)), crate::format(&d));
+        assert_eq!(visited(&HumanDuration::from(d)), crate::format(&d));
+    }
+
+    #[test]
+    #[cfg(feature = "chrono")]
+    fn time() {
+        let t = DateTime::<Utc>::UNIX_EPOCH + Duration::new(1526063310, 0);
+        assert_eq!(visited(&Serde::from(t)), crate::format(&t));
+        assert_eq!(visited(&HumanTimestamp::from(t)), crate::format(&t));
+        let t = t.fixed_offset();
+        assert_eq!(visited(&Serde::from(t)), crate::format(&t));
+    }
+}
@@ -0,0 +1,171 @@
+//! Convenience module to allow serialization via `humantime_serde` for `Vec`
+//!
+//! # Example
+//!
+//! ```
+//! use serde::{Serialize, Deserialize};
+//! use core::time::Duration;
+//!
+//! use chrono::{DateTime, Utc};
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Foo {
+//!     #[serde(with = "humantime_serde::vec")]
+//!     retries: Vec<Duration>,
+//!     #[serde(with = "humantime_serde::vec")]
+//!     times: Vec<DateTime<Utc>>,
+//! }
+//! ```
+
+use alloc::vec::Vec;
+use core::fmt;
+
+use serde::de::{SeqAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serializer};
+
+use super::format::{HumantimeFormat, Ref};
+use super::Serde;
+
+/// Serializes a `Vec<Duration>` or `Vec<chrono::DateTime>`
+///
+/// This function can be used with `serde_derive`'s `with` and
+/// `serialize_with` annotations. It accepts any slice.
+pub fn serialize<T, S>(d: &[T], s: S) -> Result<S::Ok, S::Error>
+where
+    T: HumantimeFormat,
+    S: Serializer,
+{
+    s.collect_seq(d.iter().map(Ref))
+}
+
+/// Deserializes a `Vec<Duration>` or `Vec<chrono::DateTime>`
+///
+/// This function can be used with `serde_derive`'s `with` and
+/// `deserialize_with` annotations.
+pub fn deserialize<'a, T, D>(d: D) -> Result<Vec<T>, D::Error>
+where
+    T: HumantimeFormat,
+    D: Deserializer<'a>,
+{
+    let got: Vec<Serde<T>> = Deserialize::deserialize(d)?;
+    Ok(got.into_iter().map(Serde::into_inner).collect())
+}
+
+/// Deserializes a `Vec<Duration>` or `Vec<chrono::DateTime>` into `place`,
+/// reusing its allocation.
+///
+/// On error, `place` holds the elements read before it.
+pub fn deserialize_in_place<'a, T, D>(d: D, place: &mut Vec<T>) -> Result<(), D::Error>
+where
+    T: HumantimeFormat,
+    D: Deserializer<'a>,
+{
+    d.deserialize_seq(Refill {
+        place,
+        map: Serde::<T>::into_inner,
+    })
+}
+
+/// Clears a `Vec` and refills it with the elements of a sequence, each read
+/// as `U` and mapped to `T`.
+pub(crate) struct Refill<'p, U, T> {
+    pub(crate) place: &'p mut Vec<T>,
+    pub(crate) map: fn(U) -> T,
+}
+
+impl<'de, U, T> Visitor<'de> for Refill<'_, U, T>
+where
+    U: Deserialize<'de>,
+{
+    type Value = ();
+
+    fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.write_str("a sequence")
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<(), A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        self.place.clear();
+        // Don't trust the hint with more than a page worth of elements.
+        self.place.reserve(seq.size_hint().unwrap_or(0).min(4096));
+        while let Some(v) = seq.next_element::<U>()? {
+            self.place.push((self.map)(v));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    #[cfg(feature = "chrono")]
+    use chrono::{DateTime, Utc};
+    use core::time::Duration;
+    use serde::Serialize;
+
+    #[test]
+    fn with() {
+        #[derive(Serialize, Deserialize)]
+        struct Foo {
+            #[serde(with = "super")]
+            retries: Vec<Duration>,
+        }
+
+        let json = r#"{"retries": ["1s", "1 minute"]}"#;
+        let foo = serde_json::from_str::<Foo>(json).unwrap();
+        assert_eq!(foo.retries, [Duration::from_secs(1), Duration::from_secs(60)]);
+        let reverse = serde_json::to_string(&foo).unwrap();
+        assert_eq!(reverse, r#"{"retries":["1s","1m"]}"#);
+
+        assert!(serde_json::from_str::<Foo>(r#"{"retries": ["1s", 2]}"#).is_err());
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn with_times() {
+        #[derive(Serialize, Deserialize)]
+        struct Foo {
+            #[serde(with = "super")]
+            times: Vec<DateTime<Utc>>,
+        }
+
+        let json = r#"{"times": ["2018-05-11T18:28:30Z"]}"#;
+        let foo = serde_json::from_str::<Foo>(json).unwrap();
+        assert_eq!(foo.times, [DateTime::UNIX_EPOCH + Duration::new(1526063310, 0)]);
+        let reverse = serde_json::to_string(&foo).unwrap();
+        assert_eq!(reverse, r#"{"times":["2018-05-11T18:28:30Z"]}"#);
+    }
+
+    #[test]
+    fn in_place() {
+        let mut buf = Vec::with_capacity(8);
+        let mut de = serde_json::Deserializer::from_str(r#"["1s", "2s", "3s"]"#);
+        deserialize_in_place::<Duration, _>(&mut de, &mut buf).unwrap();
+        assert_eq!(buf, [Duration::from_secs(1), Duration::from_secs(2), Duration::from_secs(3)]);
+        let ptr = buf.as_ptr();
+
+        let mut de = serde_json::Deserializer::from_str(r#"["1m"]"#);
+        deserialize_in_place::<Duration, _>(&mut de, &mut buf).unwrap();
+        assert_eq!(buf, [Duration::from_secs(60)]);
+        assert_eq!(buf.as_ptr(), ptr);
+
+        let mut de = serde_json::Deserializer::from_str(r#"["1h", "soon"]"#);
+        assert!(deserialize_in_place::<Duration, _>(&mut de, &mut buf).is_err());
+        assert_eq!(buf, [Duration::from_secs(3600)]);
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn serde_in_place() {
+        let mut buf: Vec<Serde<DateTime<Utc>>> = Vec::with_capacity(4);
+        let mut de = serde_json::Deserializer::from_str(r#"["2018-05-11T18:28:30Z"]"#);
+        Deserialize::deserialize_in_place(&mut de, &mut buf).unwrap();
+        let ptr = buf.as_ptr();
+        let mut de = serde_json::Deserializer::from_str(r#"["1970-01-01T00:00:00Z"]"#);
+        Deserialize::deserialize_in_place(&mut de, &mut buf).unwrap();
+        assert_eq!(*buf[0], DateTime::<Utc>::UNIX_EPOCH);
+        assert_eq!(buf.as_ptr(), ptr);
+    }
+}
@@ -0,0 +1,107 @@
+//! Convenience module to allow serialization via `humantime_serde` for
+//! `Vec<Option>`
+//!
+//! `None` entries are written as `null`.
+//!
+//! # Example
+//!
+//! ```
+//! use serde::{Serialize, Deserialize};
+//! use core::time::Duration;
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Foo {
+//!     #[serde(with = "humantime_serde::vec_option")]
+//!     overrides: Vec<Option<Duration>>,
+//! }
+//! ```
+
+use alloc::vec::Vec;
+
+use serde::{Deserialize, Deserializer, Serializer};
+
+use super::format::{HumantimeFormat, Ref};
+use super::vec::Refill;
+use super::Serde;
+
+/// Serializes a `Vec<Option<Duration>>` or `Vec<Option<chrono::DateTime>>`
+///
+/// This function can be used with `serde_derive`'s `with` and
+/// `serialize_with` annotations. It accepts any slice.
+pub fn serialize<T, S>(d: &[Option<T>], s: S) -> Result<S::Ok, S::Error>
+where
+    T: HumantimeFormat,
+    S: Serializer,
+{
+    s.collect_seq(d.iter().map(Ref))
+}
+
+/// Deserializes a `Vec<Option<Duration>>` or
+/// `Vec<Option<chrono::DateTime>>`
+///
+/// This function can be used with `serde_derive`'s `with` and
+/// `deserialize_with` annotations.
+pub fn deserialize<'a, T, D>(d: D) -> Result<Vec<Option<T>>, D::Error>
+where
+    T: HumantimeFormat,
+    D: Deserializer<'a>,
+{
+    let got: Vec<Serde<Option<T>>> = Deserialize::deserialize(d)?;
+    Ok(got.into_iter().map(Serde::into_inner).collect())
+}
+
+/// Deserializes a `Vec<Option<Duration>>` or
+/// `Vec<Option<chrono::DateTime>>` into `place`, reusing its allocation.
+///
+/// On error, `place` holds the elements read before it.
+pub fn deserialize_in_place<'a, T, D>(d: D, place: &mut Vec<Option<T>>) -> Result<(), D::Error>
+where
+    T: HumantimeFormat,
+    D: Deserializer<'a>,
+{
+    d.deserialize_seq(Refill {
+        place,
+        map: Serde::<Option<T>>::into_inner,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    #[cfg(feature = "chrono")]
+    use chrono::{DateTime, Utc};
+    use core::time::Duration;
+    use serde::Serialize;
+
+    #[test]
+    fn with() {
+        #[derive(Serialize, Deserialize)]
+        struct Foo {
+            #[serde(with = "super")]
+            retries: Vec<Option<Duration>>,
+        }
+
+        let json = r#"{"retries": [null, "1 minute"]}"#;
+        let foo = serde_json::from_str::<Foo>(json).unwrap();
+        assert_eq!(foo.retries, [None, Some(Duration::from_secs(60))]);
+        let reverse = serde_json::to_string(&foo).unwrap();
+        assert_eq!(reverse, r#"{"retries":[null,"1m"]}"#);
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn with_times() {
+        #[derive(Serialize, Deserialize)]
+        struct Foo {
+            #[serde(with = "super")]
+            times: Vec<Option<DateTime<Utc>>>,
+        }
+
+        let json = r#"{"times": [null, "2018-05-11T18:28:30Z"]}"#;
+        let foo = serde_json::from_str::<Foo>(json).unwrap();
+        assert_eq!(foo.times[0], None);
+        assert_eq!(foo.times[1], Some(DateTime::from_timestamp(1526063310, 0).unwrap()));
+        let reverse = serde_json::to_string(&foo).unwrap();
+        assert_eq!(reverse, r#"{"times":[null,"2018-05-11T18:28:30Z"]}"#);
+    }
+}
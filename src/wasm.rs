@@ -0,0 +1,156 @@
+//! Interop with JavaScript through `wasm-bindgen`.
+//!
+//! Requires the `wasm` feature.
+//!
+//! `HumanTimestamp` and `Serde<DateTime<Utc>>` convert to and from
+//! `js_sys::Date`, and
+//! [`to_value`]/[`from_value`] go through `serde_wasm_bindgen` using the same
+//! strings as the rest of the crate.
+//!
+//! A `Date` only holds whole milliseconds, so converting a timestamp to one
+//! drops anything finer: the round-trip gives back the timestamp truncated
+//! to the millisecond, earlier in time for timestamps before 1970 too. The
+//! strings of [`to_value`] keep full precision.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use core::convert::TryFrom;
+//! use core::time::Duration;
+//! use chrono::{DateTime, Utc};
+//! use humantime_serde::{wasm, Serde};
+//!
+//! let time = Serde::from(DateTime::<Utc>::UNIX_EPOCH);
+//! let date = js_sys::Date::from(time);
+//! assert_eq!(Serde::<DateTime<Utc>>::try_from(date).unwrap(), time);
+//!
+//! let value = wasm::to_value(&Duration::from_secs(90)).unwrap();
+//! assert_eq!(value.as_string().unwrap(), "1m 30s");
+//! let dur: Duration = wasm::from_value(value).unwrap();
+//! ```
+
+use core::convert::TryFrom;
+use core::fmt;
+
+use chrono::{DateTime, Utc};
+use js_sys::Date;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use wasm_bindgen::JsValue;
+
+use super::format::Ref;
+use super::{HumanTimestamp, Serde};
+
+/// The error returned when a `js_sys::Date` does not hold a representable
+/// point in time.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct InvalidDate;
+
+impl fmt::Display for InvalidDate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("invalid date")
+    }
+}
+
+impl core::error::Error for InvalidDate {}
+
+/// The milliseconds since the epoch of `t`, truncated.
+fn to_millis(t: DateTime<Utc>) -> f64 {
+    t.timestamp_millis() as f64
+}
+
+/// The timestamp `millis` milliseconds after the epoch.
+fn from_millis(millis: f64) -> Result<DateTime<Utc>, InvalidDate> {
+    if !millis.is_finite() {
+        return Err(InvalidDate);
+    }
+    DateTime::from_timestamp_millis(millis as i64).ok_or(InvalidDate)
+}
+
+/// Truncates to the millisecond, the precision of a `Date`.
+impl From<Serde<DateTime<Utc>>> for Date {
+    fn from(time: Serde<DateTime<Utc>>) -> Date {
+        Date::new(&JsValue::from_f64(to_millis(time.0)))
+    }
+}
+
+impl TryFrom<&Date> for Serde<DateTime<Utc>> {
+    type Error = InvalidDate;
+
+    fn try_from(date: &Date) -> Result<Serde<DateTime<Utc>>, InvalidDate> {
+        from_millis(date.get_time()).map(Serde)
+    }
+}
+
+impl TryFrom<Date> for Serde<DateTime<Utc>> {
+    type Error = InvalidDate;
+
+    fn try_from(date: Date) -> Result<Serde<DateTime<Utc>>, InvalidDate> {
+        Serde::try_from(&date)
+    }
+}
+
+/// Truncates to the millisecond, the precision of a `Date`.
+impl From<HumanTimestamp> for Date {
+    fn from(time: HumanTimestamp) -> Date {
+        Date::from(Serde::from(time.into_inner()))
+    }
+}
+
+impl TryFrom<&Date> for HumanTimestamp {
+    type Error = InvalidDate;
+
+    fn try_from(date: &Date) -> Result<HumanTimestamp, InvalidDate> {
+        Serde::<DateTime<Utc>>::try_from(date).map(|t| HumanTimestamp::from(t.into_inner()))
+    }
+}
+
+impl TryFrom<Date> for HumanTimestamp {
+    type Error = InvalidDate;
+
+    fn try_from(date: Date) -> Result<HumanTimestamp, InvalidDate> {
+        HumanTimestamp::try_from(&date)
+    }
+}
+
+/// Serializes a `Duration` or `DateTime<Tz>` into a `JsValue` string.
+pub fn to_value<T>(value: &T) -> Result<JsValue, serde_wasm_bindgen::Error>
+where
+    T: ?Sized,
+    for<'a> Ref<'a, T>: Serialize,
+{
+    serde_wasm_bindgen::to_value(&Ref(value))
+}
+
+/// Deserializes a `Duration` or `DateTime<Tz>` from a `JsValue` string.
+pub fn from_value<T>(value: JsValue) -> Result<T, serde_wasm_bindgen::Error>
+where
+    Serde<T>: DeserializeOwned,
+{
+    serde_wasm_bindgen::from_value(value).map(Serde::into_inner)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use core::time::Duration;
+
+    #[test]
+    fn millis() {
+        let t = DateTime::<Utc>::UNIX_EPOCH + Duration::from_millis(1526063310250);
+        assert_eq!(to_millis(t), 1526063310250.0);
+        assert_eq!(from_millis(to_millis(t)), Ok(t));
+        assert_eq!(from_millis(f64::NAN), Err(InvalidDate));
+        assert_eq!(from_millis(f64::INFINITY), Err(InvalidDate));
+    }
+
+    #[test]
+    fn truncation() {
+        let t = DateTime::<Utc>::UNIX_EPOCH + Duration::new(1526063310, 250_999_999);
+        let truncated = DateTime::<Utc>::UNIX_EPOCH + Duration::from_millis(1526063310250);
+        assert_eq!(from_millis(to_millis(t)), Ok(truncated));
+
+        let t = DateTime::<Utc>::UNIX_EPOCH - Duration::from_micros(1500);
+        assert_eq!(to_millis(t), -2.0);
+    }
+}
@@ -0,0 +1,196 @@
+use core::fmt;
+use core::str::FromStr;
+
+use chrono::{DateTime, NaiveTime, TimeZone, Timelike};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use super::format::{HumantimeFormat, Ref};
+use super::Serde;
+
+/// A window of time repeating every day, written `"09:00-17:00"`.
+///
+/// The end is excluded. A window whose end is before its start crosses
+/// midnight, so `"22:00-06:00"` covers the night, and one whose end equals
+/// its start covers the whole day. Times are `HH:MM` or `HH:MM:SS`.
+///
+/// `TimeWindow` implements `Serialize` and `Deserialize`, and composes with
+/// `Serde` and the `option` module like durations do.
+///
+/// # Example
+///
+/// ```
+/// use chrono::{TimeZone, Utc};
+/// use humantime_serde::TimeWindow;
+///
+/// let night: TimeWindow = "22:00-06:00".parse().unwrap();
+/// assert!(night.contains(&Utc.with_ymd_and_hms(2020, 1, 1, 23, 30, 0).unwrap()));
+/// assert!(!night.contains(&Utc.with_ymd_and_hms(2020, 1, 1, 12, 0, 0).unwrap()));
+/// assert_eq!(night.to_string(), "22:00-06:00");
+/// ```
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub struct TimeWindow {
+    start: NaiveTime,
+    end: NaiveTime,
+}
+
+impl TimeWindow {
+    /// Creates a window from `start` included to `end` excluded.
+    pub const fn new(start: NaiveTime, end: NaiveTime) -> TimeWindow {
+        TimeWindow { start, end }
+    }
+
+    /// The start of the window, included.
+    pub fn start(&self) -> NaiveTime {
+        self.start
+    }
+
+    /// The end of the window, excluded.
+    pub fn end(&self) -> NaiveTime {
+        self.end
+    }
+
+    /// Returns whether the window crosses midnight.
+    pub fn crosses_midnight(&self) -> bool {
+        self.end < self.start
+    }
+
+    /// Returns whether `time` is within the window.
+    pub fn contains_time(&self, time: NaiveTime) -> bool {
+        if self.start < self.end {
+            self.start <= time && time < self.end
+        } else {
+            self.start <= time || time < self.end || self.start == self.end
+        }
+    }
+
+    /// Returns whether `t` is within the window, in `t`'s time zone.
+    pub fn contains<Tz>(&self, t: &DateTime<Tz>) -> bool
+    where
+        Tz: TimeZone,
+    {
+        self.contains_time(t.time())
+    }
+}
+
+/// The error returned when parsing a [`TimeWindow`] fails.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct InvalidTimeWindow;
+
+impl fmt::Display for InvalidTimeWindow {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("invalid time window, expected e.g. 09:00-17:00")
+    }
+}
+
+impl core::error::Error for InvalidTimeWindow {}
+
+fn parse_time(s: &str) -> Result<NaiveTime, InvalidTimeWindow> {
+    let s = s.trim();
+    NaiveTime::parse_from_str(s, "%H:%M")
+        .or_else(|_| NaiveTime::parse_from_str(s, "%H:%M:%S"))
+        .map_err(|_| InvalidTimeWindow)
+}
+
+pub(crate) fn write_time(t: NaiveTime, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "{:02}:{:02}", t.hour(), t.minute())?;
+    if t.second() != 0 {
+        write!(f, ":{:02}", t.second())?;
+    }
+    Ok(())
+}
+
+impl HumantimeFormat for TimeWindow {
+    const EXPECTING: &'static str = "a time window";
+
+    type Err = InvalidTimeWindow;
+
+    fn parse(s: &str) -> Result<TimeWindow, InvalidTimeWindow> {
+        let (start, end) = s.split_once('-').ok_or(InvalidTimeWindow)?;
+        Ok(TimeWindow::new(parse_time(start)?, parse_time(end)?))
+    }
+
+    fn format(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write_time(self.start, f)?;
+        f.write_str("-")?;
+        write_time(self.end, f)
+    }
+}
+
+impl fmt::Display for TimeWindow {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.format(f)
+    }
+}
+
+impl FromStr for TimeWindow {
+    type Err = InvalidTimeWindow;
+
+    fn from_str(s: &str) -> Result<TimeWindow, InvalidTimeWindow> {
+        TimeWindow::parse(s)
+    }
+}
+
+impl Serialize for TimeWindow {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        Ref(self).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for TimeWindow {
+    fn deserialize<D>(d: D) -> Result<TimeWindow, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Serde::deserialize(d).map(Serde::into_inner)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloc::string::ToString;
+    use alloc::vec::Vec;
+
+    fn time(h: u32, m: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(h, m, 0).unwrap()
+    }
+
+    #[test]
+    fn contains() {
+        let day: TimeWindow = "09:00-17:00".parse().unwrap();
+        assert!(day.contains_time(time(9, 0)));
+        assert!(!day.contains_time(time(17, 0)));
+        assert!(!day.crosses_midnight());
+
+        let night: TimeWindow = "22:00-06:00".parse().unwrap();
+        assert!(night.crosses_midnight());
+        assert!(night.contains_time(time(0, 0)));
+        assert!(!night.contains_time(time(6, 0)));
+
+        let always: TimeWindow = "00:00-00:00".parse().unwrap();
+        assert!(always.contains_time(time(12, 0)));
+    }
+
+    #[test]
+    fn serde() {
+        #[derive(Serialize, Deserialize)]
+        struct Foo {
+            window: TimeWindow,
+            #[serde(with = "crate::option", default)]
+            maintenance: Option<TimeWindow>,
+            windows: Vec<TimeWindow>,
+        }
+
+        let json = r#"{"window":"09:00-17:00","maintenance":"23:30:15-01:00","windows":[]}"#;
+        let foo: Foo = serde_json::from_str(json).unwrap();
+        assert_eq!(foo.maintenance.unwrap().start().second(), 15);
+        assert_eq!(serde_json::to_string(&foo).unwrap(), json);
+
+        let err = serde_json::from_str::<TimeWindow>("\"9am-5pm\"").unwrap_err();
+        assert!(err.to_string().contains("expected a time window"));
+        assert_eq!(InvalidTimeWindow.to_string(), "invalid time window, expected e.g. 09:00-17:00");
+    }
+}
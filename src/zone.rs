@@ -0,0 +1,328 @@
+//! Timestamps converted into a configured time zone on deserialization.
+//!
+//! Input with any offset is accepted and converted, so an application
+//! storing wall time in, say, `Europe/Berlin` doesn't follow every
+//! deserialization with `with_timezone`. Timestamps are written with the
+//! offset they have in that zone.
+//!
+//! With the `chrono-tz` feature, `in_zone!` generates a module for a
+//! `chrono_tz` zone. Other zones are used through [`deserialize_into`].
+//!
+//! Legacy systems often write local wall time without any offset. The
+//! module generated by [`attach_zone!`] reads such timestamps as wall time
+//! in a given zone and yields a `DateTime<Utc>`, choosing between the two
+//! instants of an ambiguous local time according to an [`Ambiguity`]
+//! policy. It writes them back as wall time without an offset.
+//!
+//! # Example
+//!
+//! ```
+//! # #[cfg(feature = "chrono-tz")] {
+//! use chrono::DateTime;
+//! use chrono_tz::Tz;
+//! use serde::{Serialize, Deserialize};
+//!
+//! humantime_serde::in_zone!(pub mod berlin: chrono_tz::Europe::Berlin);
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Meeting {
+//!     #[serde(with = "berlin")]
+//!     starts_at: DateTime<Tz>,
+//! }
+//!
+//! let json = r#"{"starts_at": "2018-05-11T18:28:30Z"}"#;
+//! let meeting: Meeting = serde_json::from_str(json).unwrap();
+//! assert_eq!(meeting.starts_at.timezone(), chrono_tz::Europe::Berlin);
+//! let json = serde_json::to_string(&meeting).unwrap();
+//! assert_eq!(json, r#"{"starts_at":"2018-05-11T20:28:30+02:00"}"#);
+//! # }
+//! ```
+//!
+//! ```
+//! use chrono::{DateTime, Utc};
+//! use serde::{Serialize, Deserialize};
+//!
+//! humantime_serde::attach_zone!(pub mod legacy: chrono::FixedOffset::east_opt(3600).unwrap(), Reject);
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Row {
+//!     #[serde(with = "legacy")]
+//!     at: DateTime<Utc>,
+//! }
+//!
+//! let row: Row = serde_json::from_str(r#"{"at": "2018-05-11 19:28:30"}"#).unwrap();
+//! assert_eq!(row.at.timestamp(), 1526063310);
+//! let json = serde_json::to_string(&row).unwrap();
+//! assert_eq!(json, r#"{"at":"2018-05-11T19:28:30"}"#);
+//! ```
+//!
+//! [`attach_zone!`]: crate::attach_zone
+
+use core::fmt;
+
+use chrono::{DateTime, Datelike, FixedOffset, NaiveDateTime, TimeZone, Timelike, Utc};
+use serde::{de, Deserialize, Deserializer, Serializer};
+
+use super::format::HumantimeFormat;
+use super::{Ambiguity, Error, Serde};
+
+/// Serializes a `DateTime<Tz>` with its offset.
+pub fn serialize<Tz, S>(t: &DateTime<Tz>, s: S) -> Result<S::Ok, S::Error>
+where
+    Tz: TimeZone,
+    S: Serializer,
+{
+    crate::serialize(&t.fixed_offset(), s)
+}
+
+/// Deserializes a timestamp with any offset and converts it into `tz`.
+pub fn deserialize_into<'a, Tz, D>(tz: &Tz, d: D) -> Result<DateTime<Tz>, D::Error>
+where
+    Tz: TimeZone,
+    D: Deserializer<'a>,
+{
+    let t = Serde::<DateTime<FixedOffset>>::deserialize(d)?.into_inner();
+    Ok(t.with_timezone(tz))
+}
+
+/// Serializes a `DateTime<Utc>` as wall time in `tz`, without an offset.
+pub fn serialize_naive<Tz, S>(tz: &Tz, t: &DateTime<Utc>, s: S) -> Result<S::Ok, S::Error>
+where
+    Tz: TimeZone,
+    S: Serializer,
+{
+    s.collect_str(&Naive(t.with_timezone(tz).naive_local()))
+}
+
+/// Deserializes wall time in `tz` to a `DateTime<Utc>`.
+///
+/// Timestamps with an offset are accepted as well, and keep their offset.
+pub fn deserialize_attached<'a, Tz, D>(
+    tz: &Tz,
+    ambiguity: Ambiguity,
+    d: D,
+) -> Result<DateTime<Utc>, D::Error>
+where
+    Tz: TimeZone,
+    D: Deserializer<'a>,
+{
+    struct V<'t, Tz>(&'t Tz, Ambiguity);
+
+    impl<Tz> de::Visitor<'_> for V<'_, Tz>
+    where
+        Tz: TimeZone,
+    {
+        type Value = DateTime<Utc>;
+
+        fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+            fmt.write_str("a timestamp")
+        }
+
+        fn visit_str<E>(self, v: &str) -> Result<DateTime<Utc>, E>
+        where
+            E: de::Error,
+        {
+            crate::check_len(v, crate::DEFAULT_MAX_LEN, "a timestamp")?;
+            attach(self.0, self.1, v).map_err(|err| {
+                E::invalid_value(de::Unexpected::Str(v), &crate::Because(&self, &err))
+            })
+        }
+    }
+
+    d.deserialize_str(V(tz, ambiguity))
+}
+
+fn attach<Tz>(tz: &Tz, ambiguity: Ambiguity, s: &str) -> Result<DateTime<Utc>, Error>
+where
+    Tz: TimeZone,
+{
+    if let Ok(t) = DateTime::<FixedOffset>::parse(s) {
+        return Ok(t.to_utc());
+    }
+    let naive = s.parse::<NaiveDateTime>()?;
+    Ok(ambiguity.resolve(tz, &naive)?.to_utc())
+}
+
+/// `YYYY-MM-DDTHH:MM:SS`, truncating fractional seconds as the default
+/// format does.
+struct Naive(NaiveDateTime);
+
+impl fmt::Display for Naive {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let t = &self.0;
+        write!(
+            f,
+            "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
+            t.year(),
+            t.month(),
+            t.day(),
+            t.hour(),
+            t.minute(),
+            t.second(),
+        )
+    }
+}
+
+/// Generates a module for `#[serde(with = "...")]` reading and writing
+/// `DateTime<Utc>` as wall time in a zone, without an offset.
+///
+/// The zone is any expression evaluating to a `chrono::TimeZone`, and the
+/// optional second argument an [`Ambiguity`] variant, `Earliest` by
+/// default. The generated module has an `option` submodule for `Option`
+/// fields.
+///
+/// See the [`zone`](mod@crate::zone) module for details.
+#[macro_export]
+macro_rules! attach_zone {
+    ($(#[$attr:meta])* $vis:vis mod $name:ident : $zone:expr) => {
+        $crate::attach_zone!($(#[$attr])* $vis mod $name: $zone, Earliest);
+    };
+    ($(#[$attr:meta])* $vis:vis mod $name:ident : $zone:expr, $ambiguity:ident) => {
+        $(#[$attr])*
+        $vis mod $name {
+            // Resolves the zone from where the macro is invoked.
+            #[allow(unused_imports)]
+            use super::*;
+
+            /// Serializes a `DateTime<Utc>` as wall time in the zone.
+            pub fn serialize<S>(
+                t: &$crate::re::chrono::DateTime<$crate::re::chrono::Utc>,
+                s: S,
+            ) -> ::core::result::Result<S::Ok, S::Error>
+            where
+                S: $crate::re::serde::Serializer,
+            {
+                $crate::zone::serialize_naive(&$zone, t, s)
+            }
+
+            /// Deserializes wall time in the zone.
+            pub fn deserialize<'a, D>(
+                d: D,
+            ) -> ::core::result::Result<
+                $crate::re::chrono::DateTime<$crate::re::chrono::Utc>,
+                D::Error,
+            >
+            where
+                D: $crate::re::serde::Deserializer<'a>,
+            {
+                $crate::zone::deserialize_attached(&$zone, $crate::Ambiguity::$ambiguity, d)
+            }
+
+            $crate::__option_module!(
+                [] $crate::re::chrono::DateTime<$crate::re::chrono::Utc>,
+                []
+            );
+        }
+    };
+}
+
+/// Generates a module for `#[serde(with = "...")]` converting timestamps
+/// into a `chrono_tz` zone.
+///
+/// The generated module has an `option` submodule for `Option` fields.
+///
+/// See the [`zone`](mod@crate::zone) module for details.
+#[cfg(feature = "chrono-tz")]
+#[macro_export]
+macro_rules! in_zone {
+    ($(#[$attr:meta])* $vis:vis mod $name:ident : $zone:expr) => {
+        $(#[$attr])*
+        $vis mod $name {
+            // Resolves the zone from where the macro is invoked.
+            #[allow(unused_imports)]
+            use super::*;
+
+            /// Serializes a `DateTime<Tz>` with its offset.
+            pub fn serialize<S>(
+                t: &$crate::re::chrono::DateTime<$crate::re::chrono_tz::Tz>,
+                s: S,
+            ) -> ::core::result::Result<S::Ok, S::Error>
+            where
+                S: $crate::re::serde::Serializer,
+            {
+                $crate::zone::serialize(t, s)
+            }
+
+            /// Deserializes a timestamp, converting it into the zone.
+            pub fn deserialize<'a, D>(
+                d: D,
+            ) -> ::core::result::Result<
+                $crate::re::chrono::DateTime<$crate::re::chrono_tz::Tz>,
+                D::Error,
+            >
+            where
+                D: $crate::re::serde::Deserializer<'a>,
+            {
+                $crate::zone::deserialize_into(&$zone, d)
+            }
+
+            $crate::__option_module!(
+                [] $crate::re::chrono::DateTime<$crate::re::chrono_tz::Tz>,
+                []
+            );
+        }
+    };
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn fixed() {
+        let tz = FixedOffset::west_opt(5 * 3600).unwrap();
+        let d = serde_json::Value::from("2018-05-11T18:28:30+02:00");
+        let t = deserialize_into(&tz, d).unwrap();
+        assert_eq!(t.offset(), &tz);
+        assert_eq!(t.timestamp(), 1526056110);
+        let json = serialize(&t, serde_json::value::Serializer).unwrap();
+        assert_eq!(json, "2018-05-11T11:28:30-05:00");
+    }
+
+    crate::attach_zone!(mod plus_one: FixedOffset::east_opt(3600).unwrap());
+
+    #[test]
+    fn attached() {
+        #[derive(serde::Serialize, Deserialize)]
+        struct Foo {
+            #[serde(with = "plus_one")]
+            naive: DateTime<Utc>,
+            #[serde(with = "plus_one::option")]
+            offset: Option<DateTime<Utc>>,
+        }
+
+        let json = r#"{"naive": "2018-05-11T19:28:30", "offset": "2018-05-11T18:28:30Z"}"#;
+        let foo: Foo = serde_json::from_str(json).unwrap();
+        assert_eq!(foo.naive.timestamp(), 1526063310);
+        assert_eq!(foo.offset, Some(foo.naive));
+        assert_eq!(
+            serde_json::to_string(&foo).unwrap(),
+            r#"{"naive":"2018-05-11T19:28:30","offset":"2018-05-11T19:28:30"}"#
+        );
+
+        let json = r#"{"naive": "2018-05-11", "offset": null}"#;
+        assert!(serde_json::from_str::<Foo>(json).is_err());
+    }
+
+    #[cfg(feature = "chrono-tz")]
+    crate::in_zone!(mod berlin: chrono_tz::Europe::Berlin);
+
+    #[test]
+    #[cfg(feature = "chrono-tz")]
+    fn chrono_tz() {
+        #[derive(serde::Serialize, Deserialize)]
+        struct Foo {
+            #[serde(with = "berlin")]
+            at: DateTime<chrono_tz::Tz>,
+            #[serde(with = "berlin::option")]
+            maybe: Option<DateTime<chrono_tz::Tz>>,
+        }
+
+        let json = r#"{"at": "2018-01-11T18:28:30Z", "maybe": "2018-07-11T18:28:30Z"}"#;
+        let foo: Foo = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            serde_json::to_string(&foo).unwrap(),
+            r#"{"at":"2018-01-11T19:28:30+01:00","maybe":"2018-07-11T20:28:30+02:00"}"#
+        );
+    }
+}